@@ -12,6 +12,18 @@ pub use atmo_api as api;
 #[doc(inline)]
 pub use atmo_core as core;
 
+#[cfg(feature = "firehose")]
+#[doc(inline)]
+pub use atmo_firehose as firehose;
+
+#[cfg(feature = "identity")]
+#[doc(inline)]
+pub use atmo_identity as identity;
+
 #[cfg(feature = "jetstream")]
 #[doc(inline)]
 pub use atmo_jetstream as jetstream;
+
+#[cfg(feature = "server")]
+#[doc(inline)]
+pub use atmo_server as server;
@@ -0,0 +1,482 @@
+//! Session management.
+//!
+//! An [`Agent`] logs in to a PDS with `com.atproto.server.createSession`, and attaches the
+//! session's access token to each request. When the access token expires, the agent refreshes the
+//! session with `com.atproto.server.refreshSession` and retries the request.
+//!
+//! The server used to log in may be an entryway in front of many PDSes. Once logged in, requests
+//! are sent to the account's own PDS, as named in the DID document returned with the session.
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+use atmo_core::{
+    did::{DidDoc, DidUrl},
+    xrpc::{self, Request},
+    Did, Handle, Unknown,
+};
+use bytes::Bytes;
+use serde::{de::IntoDeserializer, Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    com::atproto::server::{
+        create_session, refresh_session, CreateSession, DeleteSession, RefreshSession,
+    },
+    retry::RetryPolicy,
+    RequestBuilder, Response, ResponseError, XrpcClient,
+};
+
+/// An authenticated session.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// The DID of the account.
+    pub did: Did,
+    /// The handle of the account.
+    pub handle: Handle,
+    /// The token attached to requests.
+    pub access_jwt: String,
+    /// The token used to refresh the session.
+    pub refresh_jwt: String,
+    /// The URL of the account's PDS, if known.
+    ///
+    /// This is read from the DID document returned with the session. If the server doesn't return
+    /// one, it may be set from a resolved DID document instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pds: Option<Url>,
+}
+
+impl From<create_session::Output> for Session {
+    #[inline]
+    fn from(output: create_session::Output) -> Self {
+        Session {
+            pds: pds_url(output.did_doc.as_ref()),
+            did: output.did,
+            handle: output.handle,
+            access_jwt: output.access_jwt,
+            refresh_jwt: output.refresh_jwt,
+        }
+    }
+}
+
+impl From<refresh_session::Output> for Session {
+    #[inline]
+    fn from(output: refresh_session::Output) -> Self {
+        Session {
+            pds: pds_url(output.did_doc.as_ref()),
+            did: output.did,
+            handle: output.handle,
+            access_jwt: output.access_jwt,
+            refresh_jwt: output.refresh_jwt,
+        }
+    }
+}
+
+/// Returns the PDS URL from a session's DID document.
+fn pds_url(did_doc: Option<&Unknown>) -> Option<Url> {
+    let doc = DidDoc::deserialize(did_doc?.into_deserializer()).ok()?;
+    let mut url = doc.pds_service_url()?.clone();
+
+    // Request URLs are resolved relative to this one.
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+
+    Some(url)
+}
+
+/// A trait for types which store an [`Agent`]'s session.
+pub trait SessionStore {
+    /// Returns the current session, if any.
+    fn get(&self) -> Option<Session>;
+
+    /// Replaces the current session.
+    fn set(&self, session: Session);
+
+    /// Removes the current session.
+    fn clear(&self);
+}
+
+/// A [`SessionStore`] which keeps the session in memory.
+#[derive(Debug, Default)]
+pub struct MemorySessionStore {
+    session: Mutex<Option<Session>>,
+}
+
+impl SessionStore for MemorySessionStore {
+    fn get(&self) -> Option<Session> {
+        self.session
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set(&self, session: Session) {
+        *self.session.lock().unwrap_or_else(PoisonError::into_inner) = Some(session);
+    }
+
+    fn clear(&self) {
+        *self.session.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+}
+
+type SessionCallback = Box<dyn Fn(&Session) + Send + Sync>;
+
+/// A builder for an [`Agent`].
+pub struct AgentBuilder<S> {
+    service: Url,
+    client: Option<XrpcClient>,
+    store: S,
+    on_session: Option<SessionCallback>,
+}
+
+impl<S> AgentBuilder<S>
+where
+    S: SessionStore,
+{
+    /// Sets the client used to send requests.
+    #[inline]
+    pub fn client(mut self, client: XrpcClient) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the store used to hold the session.
+    ///
+    /// The default is a [`MemorySessionStore`].
+    #[inline]
+    pub fn store<T>(self, store: T) -> AgentBuilder<T>
+    where
+        T: SessionStore,
+    {
+        AgentBuilder {
+            service: self.service,
+            client: self.client,
+            store,
+            on_session: self.on_session,
+        }
+    }
+
+    /// Sets a callback which is called whenever the agent logs in or refreshes its session.
+    ///
+    /// This may be used to persist the session's tokens.
+    #[inline]
+    pub fn on_session<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&Session) + Send + Sync + 'static,
+    {
+        self.on_session = Some(Box::new(f));
+        self
+    }
+
+    /// Creates an `Agent` with the configured options.
+    pub fn build(self) -> Agent<S> {
+        Agent {
+            service: self.service,
+            client: self.client.unwrap_or_default(),
+            store: self.store,
+            on_session: self.on_session,
+            refresh_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+/// An XRPC client which manages an authenticated session.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn async_main() {
+/// use atmo_api::Agent;
+/// use atmo_api::app::bsky::actor::GetPreferences;
+/// use url::Url;
+///
+/// let agent = Agent::new(Url::parse("https://atproto.example.com").unwrap());
+///
+/// agent.login("username", "password").await.unwrap();
+///
+/// let prefs = agent.request(GetPreferences).send().await.unwrap();
+/// # }
+/// ```
+pub struct Agent<S = MemorySessionStore> {
+    service: Url,
+    client: XrpcClient,
+    store: S,
+    on_session: Option<SessionCallback>,
+    /// Held while refreshing the session, since refresh tokens are single-use.
+    refresh_lock: tokio::sync::Mutex<()>,
+}
+
+impl Agent {
+    /// Creates an `Agent` for the PDS at `service`, with default settings.
+    #[inline]
+    pub fn new(service: Url) -> Self {
+        Agent::builder(service).build()
+    }
+
+    /// Returns a builder for an `Agent` for the PDS at `service`.
+    #[inline]
+    pub fn builder(service: Url) -> AgentBuilder<MemorySessionStore> {
+        AgentBuilder {
+            service,
+            client: None,
+            store: MemorySessionStore::default(),
+            on_session: None,
+        }
+    }
+}
+
+impl<S> Agent<S>
+where
+    S: SessionStore,
+{
+    /// Returns the current session, if any.
+    #[inline]
+    pub fn session(&self) -> Option<Session> {
+        self.store.get()
+    }
+
+    /// Resumes a previously created session.
+    #[inline]
+    pub fn resume(&self, session: Session) {
+        self.store.set(session);
+    }
+
+    /// Creates a session with `com.atproto.server.createSession`.
+    pub async fn login(
+        &self,
+        identifier: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<Session, AgentError> {
+        let input = create_session::Input {
+            identifier: identifier.into(),
+            password: password.into(),
+            auth_factor_token: None,
+        };
+
+        let resp = self
+            .client
+            .request(&self.service, CreateSession)
+            .input(&input)
+            .expect("createSession input serialization should never fail")
+            .send()
+            .await?;
+
+        let session = Session::from(resp.result.map_err(AgentError::Login)?);
+        self.update(session.clone());
+
+        Ok(session)
+    }
+
+    /// Deletes the current session with `com.atproto.server.deleteSession`.
+    ///
+    /// The session is removed from the store even if the request fails.
+    pub async fn logout(&self) -> Result<(), AgentError> {
+        let session = self.store.get().ok_or(AgentError::NoSession)?;
+        self.store.clear();
+
+        let resp = self
+            .client
+            .request(self.base_url(Some(&session)), DeleteSession)
+            .bearer_auth(&session.refresh_jwt)
+            .send()
+            .await?;
+
+        resp.result.map_err(AgentError::Logout)
+    }
+
+    /// Refreshes the current session with `com.atproto.server.refreshSession`.
+    ///
+    /// This happens automatically when a request fails with an `ExpiredToken` error.
+    pub async fn refresh(&self) -> Result<Session, AgentError> {
+        let _guard = self.refresh_lock.lock().await;
+        self.refresh_locked().await
+    }
+
+    /// Refreshes the current session. The caller must hold `refresh_lock`.
+    async fn refresh_locked(&self) -> Result<Session, AgentError> {
+        let session = self.store.get().ok_or(AgentError::NoSession)?;
+
+        let resp = self
+            .client
+            .request(self.base_url(Some(&session)), RefreshSession)
+            .bearer_auth(&session.refresh_jwt)
+            .send()
+            .await?;
+
+        let mut refreshed = Session::from(resp.result.map_err(AgentError::Refresh)?);
+        refreshed.pds = refreshed.pds.or(session.pds);
+        self.update(refreshed.clone());
+
+        Ok(refreshed)
+    }
+
+    /// Creates a builder for an XRPC request authenticated with the current session.
+    ///
+    /// The request is sent to the account's PDS if it is known, and otherwise to the server used
+    /// to log in.
+    pub fn request<R>(&self, req: R) -> AgentRequestBuilder<'_, R, S>
+    where
+        R: Request,
+    {
+        let session = self.store.get();
+
+        AgentRequestBuilder {
+            agent: self,
+            inner: self.client.request(self.base_url(session.as_ref()), req),
+        }
+    }
+
+    fn base_url<'a>(&'a self, session: Option<&'a Session>) -> &'a Url {
+        session
+            .and_then(|s| s.pds.as_ref())
+            .unwrap_or(&self.service)
+    }
+
+    /// Refreshes the session after `expired` was rejected, unless it has been refreshed already.
+    async fn refresh_expired(&self, expired: &Session) -> Result<Session, AgentError> {
+        // Another request may have refreshed the session while this one waited for the lock.
+        let _guard = self.refresh_lock.lock().await;
+        match self.store.get() {
+            Some(current) if current.access_jwt != expired.access_jwt => Ok(current),
+            _ => self.refresh_locked().await,
+        }
+    }
+
+    fn update(&self, session: Session) {
+        if let Some(f) = &self.on_session {
+            f(&session);
+        }
+
+        self.store.set(session);
+    }
+}
+
+/// A builder for an XRPC request sent by an [`Agent`].
+pub struct AgentRequestBuilder<'a, R, S> {
+    agent: &'a Agent<S>,
+    inner: RequestBuilder<R>,
+}
+
+impl<R, S> AgentRequestBuilder<'_, R, S>
+where
+    R: Request,
+    S: SessionStore,
+{
+    /// Sets the query parameters of the request.
+    pub fn params(mut self, params: &R::Params) -> Result<Self, serde_urlencoded_xrpc::ser::Error> {
+        self.inner = self.inner.params(params)?;
+        Ok(self)
+    }
+
+    /// Sets the body of the request.
+    pub fn input(mut self, input: &R::Input) -> Result<Self, R::InputError> {
+        self.inner = self.inner.input(input)?;
+        Ok(self)
+    }
+
+    /// Sets the value of the `Content-Type` header for the request.
+    #[inline]
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.inner = self.inner.content_type(content_type);
+        self
+    }
+
+    /// Asks the PDS to forward this request to another service, such as an AppView.
+    ///
+    /// See [`RequestBuilder::proxy`].
+    #[inline]
+    pub fn proxy(mut self, service: &DidUrl) -> Self {
+        self.inner = self.inner.proxy(service);
+        self
+    }
+
+    /// Sets the policy for retrying this request if it fails.
+    ///
+    /// See [`RequestBuilder::retry_policy`].
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.inner = self.inner.retry_policy(policy);
+        self
+    }
+
+    /// Sends the XRPC request with the session's access token.
+    ///
+    /// If the server reports that the access token has expired, the session is refreshed and the
+    /// request is sent again.
+    pub async fn send(self) -> Result<Response<R>, AgentError> {
+        let session = self.agent.store.get().ok_or(AgentError::NoSession)?;
+        let retry = self.inner.clone();
+
+        let (parts, bytes) = self
+            .inner
+            .bearer_auth(&session.access_jwt)
+            .send_raw()
+            .await?;
+
+        if !is_expired_token(&parts, &bytes) {
+            return Ok(RequestBuilder::decode(parts, bytes)?);
+        }
+
+        let session = self.agent.refresh_expired(&session).await?;
+
+        let (parts, bytes) = retry.bearer_auth(&session.access_jwt).send_raw().await?;
+        Ok(RequestBuilder::decode(parts, bytes)?)
+    }
+}
+
+fn is_expired_token(parts: &http::response::Parts, bytes: &Bytes) -> bool {
+    parts.status.is_client_error()
+        && serde_json::from_slice::<xrpc::Error<String>>(bytes)
+            .is_ok_and(|e| e.error == xrpc::ErrorCode::ExpiredToken)
+}
+
+/// An error produced by an [`Agent`].
+#[derive(Debug)]
+pub enum AgentError {
+    /// `createSession` failed.
+    Login(xrpc::Error<<CreateSession as Request>::RpcError>),
+    /// `deleteSession` failed.
+    Logout(xrpc::Error<<DeleteSession as Request>::RpcError>),
+    /// The agent has no session.
+    NoSession,
+    /// `refreshSession` failed.
+    Refresh(xrpc::Error<<RefreshSession as Request>::RpcError>),
+    /// An error occurred while sending a request.
+    Response(ResponseError),
+}
+
+impl From<ResponseError> for AgentError {
+    #[inline]
+    fn from(e: ResponseError) -> Self {
+        AgentError::Response(e)
+    }
+}
+
+impl fmt::Display for AgentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AgentError::Login(e) => write!(f, "login failed: {e}"),
+            AgentError::Logout(e) => write!(f, "logout failed: {e}"),
+            AgentError::NoSession => f.write_str("not logged in"),
+            AgentError::Refresh(e) => write!(f, "session refresh failed: {e}"),
+            AgentError::Response(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl Error for AgentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AgentError::Response(e) => Some(e),
+            AgentError::Login(_)
+            | AgentError::Logout(_)
+            | AgentError::NoSession
+            | AgentError::Refresh(_) => None,
+        }
+    }
+}
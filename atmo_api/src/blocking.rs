@@ -0,0 +1,260 @@
+//! A blocking XRPC client.
+//!
+//! The [`XrpcClient`] and [`RequestBuilder`] in this module mirror their async counterparts in the
+//! crate root, but send requests on the current thread. Requests are encoded and responses are
+//! decoded exactly as by the async client, and both return the same [`Response`] and
+//! [`ResponseError`] types.
+//!
+//! By default, requests are sent with a [`reqwest::blocking::Client`], which must not be used
+//! within an async runtime.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use atmo_api::blocking::XrpcClient;
+//! use atmo_api::com::atproto::identity::{resolve_handle, ResolveHandle};
+//! use url::Url;
+//!
+//! let client = XrpcClient::new();
+//! let url = Url::parse("https://pds.example.com/").unwrap();
+//!
+//! let resp = client
+//!     .request(&url, ResolveHandle)
+//!     .params(&resolve_handle::Params {
+//!         handle: "alice.test".parse().unwrap(),
+//!     })
+//!     .unwrap()
+//!     .send()
+//!     .unwrap();
+//! ```
+
+use std::{error::Error as StdError, fmt, marker::PhantomData, sync::Arc};
+
+use atmo_core::{did::DidUrl, xrpc::Request};
+use bytes::Bytes;
+use http::{header, HeaderName};
+use url::Url;
+
+use crate::{retry::RetryPolicy, RequestState, Response, ResponseError};
+
+/// A trait for blocking HTTP clients which can send XRPC requests.
+///
+/// This is the blocking counterpart of [`HttpTransport`](crate::transport::HttpTransport).
+pub trait BlockingTransport {
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Sends `request`, returning the response with its full body.
+    ///
+    /// Error statuses are returned as responses, not as errors.
+    fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, Self::Error>;
+}
+
+impl BlockingTransport for reqwest::blocking::Client {
+    type Error = reqwest::Error;
+
+    fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, Self::Error> {
+        let request = reqwest::blocking::Request::try_from(request)?;
+
+        let resp = self.execute(request)?;
+        let mut builder = http::Response::builder()
+            .status(resp.status())
+            .version(resp.version());
+        if let Some(headers) = builder.headers_mut() {
+            *headers = resp.headers().clone();
+        }
+        let bytes = resp.bytes()?;
+
+        Ok(builder.body(bytes).expect("response parts should be valid"))
+    }
+}
+
+/// An object-safe version of [`BlockingTransport`].
+trait DynTransport: Send + Sync {
+    fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Bytes>, Box<dyn StdError + Send + Sync>>;
+}
+
+impl<T> DynTransport for T
+where
+    T: BlockingTransport + Send + Sync,
+{
+    fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Bytes>, Box<dyn StdError + Send + Sync>> {
+        BlockingTransport::send(self, request).map_err(|e| e.into())
+    }
+}
+
+/// A blocking version of [`crate::XrpcClient`].
+#[derive(Clone)]
+pub struct XrpcClient {
+    transport: Arc<dyn DynTransport>,
+    retry: RetryPolicy,
+}
+
+impl Default for XrpcClient {
+    #[inline]
+    fn default() -> Self {
+        XrpcClient::new()
+    }
+}
+
+impl XrpcClient {
+    /// Creates an `XrpcClient` with default settings.
+    ///
+    /// Requests are sent with a [`reqwest::blocking::Client`]. Failed requests are not retried.
+    /// See [`retry_policy`](Self::retry_policy).
+    pub fn new() -> Self {
+        XrpcClient::with_transport(reqwest::blocking::Client::new())
+    }
+
+    /// Creates an `XrpcClient` which sends requests with `transport`.
+    pub fn with_transport<T>(transport: T) -> Self
+    where
+        T: BlockingTransport + Send + Sync + 'static,
+    {
+        XrpcClient {
+            transport: Arc::new(transport),
+            retry: RetryPolicy::never(),
+        }
+    }
+
+    /// Sets the policy for retrying failed requests sent by this client.
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Creates a builder for an XRPC request.
+    ///
+    /// See [`crate::XrpcClient::request`].
+    pub fn request<R>(&self, base_url: &Url, req: R) -> RequestBuilder<R>
+    where
+        R: Request,
+    {
+        let _ = req;
+
+        RequestBuilder {
+            transport: Arc::clone(&self.transport),
+            state: RequestState::new::<R>(base_url, self.retry),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// A blocking version of [`crate::RequestBuilder`].
+pub struct RequestBuilder<R> {
+    transport: Arc<dyn DynTransport>,
+    state: RequestState,
+    marker: PhantomData<R>,
+}
+
+impl<R> Clone for RequestBuilder<R> {
+    fn clone(&self) -> Self {
+        RequestBuilder {
+            transport: Arc::clone(&self.transport),
+            state: self.state.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R> RequestBuilder<R>
+where
+    R: Request,
+{
+    pub fn params(mut self, params: &R::Params) -> Result<Self, serde_urlencoded_xrpc::ser::Error> {
+        self.state.params::<R>(params)?;
+        Ok(self)
+    }
+
+    /// Sets the body of the request.
+    ///
+    /// See [`crate::RequestBuilder::input`].
+    pub fn input(mut self, input: &R::Input) -> Result<Self, R::InputError> {
+        self.state.input::<R>(input)?;
+        Ok(self)
+    }
+
+    /// Sets the value of the `Content-Type` header for the request.
+    #[inline]
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.state.header(header::CONTENT_TYPE, content_type);
+        self
+    }
+
+    /// Sets the policy for retrying this request if it fails, overriding the client's policy.
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.state.retry = policy;
+        self
+    }
+
+    /// Asks the server to forward this request to another service.
+    ///
+    /// See [`crate::RequestBuilder::proxy`].
+    #[inline]
+    pub fn proxy(mut self, service: &DidUrl) -> Self {
+        self.state.header(
+            HeaderName::from_static("atproto-proxy"),
+            service.to_string(),
+        );
+        self
+    }
+
+    /// Applies XRPC admin authorization to this request.
+    ///
+    /// See [`crate::RequestBuilder::admin_auth`].
+    #[inline]
+    pub fn admin_auth<T>(mut self, token: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        self.state.admin_auth(token);
+        self
+    }
+
+    /// Applies HTTP `Bearer` authorization to this request.
+    ///
+    /// See [`crate::RequestBuilder::bearer_auth`].
+    #[inline]
+    pub fn bearer_auth<T>(mut self, token: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        self.state
+            .header(header::AUTHORIZATION, format!("Bearer {token}"));
+        self
+    }
+
+    /// Sends the XRPC request, blocking until the response is received.
+    ///
+    /// The request is retried according to its retry policy, sleeping the current thread between
+    /// attempts.
+    pub fn send(self) -> Result<Response<R>, ResponseError> {
+        let mut attempt = 1;
+        let (parts, bytes) = loop {
+            let result = self.send_once();
+            let Some(delay) = self.state.retry_delay::<R>(attempt, &result) else {
+                break result?;
+            };
+
+            std::thread::sleep(delay);
+            attempt += 1;
+        };
+
+        crate::RequestBuilder::<R>::decode(parts, bytes)
+    }
+
+    /// Sends the XRPC request once, returning the raw response.
+    fn send_once(&self) -> Result<(http::response::Parts, Bytes), ResponseError> {
+        let req = self.state.http_request::<R>()?;
+        let resp = self.transport.send(req).map_err(ResponseError::Http)?;
+
+        Ok(resp.into_parts())
+    }
+}
@@ -31,6 +31,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -82,6 +85,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -133,6 +139,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -184,6 +193,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -205,6 +217,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetSuggestions {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.actors
+                }
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Profile {
                 #[serde(default)]
@@ -237,6 +264,10 @@ pub mod app {
                 #[serde(skip_serializing_if = "std::option::Option::is_none")]
                 pub pinned_post: std::option::Option<crate::com::atproto::repo::StrongRef>,
             }
+            impl atmo_core::record::Record for Profile {
+                const NSID: &'static str = "app.bsky.actor.profile";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Literal("self");
+            }
             #[derive(Debug)]
             pub struct PutPreferences;
             impl atmo_core::xrpc::Request for PutPreferences {
@@ -269,6 +300,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -320,6 +354,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -341,6 +378,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchActors {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.actors
+                }
+            }
             #[derive(Debug)]
             pub struct SearchActorsTypeahead;
             impl atmo_core::xrpc::Request for SearchActorsTypeahead {
@@ -371,6 +423,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -1845,6 +1900,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -1892,6 +1950,10 @@ pub mod app {
                 #[serde(skip_serializing_if = "std::option::Option::is_none")]
                 pub labels: std::option::Option<crate::app::bsky::feed::generator::main::Labels>,
             }
+            impl atmo_core::record::Record for Generator {
+                const NSID: &'static str = "app.bsky.feed.generator";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Any;
+            }
             #[derive(Debug)]
             pub struct GetActorFeeds;
             impl atmo_core::xrpc::Request for GetActorFeeds {
@@ -1922,6 +1984,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -1943,6 +2008,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetActorFeeds {
+                type Item = crate::app::bsky::feed::defs::GeneratorView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feeds
+                }
+            }
             #[derive(Debug)]
             pub struct GetActorLikes;
             impl atmo_core::xrpc::Request for GetActorLikes {
@@ -1951,7 +2031,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::get_actor_likes::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::get_actor_likes::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -1973,6 +2053,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -1994,6 +2077,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetActorLikes {
+                type Item = crate::app::bsky::feed::defs::FeedViewPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feed
+                }
+            }
             #[derive(Debug)]
             pub struct GetAuthorFeed;
             impl atmo_core::xrpc::Request for GetAuthorFeed {
@@ -2002,7 +2100,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::get_author_feed::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::get_author_feed::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -2024,6 +2122,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2045,6 +2146,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetAuthorFeed {
+                type Item = crate::app::bsky::feed::defs::FeedViewPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feed
+                }
+            }
             #[derive(Debug)]
             pub struct GetFeed;
             impl atmo_core::xrpc::Request for GetFeed {
@@ -2053,7 +2169,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::get_feed::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::get_feed::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -2075,6 +2191,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2096,6 +2215,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetFeed {
+                type Item = crate::app::bsky::feed::defs::FeedViewPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feed
+                }
+            }
             #[derive(Debug)]
             pub struct GetFeedGenerator;
             impl atmo_core::xrpc::Request for GetFeedGenerator {
@@ -2126,6 +2260,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2177,6 +2314,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2206,7 +2346,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::get_feed_skeleton::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::get_feed_skeleton::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -2228,6 +2368,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2249,6 +2392,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetFeedSkeleton {
+                type Item = crate::app::bsky::feed::defs::SkeletonFeedPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feed
+                }
+            }
             #[derive(Debug)]
             pub struct GetLikes;
             impl atmo_core::xrpc::Request for GetLikes {
@@ -2279,6 +2437,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2300,6 +2461,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetLikes {
+                type Item = crate::app::bsky::feed::get_likes::Like;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.likes
+                }
+            }
             #[derive(Debug)]
             pub struct GetListFeed;
             impl atmo_core::xrpc::Request for GetListFeed {
@@ -2308,7 +2484,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::get_list_feed::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::get_list_feed::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -2330,6 +2506,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2351,6 +2530,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetListFeed {
+                type Item = crate::app::bsky::feed::defs::FeedViewPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feed
+                }
+            }
             #[derive(Debug)]
             pub struct GetPostThread;
             impl atmo_core::xrpc::Request for GetPostThread {
@@ -2359,7 +2553,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::get_post_thread::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::get_post_thread::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -2381,6 +2575,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2432,6 +2629,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2483,6 +2683,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2504,6 +2707,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetQuotes {
+                type Item = crate::app::bsky::feed::defs::PostView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.posts
+                }
+            }
             #[derive(Debug)]
             pub struct GetRepostedBy;
             impl atmo_core::xrpc::Request for GetRepostedBy {
@@ -2534,6 +2752,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2555,6 +2776,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetRepostedBy {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.reposted_by
+                }
+            }
             #[derive(Debug)]
             pub struct GetSuggestedFeeds;
             impl atmo_core::xrpc::Request for GetSuggestedFeeds {
@@ -2585,6 +2821,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2606,6 +2845,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetSuggestedFeeds {
+                type Item = crate::app::bsky::feed::defs::GeneratorView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feeds
+                }
+            }
             #[derive(Debug)]
             pub struct GetTimeline;
             impl atmo_core::xrpc::Request for GetTimeline {
@@ -2636,6 +2890,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2657,12 +2914,31 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetTimeline {
+                type Item = crate::app::bsky::feed::defs::FeedViewPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feed
+                }
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Like {
                 #[serde(rename = "createdAt")]
                 pub created_at: atmo_core::DateTime,
                 pub subject: crate::com::atproto::repo::StrongRef,
             }
+            impl atmo_core::record::Record for Like {
+                const NSID: &'static str = "app.bsky.feed.like";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Post {
                 #[serde(rename = "createdAt")]
@@ -2691,6 +2967,10 @@ pub mod app {
                 pub tags: std::option::Option<std::vec::Vec<std::string::String>>,
                 pub text: std::string::String,
             }
+            impl atmo_core::record::Record for Post {
+                const NSID: &'static str = "app.bsky.feed.post";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Postgate {
                 #[serde(rename = "createdAt")]
@@ -2707,12 +2987,20 @@ pub mod app {
                 >,
                 pub post: atmo_core::AtUri,
             }
+            impl atmo_core::record::Record for Postgate {
+                const NSID: &'static str = "app.bsky.feed.postgate";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Repost {
                 #[serde(rename = "createdAt")]
                 pub created_at: atmo_core::DateTime,
                 pub subject: crate::com::atproto::repo::StrongRef,
             }
+            impl atmo_core::record::Record for Repost {
+                const NSID: &'static str = "app.bsky.feed.repost";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Debug)]
             pub struct SearchPosts;
             impl atmo_core::xrpc::Request for SearchPosts {
@@ -2721,7 +3009,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::feed::search_posts::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::feed::search_posts::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -2743,6 +3031,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -2764,6 +3055,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchPosts {
+                type Item = crate::app::bsky::feed::defs::PostView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.posts
+                }
+            }
             #[derive(Debug)]
             pub struct SendInteractions;
             impl atmo_core::xrpc::Request for SendInteractions {
@@ -2796,6 +3102,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -2830,6 +3139,10 @@ pub mod app {
                 pub hidden_replies: std::option::Option<std::vec::Vec<atmo_core::AtUri>>,
                 pub post: atmo_core::AtUri,
             }
+            impl atmo_core::record::Record for Threadgate {
+                const NSID: &'static str = "app.bsky.feed.threadgate";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             pub mod defs {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct BlockedAuthor {
@@ -4858,12 +5171,20 @@ pub mod app {
                 pub created_at: atmo_core::DateTime,
                 pub subject: atmo_core::Did,
             }
+            impl atmo_core::record::Record for Block {
+                const NSID: &'static str = "app.bsky.graph.block";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Follow {
                 #[serde(rename = "createdAt")]
                 pub created_at: atmo_core::DateTime,
                 pub subject: atmo_core::Did,
             }
+            impl atmo_core::record::Record for Follow {
+                const NSID: &'static str = "app.bsky.graph.follow";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Debug)]
             pub struct GetActorStarterPacks;
             impl atmo_core::xrpc::Request for GetActorStarterPacks {
@@ -4894,6 +5215,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -4915,6 +5239,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetActorStarterPacks {
+                type Item = crate::app::bsky::graph::defs::StarterPackViewBasic;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.starter_packs
+                }
+            }
             #[derive(Debug)]
             pub struct GetBlocks;
             impl atmo_core::xrpc::Request for GetBlocks {
@@ -4945,6 +5284,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -4966,6 +5308,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetBlocks {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.blocks
+                }
+            }
             #[derive(Debug)]
             pub struct GetFollowers;
             impl atmo_core::xrpc::Request for GetFollowers {
@@ -4996,6 +5353,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5017,6 +5377,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetFollowers {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.followers
+                }
+            }
             #[derive(Debug)]
             pub struct GetFollows;
             impl atmo_core::xrpc::Request for GetFollows {
@@ -5047,6 +5422,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5068,6 +5446,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetFollows {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.follows
+                }
+            }
             #[derive(Debug)]
             pub struct GetKnownFollowers;
             impl atmo_core::xrpc::Request for GetKnownFollowers {
@@ -5098,6 +5491,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5119,6 +5515,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetKnownFollowers {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.followers
+                }
+            }
             #[derive(Debug)]
             pub struct GetList;
             impl atmo_core::xrpc::Request for GetList {
@@ -5149,6 +5560,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5170,6 +5584,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetList {
+                type Item = crate::app::bsky::graph::defs::ListItemView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.items
+                }
+            }
             #[derive(Debug)]
             pub struct GetListBlocks;
             impl atmo_core::xrpc::Request for GetListBlocks {
@@ -5200,6 +5629,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5221,6 +5653,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetListBlocks {
+                type Item = crate::app::bsky::graph::defs::ListView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.lists
+                }
+            }
             #[derive(Debug)]
             pub struct GetListMutes;
             impl atmo_core::xrpc::Request for GetListMutes {
@@ -5251,6 +5698,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5272,6 +5722,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetListMutes {
+                type Item = crate::app::bsky::graph::defs::ListView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.lists
+                }
+            }
             #[derive(Debug)]
             pub struct GetLists;
             impl atmo_core::xrpc::Request for GetLists {
@@ -5302,6 +5767,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5323,6 +5791,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetLists {
+                type Item = crate::app::bsky::graph::defs::ListView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.lists
+                }
+            }
             #[derive(Debug)]
             pub struct GetMutes;
             impl atmo_core::xrpc::Request for GetMutes {
@@ -5353,6 +5836,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5374,6 +5860,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetMutes {
+                type Item = crate::app::bsky::actor::defs::ProfileView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.mutes
+                }
+            }
             #[derive(Debug)]
             pub struct GetRelationships;
             impl atmo_core::xrpc::Request for GetRelationships {
@@ -5382,7 +5883,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::graph::get_relationships::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::graph::get_relationships::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -5404,7 +5905,10 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
-                fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
+                fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
                 }
@@ -5455,6 +5959,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5506,6 +6013,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5557,6 +6067,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5599,12 +6112,20 @@ pub mod app {
                 pub name: std::string::String,
                 pub purpose: crate::app::bsky::graph::defs::ListPurpose,
             }
+            impl atmo_core::record::Record for List {
+                const NSID: &'static str = "app.bsky.graph.list";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Listblock {
                 #[serde(rename = "createdAt")]
                 pub created_at: atmo_core::DateTime,
                 pub subject: atmo_core::AtUri,
             }
+            impl atmo_core::record::Record for Listblock {
+                const NSID: &'static str = "app.bsky.graph.listblock";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Listitem {
                 #[serde(rename = "createdAt")]
@@ -5612,6 +6133,10 @@ pub mod app {
                 pub list: atmo_core::AtUri,
                 pub subject: atmo_core::Did,
             }
+            impl atmo_core::record::Record for Listitem {
+                const NSID: &'static str = "app.bsky.graph.listitem";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Debug)]
             pub struct MuteActor;
             impl atmo_core::xrpc::Request for MuteActor {
@@ -5644,6 +6169,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -5697,6 +6225,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -5750,6 +6281,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -5801,6 +6335,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -5822,6 +6359,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchStarterPacks {
+                type Item = crate::app::bsky::graph::defs::StarterPackViewBasic;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.starter_packs
+                }
+            }
             #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
             pub struct Starterpack {
                 #[serde(rename = "createdAt")]
@@ -5842,6 +6394,10 @@ pub mod app {
                 pub list: atmo_core::AtUri,
                 pub name: std::string::String,
             }
+            impl atmo_core::record::Record for Starterpack {
+                const NSID: &'static str = "app.bsky.graph.starterpack";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Tid;
+            }
             #[derive(Debug)]
             pub struct UnmuteActor;
             impl atmo_core::xrpc::Request for UnmuteActor {
@@ -5874,6 +6430,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -5927,6 +6486,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -5980,6 +6542,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -6701,6 +7266,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -6731,6 +7299,10 @@ pub mod app {
                 pub labels: std::option::Option<crate::app::bsky::labeler::service::main::Labels>,
                 pub policies: crate::app::bsky::labeler::defs::LabelerPolicies,
             }
+            impl atmo_core::record::Record for Service {
+                const NSID: &'static str = "app.bsky.labeler.service";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Literal("self");
+            }
             pub mod defs {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct LabelerPolicies {
@@ -6967,6 +7539,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7018,6 +7593,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7039,6 +7617,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListNotifications {
+                type Item = crate::app::bsky::notification::list_notifications::Notification;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.notifications
+                }
+            }
             #[derive(Debug)]
             pub struct PutPreferences;
             impl atmo_core::xrpc::Request for PutPreferences {
@@ -7071,6 +7664,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -7124,6 +7720,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -7177,6 +7776,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -7523,6 +8125,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7574,6 +8179,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7595,6 +8203,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetPopularFeedGenerators {
+                type Item = crate::app::bsky::feed::defs::GeneratorView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.feeds
+                }
+            }
             #[derive(Debug)]
             pub struct GetSuggestionsSkeleton;
             impl atmo_core::xrpc::Request for GetSuggestionsSkeleton {
@@ -7625,6 +8248,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7646,6 +8272,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetSuggestionsSkeleton {
+                type Item = crate::app::bsky::unspecced::defs::SkeletonSearchActor;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.actors
+                }
+            }
             #[derive(Debug)]
             pub struct GetTaggedSuggestions;
             impl atmo_core::xrpc::Request for GetTaggedSuggestions {
@@ -7676,6 +8317,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7705,7 +8349,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::unspecced::search_actors_skeleton::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::unspecced::search_actors_skeleton::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -7727,6 +8371,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7748,6 +8395,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchActorsSkeleton {
+                type Item = crate::app::bsky::unspecced::defs::SkeletonSearchActor;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.actors
+                }
+            }
             #[derive(Debug)]
             pub struct SearchPostsSkeleton;
             impl atmo_core::xrpc::Request for SearchPostsSkeleton {
@@ -7756,7 +8418,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::unspecced::search_posts_skeleton::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::unspecced::search_posts_skeleton::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -7778,6 +8440,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7799,6 +8464,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchPostsSkeleton {
+                type Item = crate::app::bsky::unspecced::defs::SkeletonSearchPost;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.posts
+                }
+            }
             #[derive(Debug)]
             pub struct SearchStarterPacksSkeleton;
             impl atmo_core::xrpc::Request for SearchStarterPacksSkeleton {
@@ -7807,7 +8487,7 @@ pub mod app {
                 type InputError = std::convert::Infallible;
                 type Output = crate::app::bsky::unspecced::search_starter_packs_skeleton::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::app::bsky::unspecced::search_starter_packs_skeleton::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -7829,6 +8509,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -7850,6 +8533,21 @@ pub mod app {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchStarterPacksSkeleton {
+                type Item = crate::app::bsky::unspecced::defs::SkeletonSearchStarterPack;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.starter_packs
+                }
+            }
             pub mod defs {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct SkeletonSearchActor {
@@ -8197,6 +8895,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8250,6 +8951,9 @@ pub mod app {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8301,7 +9005,10 @@ pub mod app {
                     Ok(())
                 }
                 fn input_content_type() -> Option<&'static str> {
-                    None
+                    Some("video/mp4")
+                }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
                 }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     Ok(input.clone())
@@ -8422,6 +9129,10 @@ pub mod chat {
                 #[serde(rename = "allowIncoming")]
                 pub allow_incoming: crate::chat::bsky::actor::declaration::main::AllowIncoming,
             }
+            impl atmo_core::record::Record for Declaration {
+                const NSID: &'static str = "chat.bsky.actor.declaration";
+                const KEY: atmo_core::record::KeyType = atmo_core::record::KeyType::Literal("self");
+            }
             #[derive(Debug)]
             pub struct DeleteAccount;
             impl atmo_core::xrpc::Request for DeleteAccount {
@@ -8454,6 +9165,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8507,6 +9221,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/jsonl")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8628,6 +9345,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -8677,6 +9397,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8728,6 +9451,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8779,6 +9505,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8800,6 +9529,21 @@ pub mod chat {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetLog {
+                type Item = crate::chat::bsky::convo::get_log::output::Logs;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.logs
+                }
+            }
             #[derive(Debug)]
             pub struct GetMessages;
             impl atmo_core::xrpc::Request for GetMessages {
@@ -8830,6 +9574,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8851,6 +9598,21 @@ pub mod chat {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetMessages {
+                type Item = crate::chat::bsky::convo::get_messages::output::Messages;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.messages
+                }
+            }
             #[derive(Debug)]
             pub struct LeaveConvo;
             impl atmo_core::xrpc::Request for LeaveConvo {
@@ -8883,6 +9645,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -8932,6 +9697,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -8953,6 +9721,21 @@ pub mod chat {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListConvos {
+                type Item = crate::chat::bsky::convo::defs::ConvoView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.convos
+                }
+            }
             #[derive(Debug)]
             pub struct MuteConvo;
             impl atmo_core::xrpc::Request for MuteConvo {
@@ -8985,6 +9768,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -9036,6 +9822,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -9087,6 +9876,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -9138,6 +9930,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -9189,6 +9984,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -9920,6 +10718,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -9971,6 +10772,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -10024,6 +10828,9 @@ pub mod chat {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10198,6 +11005,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10251,6 +11061,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10304,6 +11117,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10357,6 +11173,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10408,6 +11227,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -10459,6 +11281,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -10510,6 +11335,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -10531,6 +11359,21 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetInviteCodes {
+                type Item = crate::com::atproto::server::defs::InviteCode;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.codes
+                }
+            }
             #[derive(Debug)]
             pub struct GetSubjectStatus;
             impl atmo_core::xrpc::Request for GetSubjectStatus {
@@ -10561,6 +11404,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -10612,6 +11458,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -10633,6 +11482,21 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchAccounts {
+                type Item = crate::com::atproto::admin::defs::AccountView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.accounts
+                }
+            }
             #[derive(Debug)]
             pub struct SendEmail;
             impl atmo_core::xrpc::Request for SendEmail {
@@ -10665,6 +11529,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10716,6 +11583,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10769,6 +11639,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10822,6 +11695,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -10875,6 +11751,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -11500,6 +12379,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -11553,6 +12435,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -11606,6 +12491,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -11659,6 +12547,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -11710,6 +12601,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -11763,6 +12657,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -11885,6 +12782,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -11906,6 +12806,42 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for QueryLabels {
+                type Item = crate::com::atproto::label::defs::Label;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.labels
+                }
+            }
+            #[derive(Debug)]
+            pub struct SubscribeLabels;
+            impl atmo_core::xrpc::Subscription for SubscribeLabels {
+                type Params = crate::com::atproto::label::subscribe_labels::Params;
+                type Message = crate::com::atproto::label::subscribe_labels::Message;
+                type RpcError = crate::com::atproto::label::subscribe_labels::Error;
+                #[inline]
+                fn nsid() -> &'static str {
+                    "com.atproto.label.subscribeLabels"
+                }
+                fn serialize_params(
+                    params: &Self::Params,
+                ) -> Result<String, serde_urlencoded_xrpc::ser::Error> {
+                    serde_urlencoded_xrpc::to_string(params)
+                }
+                fn deserialize_params(
+                    query: &str,
+                ) -> Result<Self::Params, serde_urlencoded_xrpc::de::Error> {
+                    serde_urlencoded_xrpc::from_str(query)
+                }
+            }
             pub mod defs {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct Label {
@@ -12129,6 +13065,26 @@ pub mod com {
                 }
             }
             pub mod subscribe_labels {
+                #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
+                pub enum Error {
+                    FutureCursor,
+                    #[serde(untagged)]
+                    Other(String),
+                }
+                impl Error {
+                    pub fn as_str(&self) -> &str {
+                        match self {
+                            Self::FutureCursor => "FutureCursor",
+                            Self::Other(s) => s.as_str(),
+                        }
+                    }
+                }
+                impl std::fmt::Display for Error {
+                    #[inline]
+                    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+                        f.write_str(self.as_str())
+                    }
+                }
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct Info {
                     #[serde(default)]
@@ -12141,6 +13097,67 @@ pub mod com {
                     pub labels: std::vec::Vec<crate::com::atproto::label::defs::Label>,
                     pub seq: i64,
                 }
+                #[derive(Clone, Debug, PartialEq, Eq)]
+                pub enum Message {
+                    Info(crate::com::atproto::label::subscribe_labels::Info),
+                    Labels(crate::com::atproto::label::subscribe_labels::Labels),
+                    Other(atmo_core::Unknown),
+                }
+                impl serde::Serialize for Message {
+                    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+                    where
+                        S: serde::Serializer,
+                    {
+                        let (ty, map): (&str, &dyn erased_serde::Serialize) = match self {
+                            Message::Info(value) => {
+                                ("com.atproto.label.subscribeLabels#info", value)
+                            }
+                            Message::Labels(value) => {
+                                ("com.atproto.label.subscribeLabels#labels", value)
+                            }
+                            Message::Other(unknown) => return unknown.serialize(ser),
+                        };
+                        atmo_core::union_::UnionSerialize { ty, map }.serialize(ser)
+                    }
+                }
+                impl<'de> serde::Deserialize<'de> for Message {
+                    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+                    where
+                        D: serde::Deserializer<'de>,
+                    {
+                        use serde::de::Error as _;
+                        if des.is_human_readable() {
+                            let visitor: atmo_core::union_::UnionVisitor<serde_json::Value> =
+                                Default::default();
+                            let union_ = des.deserialize_map(visitor)?;
+                            let map_des = serde::de::value::MapDeserializer::new(
+                                union_.map.iter().map(|(k, v)| (k.as_ref(), v)),
+                            );
+                            let res = match union_ . ty . as_ref () { "com.atproto.label.subscribeLabels#info" => crate :: com :: atproto :: label :: subscribe_labels :: Info :: deserialize (map_des) . map (Self :: Info) , "com.atproto.label.subscribeLabels#labels" => crate :: com :: atproto :: label :: subscribe_labels :: Labels :: deserialize (map_des) . map (Self :: Labels) , _ => atmo_core :: Unknown :: deserialize (map_des) . map (Self :: Other) , } ;
+                            res.map_err(D::Error::custom)
+                        } else {
+                            let visitor: atmo_core::union_::UnionVisitor<ipld_core::ipld::Ipld> =
+                                Default::default();
+                            let union_ = des.deserialize_map(visitor)?;
+                            let map_des = serde::de::value::MapDeserializer::new(
+                                union_.map.iter().map(|(k, v)| {
+                                    (
+                                        k.as_ref(),
+                                        atmo_core::union_::IpldIntoDeserializer(v.clone()),
+                                    )
+                                }),
+                            );
+                            let res = match union_ . ty . as_ref () { "com.atproto.label.subscribeLabels#info" => crate :: com :: atproto :: label :: subscribe_labels :: Info :: deserialize (map_des) . map (Self :: Info) , "com.atproto.label.subscribeLabels#labels" => crate :: com :: atproto :: label :: subscribe_labels :: Labels :: deserialize (map_des) . map (Self :: Labels) , _ => atmo_core :: Unknown :: deserialize (map_des) . map (Self :: Other) , } ;
+                            res.map_err(D::Error::custom)
+                        }
+                    }
+                }
+                #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
+                pub struct Params {
+                    #[serde(default)]
+                    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+                    pub cursor: std::option::Option<i64>,
+                }
                 pub mod info {
                     #[derive(
                         Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize,
@@ -12200,6 +13217,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -12469,7 +13489,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::repo::apply_writes::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::repo::apply_writes::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -12493,6 +13513,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -12520,7 +13543,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::repo::create_record::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::repo::create_record::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -12544,6 +13567,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -12571,7 +13597,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::repo::delete_record::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::repo::delete_record::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -12595,6 +13621,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -12644,6 +13673,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -12673,7 +13705,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::repo::get_record::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::repo::get_record::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -12695,6 +13727,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -12746,6 +13781,9 @@ pub mod com {
                     Ok(())
                 }
                 fn input_content_type() -> Option<&'static str> {
+                    Some("application/vnd.ipld.car")
+                }
+                fn output_content_type() -> Option<&'static str> {
                     None
                 }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
@@ -12799,6 +13837,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -12820,6 +13861,21 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListMissingBlobs {
+                type Item = crate::com::atproto::repo::list_missing_blobs::RecordBlob;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.blobs
+                }
+            }
             #[derive(Debug)]
             pub struct ListRecords;
             impl atmo_core::xrpc::Request for ListRecords {
@@ -12850,6 +13906,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -12871,6 +13930,21 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListRecords {
+                type Item = crate::com::atproto::repo::list_records::Record;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.records
+                }
+            }
             #[derive(Debug)]
             pub struct PutRecord;
             impl atmo_core::xrpc::Request for PutRecord {
@@ -12879,7 +13953,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::repo::put_record::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::repo::put_record::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -12903,6 +13977,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -12957,7 +14034,10 @@ pub mod com {
                     Ok(())
                 }
                 fn input_content_type() -> Option<&'static str> {
-                    None
+                    Some("*/*")
+                }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
                 }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     Ok(input.clone())
@@ -13611,6 +14691,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -13666,6 +14749,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -13695,7 +14781,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = ();
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::confirm_email::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -13719,6 +14805,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -13748,7 +14837,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::server::create_account::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::create_account::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -13772,6 +14861,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -13799,7 +14891,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::server::create_app_password::AppPassword;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::create_app_password::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -13823,6 +14915,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -13874,6 +14969,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -13925,6 +15023,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -13952,7 +15053,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = crate::com::atproto::server::create_session::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::create_session::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -13976,6 +15077,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14027,6 +15131,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14080,6 +15187,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14133,6 +15243,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14188,6 +15301,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14217,7 +15333,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::server::get_account_invite_codes::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::get_account_invite_codes::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -14239,6 +15355,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14268,7 +15387,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::server::get_service_auth::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::get_service_auth::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -14290,6 +15409,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14343,6 +15465,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14372,7 +15497,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::server::list_app_passwords::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::list_app_passwords::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -14396,6 +15521,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14425,7 +15553,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::server::refresh_session::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::refresh_session::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -14449,6 +15577,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14502,6 +15633,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14557,6 +15691,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14612,6 +15749,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -14665,6 +15805,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14718,6 +15861,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14769,6 +15915,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14822,6 +15971,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14851,7 +16003,7 @@ pub mod com {
                 type InputError = serde_json::Error;
                 type Output = ();
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::server::update_email::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -14875,6 +16027,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -14922,8 +16077,6 @@ pub mod com {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
                 pub enum Error {
                     AccountNotFound,
-                    ExpiredToken,
-                    InvalidToken,
                     InvalidEmail,
                     #[serde(untagged)]
                     Other(String),
@@ -14932,8 +16085,6 @@ pub mod com {
                     pub fn as_str(&self) -> &str {
                         match self {
                             Self::AccountNotFound => "AccountNotFound",
-                            Self::ExpiredToken => "ExpiredToken",
-                            Self::InvalidToken => "InvalidToken",
                             Self::InvalidEmail => "InvalidEmail",
                             Self::Other(s) => s.as_str(),
                         }
@@ -15237,28 +16388,6 @@ pub mod com {
                 }
             }
             pub mod delete_account {
-                #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
-                pub enum Error {
-                    ExpiredToken,
-                    InvalidToken,
-                    #[serde(untagged)]
-                    Other(String),
-                }
-                impl Error {
-                    pub fn as_str(&self) -> &str {
-                        match self {
-                            Self::ExpiredToken => "ExpiredToken",
-                            Self::InvalidToken => "InvalidToken",
-                            Self::Other(s) => s.as_str(),
-                        }
-                    }
-                }
-                impl std::fmt::Display for Error {
-                    #[inline]
-                    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-                        f.write_str(self.as_str())
-                    }
-                }
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct Input {
                     pub did: atmo_core::Did,
@@ -15578,28 +16707,6 @@ pub mod com {
                 }
             }
             pub mod reset_password {
-                #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
-                pub enum Error {
-                    ExpiredToken,
-                    InvalidToken,
-                    #[serde(untagged)]
-                    Other(String),
-                }
-                impl Error {
-                    pub fn as_str(&self) -> &str {
-                        match self {
-                            Self::ExpiredToken => "ExpiredToken",
-                            Self::InvalidToken => "InvalidToken",
-                            Self::Other(s) => s.as_str(),
-                        }
-                    }
-                }
-                impl std::fmt::Display for Error {
-                    #[inline]
-                    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-                        f.write_str(self.as_str())
-                    }
-                }
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct Input {
                     pub password: std::string::String,
@@ -15615,8 +16722,6 @@ pub mod com {
             pub mod update_email {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
                 pub enum Error {
-                    ExpiredToken,
-                    InvalidToken,
                     TokenRequired,
                     #[serde(untagged)]
                     Other(String),
@@ -15624,8 +16729,6 @@ pub mod com {
                 impl Error {
                     pub fn as_str(&self) -> &str {
                         match self {
-                            Self::ExpiredToken => "ExpiredToken",
-                            Self::InvalidToken => "InvalidToken",
                             Self::TokenRequired => "TokenRequired",
                             Self::Other(s) => s.as_str(),
                         }
@@ -15659,7 +16762,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = bytes::Bytes;
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_blob::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -15681,6 +16784,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("*/*")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -15710,7 +16816,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = bytes::Bytes;
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_blocks::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -15732,6 +16838,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/vnd.ipld.car")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -15783,6 +16892,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/vnd.ipld.car")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -15812,7 +16924,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::sync::get_head::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_head::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -15834,6 +16946,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -15863,7 +16978,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::sync::get_latest_commit::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_latest_commit::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -15885,6 +17000,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -15914,7 +17032,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = bytes::Bytes;
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_record::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -15936,6 +17054,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/vnd.ipld.car")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -15965,7 +17086,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = bytes::Bytes;
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_repo::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -15987,6 +17108,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/vnd.ipld.car")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -16016,7 +17140,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::sync::get_repo_status::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::get_repo_status::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -16038,6 +17162,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -16067,7 +17194,7 @@ pub mod com {
                 type InputError = std::convert::Infallible;
                 type Output = crate::com::atproto::sync::list_blobs::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::com::atproto::sync::list_blobs::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -16089,6 +17216,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -16110,6 +17240,21 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListBlobs {
+                type Item = atmo_core::CidString;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.cids
+                }
+            }
             #[derive(Debug)]
             pub struct ListRepos;
             impl atmo_core::xrpc::Request for ListRepos {
@@ -16140,6 +17285,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -16161,6 +17309,21 @@ pub mod com {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListRepos {
+                type Item = crate::com::atproto::sync::list_repos::Repo;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.repos
+                }
+            }
             #[derive(Debug)]
             pub struct NotifyOfUpdate;
             impl atmo_core::xrpc::Request for NotifyOfUpdate {
@@ -16193,6 +17356,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -16246,6 +17412,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -16267,6 +17436,27 @@ pub mod com {
                     Ok(())
                 }
             }
+            #[derive(Debug)]
+            pub struct SubscribeRepos;
+            impl atmo_core::xrpc::Subscription for SubscribeRepos {
+                type Params = crate::com::atproto::sync::subscribe_repos::Params;
+                type Message = crate::com::atproto::sync::subscribe_repos::Message;
+                type RpcError = crate::com::atproto::sync::subscribe_repos::Error;
+                #[inline]
+                fn nsid() -> &'static str {
+                    "com.atproto.sync.subscribeRepos"
+                }
+                fn serialize_params(
+                    params: &Self::Params,
+                ) -> Result<String, serde_urlencoded_xrpc::ser::Error> {
+                    serde_urlencoded_xrpc::to_string(params)
+                }
+                fn deserialize_params(
+                    query: &str,
+                ) -> Result<Self::Params, serde_urlencoded_xrpc::de::Error> {
+                    serde_urlencoded_xrpc::from_str(query)
+                }
+            }
             pub mod get_blob {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
                 pub enum Error {
@@ -16708,6 +17898,28 @@ pub mod com {
                     #[serde(rename = "tooBig")]
                     pub too_big: bool,
                 }
+                #[derive(Clone, Debug, PartialEq, Eq, serde :: Serialize, serde :: Deserialize)]
+                pub enum Error {
+                    FutureCursor,
+                    ConsumerTooSlow,
+                    #[serde(untagged)]
+                    Other(String),
+                }
+                impl Error {
+                    pub fn as_str(&self) -> &str {
+                        match self {
+                            Self::FutureCursor => "FutureCursor",
+                            Self::ConsumerTooSlow => "ConsumerTooSlow",
+                            Self::Other(s) => s.as_str(),
+                        }
+                    }
+                }
+                impl std::fmt::Display for Error {
+                    #[inline]
+                    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+                        f.write_str(self.as_str())
+                    }
+                }
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct Handle {
                     pub did: atmo_core::Did,
@@ -16731,6 +17943,79 @@ pub mod com {
                     pub message: std::option::Option<std::string::String>,
                     pub name: crate::com::atproto::sync::subscribe_repos::info::Name,
                 }
+                #[derive(Clone, Debug, PartialEq, Eq)]
+                pub enum Message {
+                    Account(crate::com::atproto::sync::subscribe_repos::Account),
+                    Commit(crate::com::atproto::sync::subscribe_repos::Commit),
+                    Handle(crate::com::atproto::sync::subscribe_repos::Handle),
+                    Identity(crate::com::atproto::sync::subscribe_repos::Identity),
+                    Info(crate::com::atproto::sync::subscribe_repos::Info),
+                    Migrate(crate::com::atproto::sync::subscribe_repos::Migrate),
+                    Tombstone(crate::com::atproto::sync::subscribe_repos::Tombstone),
+                    Other(atmo_core::Unknown),
+                }
+                impl serde::Serialize for Message {
+                    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+                    where
+                        S: serde::Serializer,
+                    {
+                        let (ty, map): (&str, &dyn erased_serde::Serialize) = match self {
+                            Message::Account(value) => {
+                                ("com.atproto.sync.subscribeRepos#account", value)
+                            }
+                            Message::Commit(value) => {
+                                ("com.atproto.sync.subscribeRepos#commit", value)
+                            }
+                            Message::Handle(value) => {
+                                ("com.atproto.sync.subscribeRepos#handle", value)
+                            }
+                            Message::Identity(value) => {
+                                ("com.atproto.sync.subscribeRepos#identity", value)
+                            }
+                            Message::Info(value) => ("com.atproto.sync.subscribeRepos#info", value),
+                            Message::Migrate(value) => {
+                                ("com.atproto.sync.subscribeRepos#migrate", value)
+                            }
+                            Message::Tombstone(value) => {
+                                ("com.atproto.sync.subscribeRepos#tombstone", value)
+                            }
+                            Message::Other(unknown) => return unknown.serialize(ser),
+                        };
+                        atmo_core::union_::UnionSerialize { ty, map }.serialize(ser)
+                    }
+                }
+                impl<'de> serde::Deserialize<'de> for Message {
+                    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+                    where
+                        D: serde::Deserializer<'de>,
+                    {
+                        use serde::de::Error as _;
+                        if des.is_human_readable() {
+                            let visitor: atmo_core::union_::UnionVisitor<serde_json::Value> =
+                                Default::default();
+                            let union_ = des.deserialize_map(visitor)?;
+                            let map_des = serde::de::value::MapDeserializer::new(
+                                union_.map.iter().map(|(k, v)| (k.as_ref(), v)),
+                            );
+                            let res = match union_ . ty . as_ref () { "com.atproto.sync.subscribeRepos#account" => crate :: com :: atproto :: sync :: subscribe_repos :: Account :: deserialize (map_des) . map (Self :: Account) , "com.atproto.sync.subscribeRepos#commit" => crate :: com :: atproto :: sync :: subscribe_repos :: Commit :: deserialize (map_des) . map (Self :: Commit) , "com.atproto.sync.subscribeRepos#handle" => crate :: com :: atproto :: sync :: subscribe_repos :: Handle :: deserialize (map_des) . map (Self :: Handle) , "com.atproto.sync.subscribeRepos#identity" => crate :: com :: atproto :: sync :: subscribe_repos :: Identity :: deserialize (map_des) . map (Self :: Identity) , "com.atproto.sync.subscribeRepos#info" => crate :: com :: atproto :: sync :: subscribe_repos :: Info :: deserialize (map_des) . map (Self :: Info) , "com.atproto.sync.subscribeRepos#migrate" => crate :: com :: atproto :: sync :: subscribe_repos :: Migrate :: deserialize (map_des) . map (Self :: Migrate) , "com.atproto.sync.subscribeRepos#tombstone" => crate :: com :: atproto :: sync :: subscribe_repos :: Tombstone :: deserialize (map_des) . map (Self :: Tombstone) , _ => atmo_core :: Unknown :: deserialize (map_des) . map (Self :: Other) , } ;
+                            res.map_err(D::Error::custom)
+                        } else {
+                            let visitor: atmo_core::union_::UnionVisitor<ipld_core::ipld::Ipld> =
+                                Default::default();
+                            let union_ = des.deserialize_map(visitor)?;
+                            let map_des = serde::de::value::MapDeserializer::new(
+                                union_.map.iter().map(|(k, v)| {
+                                    (
+                                        k.as_ref(),
+                                        atmo_core::union_::IpldIntoDeserializer(v.clone()),
+                                    )
+                                }),
+                            );
+                            let res = match union_ . ty . as_ref () { "com.atproto.sync.subscribeRepos#account" => crate :: com :: atproto :: sync :: subscribe_repos :: Account :: deserialize (map_des) . map (Self :: Account) , "com.atproto.sync.subscribeRepos#commit" => crate :: com :: atproto :: sync :: subscribe_repos :: Commit :: deserialize (map_des) . map (Self :: Commit) , "com.atproto.sync.subscribeRepos#handle" => crate :: com :: atproto :: sync :: subscribe_repos :: Handle :: deserialize (map_des) . map (Self :: Handle) , "com.atproto.sync.subscribeRepos#identity" => crate :: com :: atproto :: sync :: subscribe_repos :: Identity :: deserialize (map_des) . map (Self :: Identity) , "com.atproto.sync.subscribeRepos#info" => crate :: com :: atproto :: sync :: subscribe_repos :: Info :: deserialize (map_des) . map (Self :: Info) , "com.atproto.sync.subscribeRepos#migrate" => crate :: com :: atproto :: sync :: subscribe_repos :: Migrate :: deserialize (map_des) . map (Self :: Migrate) , "com.atproto.sync.subscribeRepos#tombstone" => crate :: com :: atproto :: sync :: subscribe_repos :: Tombstone :: deserialize (map_des) . map (Self :: Tombstone) , _ => atmo_core :: Unknown :: deserialize (map_des) . map (Self :: Other) , } ;
+                            res.map_err(D::Error::custom)
+                        }
+                    }
+                }
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct Migrate {
                     pub did: atmo_core::Did,
@@ -16740,6 +18025,12 @@ pub mod com {
                     pub time: atmo_core::DateTime,
                 }
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
+                pub struct Params {
+                    #[serde(default)]
+                    #[serde(skip_serializing_if = "std::option::Option::is_none")]
+                    pub cursor: std::option::Option<i64>,
+                }
+                #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct RepoOp {
                     pub action: crate::com::atproto::sync::subscribe_repos::repo_op::Action,
                     pub cid: atmo_core::Nullable<atmo_core::CidLink>,
@@ -16875,6 +18166,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -16926,6 +18220,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -16977,6 +18274,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17030,6 +18330,9 @@ pub mod com {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -17109,7 +18412,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = crate::tools::ozone::communication::defs::TemplateView;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::communication::create_template::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -17133,6 +18436,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -17184,6 +18490,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -17237,6 +18546,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17266,7 +18578,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = crate::tools::ozone::communication::defs::TemplateView;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::communication::update_template::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -17290,6 +18602,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -17437,7 +18752,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = crate::tools::ozone::moderation::defs::ModEventView;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::moderation::emit_event::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -17461,6 +18776,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -17510,6 +18828,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17539,7 +18860,7 @@ pub mod tools {
                 type InputError = std::convert::Infallible;
                 type Output = crate::tools::ozone::moderation::defs::RecordViewDetail;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::moderation::get_record::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -17561,6 +18882,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17612,6 +18936,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17641,7 +18968,7 @@ pub mod tools {
                 type InputError = std::convert::Infallible;
                 type Output = crate::tools::ozone::moderation::defs::RepoViewDetail;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::moderation::get_repo::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -17663,6 +18990,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17714,6 +19044,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17765,6 +19098,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17786,6 +19122,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for QueryEvents {
+                type Item = crate::tools::ozone::moderation::defs::ModEventView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.events
+                }
+            }
             #[derive(Debug)]
             pub struct QueryStatuses;
             impl atmo_core::xrpc::Request for QueryStatuses {
@@ -17816,6 +19167,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17837,6 +19191,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for QueryStatuses {
+                type Item = crate::tools::ozone::moderation::defs::SubjectStatusView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.subject_statuses
+                }
+            }
             #[derive(Debug)]
             pub struct SearchRepos;
             impl atmo_core::xrpc::Request for SearchRepos {
@@ -17867,6 +19236,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -17888,6 +19260,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchRepos {
+                type Item = crate::tools::ozone::moderation::defs::RepoView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.repos
+                }
+            }
             pub mod defs {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct AccountEvent {
@@ -19969,6 +21356,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -20101,6 +21491,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -20130,7 +21523,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = crate::tools::ozone::set::delete_set::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::set::delete_set::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -20154,6 +21547,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -20181,7 +21577,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = ();
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::set::delete_values::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -20205,6 +21601,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -20234,7 +21633,7 @@ pub mod tools {
                 type InputError = std::convert::Infallible;
                 type Output = crate::tools::ozone::set::get_values::Output;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::set::get_values::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::GET
@@ -20256,6 +21655,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -20277,6 +21679,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for GetValues {
+                type Item = std::string::String;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.values
+                }
+            }
             #[derive(Debug)]
             pub struct QuerySets;
             impl atmo_core::xrpc::Request for QuerySets {
@@ -20307,6 +21724,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -20328,6 +21748,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for QuerySets {
+                type Item = crate::tools::ozone::set::defs::SetView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.sets
+                }
+            }
             #[derive(Debug)]
             pub struct UpsertSet;
             impl atmo_core::xrpc::Request for UpsertSet {
@@ -20360,6 +21795,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -20619,6 +22057,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -20640,6 +22081,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListOptions {
+                type Item = crate::tools::ozone::setting::defs::Option;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.options
+                }
+            }
             #[derive(Debug)]
             pub struct RemoveOptions;
             impl atmo_core::xrpc::Request for RemoveOptions {
@@ -20672,6 +22128,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -20723,6 +22182,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -21045,6 +22507,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -21096,6 +22561,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -21117,6 +22585,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for FindRelatedAccounts {
+                type Item = crate::tools::ozone::signature::find_related_accounts::RelatedAccount;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.accounts
+                }
+            }
             #[derive(Debug)]
             pub struct SearchAccounts;
             impl atmo_core::xrpc::Request for SearchAccounts {
@@ -21147,6 +22630,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -21168,6 +22654,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for SearchAccounts {
+                type Item = crate::com::atproto::admin::defs::AccountView;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.accounts
+                }
+            }
             pub mod defs {
                 #[derive(Clone, Debug, PartialEq, Eq, serde :: Deserialize, serde :: Serialize)]
                 pub struct SigDetail {
@@ -21244,7 +22745,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = crate::tools::ozone::team::defs::Member;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::team::add_member::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -21268,6 +22769,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -21295,7 +22799,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = ();
                 type OutputError = std::convert::Infallible;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::team::delete_member::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -21319,6 +22823,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    None
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
@@ -21370,6 +22877,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     None
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     let _ = input;
                     Ok(bytes::Bytes::new())
@@ -21391,6 +22901,21 @@ pub mod tools {
                     serde_json::from_slice(bytes)
                 }
             }
+            impl atmo_core::xrpc::Paginated for ListMembers {
+                type Item = crate::tools::ozone::team::defs::Member;
+                #[inline]
+                fn cursor(output: &Self::Output) -> Option<&str> {
+                    output.cursor.as_deref()
+                }
+                #[inline]
+                fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                    params.cursor = cursor;
+                }
+                #[inline]
+                fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                    output.members
+                }
+            }
             #[derive(Debug)]
             pub struct UpdateMember;
             impl atmo_core::xrpc::Request for UpdateMember {
@@ -21399,7 +22924,7 @@ pub mod tools {
                 type InputError = serde_json::Error;
                 type Output = crate::tools::ozone::team::defs::Member;
                 type OutputError = serde_json::Error;
-                type RpcError = String;
+                type RpcError = crate::tools::ozone::team::update_member::Error;
                 #[inline]
                 fn method() -> http::Method {
                     http::Method::POST
@@ -21423,6 +22948,9 @@ pub mod tools {
                 fn input_content_type() -> Option<&'static str> {
                     Some("application/json")
                 }
+                fn output_content_type() -> Option<&'static str> {
+                    Some("application/json")
+                }
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     serde_json::to_vec(input).map(bytes::Bytes::from)
                 }
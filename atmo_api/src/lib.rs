@@ -6,39 +6,87 @@
 // TODO(dp): box large enum variants
 #![allow(clippy::large_enum_variant)]
 
-use std::{error::Error, fmt, marker::PhantomData};
+use std::{error::Error, fmt, marker::PhantomData, sync::Arc, time::Duration};
 
+pub mod agent;
+#[cfg(feature = "blocking")]
+pub mod blocking;
 mod generated;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+#[cfg(feature = "oauth")]
+pub mod oauth;
+pub mod pagination;
+pub mod record;
+pub mod retry;
 #[cfg(test)]
 mod tests;
+pub mod transport;
 
-use atmo_core::xrpc::{self, Request};
+pub use agent::Agent;
+use atmo_core::{
+    did::DidUrl,
+    xrpc::{self, Request},
+};
 use bytes::Bytes;
+use data_encoding::BASE64;
 pub use generated::*;
-use http_body_util::{BodyExt, Full};
+use http::{header, HeaderMap, HeaderName, HeaderValue};
+use http_body_util::Full;
+use retry::{RateLimit, RetryPolicy};
+use transport::{DynTransport, HttpTransport};
 use url::Url;
 
+#[derive(Clone)]
 pub struct XrpcClient {
-    inner: reqwest::Client,
+    transport: Arc<dyn DynTransport>,
+    retry: RetryPolicy,
+}
+
+impl fmt::Debug for XrpcClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XrpcClient")
+            .field("retry", &self.retry)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Default for XrpcClient {
     #[inline]
     fn default() -> Self {
-        Self {
-            inner: Default::default(),
-        }
+        XrpcClient::new()
     }
 }
 
 impl XrpcClient {
     /// Creates an `XrpcClient` with default settings.
+    ///
+    /// Requests are sent with a [`reqwest::Client`]. Failed requests are not retried. See
+    /// [`retry_policy`](Self::retry_policy).
     pub fn new() -> Self {
-        Self {
-            inner: reqwest::Client::new(),
+        XrpcClient::with_transport(reqwest::Client::new())
+    }
+
+    /// Creates an `XrpcClient` which sends requests with `transport`.
+    ///
+    /// See the [`transport`] module.
+    pub fn with_transport<T>(transport: T) -> Self
+    where
+        T: HttpTransport + Send + Sync + 'static,
+    {
+        XrpcClient {
+            transport: Arc::new(transport),
+            retry: RetryPolicy::never(),
         }
     }
 
+    /// Sets the policy for retrying failed requests sent by this client.
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
     /// Creates a builder for an XRPC request.
     ///
     /// Every known XRPC request is represented by a type in the [`api`] module.
@@ -75,22 +123,44 @@ impl XrpcClient {
     {
         let _ = req;
 
-        // TODO(dp): don't assert, custom error type
-        assert!(base_url.path().ends_with('/'));
-
-        let url = base_url
-            .join("xrpc/")
-            .and_then(|u| u.join(R::nsid()))
-            .unwrap();
-
-        let builder = self.inner.request(R::method(), url);
-
         RequestBuilder {
-            builder,
-            query: None,
+            transport: Arc::clone(&self.transport),
+            state: RequestState::new::<R>(base_url, self.retry),
             marker: PhantomData,
         }
     }
+
+    /// Sends a plain HTTP request with this client's transport, retrying it according to the
+    /// client's retry policy.
+    ///
+    /// `build` is called before each attempt, so that single-use headers such as DPoP proofs are
+    /// created afresh.
+    #[cfg(feature = "oauth")]
+    pub(crate) async fn send_http<F>(
+        &self,
+        mut build: F,
+    ) -> Result<http::Response<Bytes>, ResponseError>
+    where
+        F: FnMut() -> http::Request<Bytes>,
+    {
+        let mut attempt = 1;
+        loop {
+            let req = build();
+            let method = req.method().clone();
+            let result = self.transport.send(req).await.map_err(ResponseError::Http);
+
+            let response = result
+                .as_ref()
+                .ok()
+                .map(|resp| (resp.status(), resp.headers()));
+            let Some(delay) = self.retry.delay_after(&method, attempt, response) else {
+                return result;
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
 }
 
 /// A response to an XRPC request.
@@ -121,15 +191,21 @@ where
     pub fn result(&self) -> Result<&R::Output, &xrpc::Error<R::RpcError>> {
         self.result.as_ref()
     }
+
+    /// Returns the rate limit reported by the server, if any.
+    #[inline]
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        RateLimit::from_headers(&self.parts.headers)
+    }
 }
 
 /// An error which occurred during an XRPC call.
 #[derive(Debug)]
 pub enum ResponseError {
     /// An error occurred in the underlying HTTP request.
-    Http(reqwest::Error),
+    Http(Box<dyn Error + Send + Sync>),
     /// The returned HTTP response was not recognized.
-    InvalidXrpc(InvalidXrpcError),
+    InvalidXrpc(Box<InvalidXrpcError>),
 }
 
 /// An error produced when an XRPC response body could not be deserialized.
@@ -141,37 +217,250 @@ pub struct InvalidXrpcError {
     pub response: http::Response<Full<Bytes>>,
 }
 
-pub struct RequestBuilder<R> {
-    builder: reqwest::RequestBuilder,
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseError::Http(e) => fmt::Display::fmt(e, f),
+            ResponseError::InvalidXrpc(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl Error for ResponseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ResponseError::Http(e) => Some(&**e),
+            ResponseError::InvalidXrpc(e) => Some(&**e),
+        }
+    }
+}
+
+impl fmt::Display for InvalidXrpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid XRPC response: {}", self.error)
+    }
+}
+
+impl Error for InvalidXrpcError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// An error produced when the media type of an XRPC response does not match the method's output
+/// encoding.
+#[derive(Debug)]
+pub struct ContentTypeError {
+    /// The encoding of the method's output.
+    pub expected: &'static str,
+    /// The `Content-Type` of the response, if any.
+    pub found: Option<String>,
+}
+
+impl fmt::Display for ContentTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.found {
+            Some(found) => write!(
+                f,
+                "unexpected content type {found}, expected {}",
+                self.expected
+            ),
+            None => write!(f, "missing content type, expected {}", self.expected),
+        }
+    }
+}
+
+impl Error for ContentTypeError {}
+
+/// An XRPC request being built, independent of how it is sent.
+///
+/// This is shared by [`RequestBuilder`] and the blocking `RequestBuilder`, so that both encode
+/// requests identically.
+#[derive(Clone)]
+pub(crate) struct RequestState {
+    url: Url,
     query: Option<String>,
+    headers: HeaderMap,
+    body: Bytes,
+    /// The first invalid header value, reported when the request is sent.
+    error: Option<Arc<http::Error>>,
+    retry: RetryPolicy,
+}
+
+impl RequestState {
+    /// Creates a request for the XRPC method `R` to the service at `base_url`.
+    pub(crate) fn new<R>(base_url: &Url, retry: RetryPolicy) -> Self
+    where
+        R: Request,
+    {
+        // TODO(dp): don't assert, custom error type
+        assert!(base_url.path().ends_with('/'));
+
+        let url = base_url
+            .join("xrpc/")
+            .and_then(|u| u.join(R::nsid()))
+            .unwrap();
+
+        RequestState {
+            url,
+            query: None,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            error: None,
+            retry,
+        }
+    }
+
+    pub(crate) fn params<R>(
+        &mut self,
+        params: &R::Params,
+    ) -> Result<(), serde_urlencoded_xrpc::ser::Error>
+    where
+        R: Request,
+    {
+        self.query = Some(R::serialize_params(params)?);
+        Ok(())
+    }
+
+    pub(crate) fn input<R>(&mut self, input: &R::Input) -> Result<(), R::InputError>
+    where
+        R: Request,
+    {
+        self.body = R::serialize_input(input)?;
+
+        if let Some(enc) = R::input_content_type().filter(|enc| !enc.contains('*')) {
+            self.header(header::CONTENT_TYPE, enc);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn admin_auth<T>(&mut self, token: T)
+    where
+        T: fmt::Display,
+    {
+        let credentials = BASE64.encode(format!("admin:{token}").as_bytes());
+        self.header(header::AUTHORIZATION, format!("Basic {credentials}"));
+    }
+
+    /// Sets the value of a header, replacing any previous value.
+    pub(crate) fn header<V>(&mut self, name: HeaderName, value: V)
+    where
+        V: TryInto<HeaderValue>,
+        V::Error: Into<http::Error>,
+    {
+        match value.try_into() {
+            Ok(value) => {
+                self.headers.insert(name, value);
+            }
+            Err(e) => {
+                self.error.get_or_insert_with(|| Arc::new(e.into()));
+            }
+        }
+    }
+
+    /// Builds the HTTP request for the XRPC method `R`.
+    pub(crate) fn http_request<R>(&self) -> Result<http::Request<Bytes>, ResponseError>
+    where
+        R: Request,
+    {
+        if let Some(e) = &self.error {
+            return Err(ResponseError::Http(Box::new(Arc::clone(e))));
+        }
+
+        let mut url = self.url.clone();
+        url.set_query(self.query.as_deref());
+
+        let mut req = http::Request::new(self.body.clone());
+        *req.method_mut() = R::method();
+        *req.uri_mut() = url
+            .as_str()
+            .parse()
+            .map_err(|e| ResponseError::Http(Box::new(e)))?;
+        *req.headers_mut() = self.headers.clone();
+
+        Ok(req)
+    }
+
+    /// Returns how long to wait before sending the request again after attempt `attempt` produced
+    /// `result`, or `None` if it should not be retried.
+    pub(crate) fn retry_delay<R>(
+        &self,
+        attempt: u32,
+        result: &Result<(http::response::Parts, Bytes), ResponseError>,
+    ) -> Option<Duration>
+    where
+        R: Request,
+    {
+        let response = result
+            .as_ref()
+            .ok()
+            .map(|(parts, _)| (parts.status, &parts.headers));
+        self.retry.delay_after(&R::method(), attempt, response)
+    }
+}
+
+pub struct RequestBuilder<R> {
+    transport: Arc<dyn DynTransport>,
+    state: RequestState,
     marker: PhantomData<R>,
 }
 
+impl<R> Clone for RequestBuilder<R> {
+    fn clone(&self) -> Self {
+        RequestBuilder {
+            transport: Arc::clone(&self.transport),
+            state: self.state.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
 impl<R> RequestBuilder<R>
 where
     R: Request,
 {
     pub fn params(mut self, params: &R::Params) -> Result<Self, serde_urlencoded_xrpc::ser::Error> {
-        self.query = Some(R::serialize_params(params)?);
+        self.state.params::<R>(params)?;
         Ok(self)
     }
 
+    /// Sets the body of the request.
+    ///
+    /// The `Content-Type` header is set to the method's input encoding. If the encoding is a
+    /// wildcard, such as `*/*` for `com.atproto.repo.uploadBlob`, the actual media type must be
+    /// set with [`content_type`](Self::content_type).
     pub fn input(mut self, input: &R::Input) -> Result<Self, R::InputError> {
-        self.builder = self.builder.body(R::serialize_input(input)?);
-
-        if let Some(enc) = R::input_content_type() {
-            self.builder = self.builder.header(http::header::CONTENT_TYPE, enc);
-        }
-
+        self.state.input::<R>(input)?;
         Ok(self)
     }
 
     /// Sets the value of the `Content-Type` header for the request.
     #[inline]
     pub fn content_type(mut self, content_type: &str) -> Self {
-        self.builder = self
-            .builder
-            .header(http::header::CONTENT_TYPE, content_type);
+        self.state.header(header::CONTENT_TYPE, content_type);
+        self
+    }
+
+    /// Sets the policy for retrying this request if it fails, overriding the client's policy.
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.state.retry = policy;
+        self
+    }
+
+    /// Asks the server to forward this request to another service.
+    ///
+    /// `service` is the DID of the service, with the fragment of the service entry in its DID
+    /// document, such as `did:web:api.bsky.app#bsky_appview`. It is sent in the `atproto-proxy`
+    /// header. See the [Service Proxying] section of the ATProto specification.
+    ///
+    /// [Service Proxying]: https://atproto.com/specs/xrpc#service-proxying
+    #[inline]
+    pub fn proxy(mut self, service: &DidUrl) -> Self {
+        self.state.header(
+            HeaderName::from_static("atproto-proxy"),
+            service.to_string(),
+        );
         self
     }
 
@@ -193,7 +482,7 @@ where
     where
         T: fmt::Display,
     {
-        self.builder = self.builder.basic_auth("admin", Some(token));
+        self.state.admin_auth(token);
         self
     }
 
@@ -215,7 +504,34 @@ where
     where
         T: fmt::Display,
     {
-        self.builder = self.builder.bearer_auth(token);
+        self.state
+            .header(header::AUTHORIZATION, format!("Bearer {token}"));
+        self
+    }
+
+    /// Applies OAuth DPoP authorization to this request.
+    ///
+    /// The DPoP-bound `access_token` is sent in the `Authorization` header, along with a proof
+    /// signed by `key` in the `DPoP` header. `nonce` is the latest DPoP nonce provided by the
+    /// server, if any.
+    ///
+    /// See the [OAuth] section of the ATProto specification.
+    ///
+    /// [OAuth]: https://atproto.com/specs/oauth
+    #[cfg(feature = "oauth")]
+    pub fn dpop_auth(
+        mut self,
+        access_token: &str,
+        key: &oauth::DpopKey,
+        nonce: Option<&str>,
+    ) -> Self {
+        let proof = key.proof(&R::method(), &self.state.url, nonce, Some(access_token));
+
+        // A DPoP proof may only be used once.
+        self.state.retry = RetryPolicy::never();
+        self.state
+            .header(header::AUTHORIZATION, format!("DPoP {access_token}"));
+        self.state.header(HeaderName::from_static("dpop"), proof);
         self
     }
 
@@ -225,30 +541,71 @@ where
     /// returns an error if the request could not be sent, or if an error occurred while receiving
     /// the response.
     pub async fn send(self) -> Result<Response<R>, ResponseError> {
-        let (cl, req) = self.builder.build_split();
+        let (parts, bytes) = self.send_raw().await?;
+        Self::decode(parts, bytes)
+    }
 
-        let mut req = req.unwrap();
-        req.url_mut().set_query(self.query.as_deref());
+    /// Returns how long to wait before sending the request again after attempt `attempt` produced
+    /// `result`, or `None` if it should not be retried.
+    #[cfg(feature = "oauth")]
+    pub(crate) fn retry_delay(
+        &self,
+        attempt: u32,
+        result: &Result<(http::response::Parts, Bytes), ResponseError>,
+    ) -> Option<Duration> {
+        self.state.retry_delay::<R>(attempt, result)
+    }
 
-        let resp: http::Response<_> = cl.execute(req).await.map_err(ResponseError::Http)?.into();
-        let (parts, body) = resp.into_parts();
+    /// Sends the XRPC request, returning the raw response.
+    ///
+    /// The request is retried according to its retry policy.
+    pub(crate) async fn send_raw(self) -> Result<(http::response::Parts, Bytes), ResponseError> {
+        let mut attempt = 1;
+        loop {
+            let result = self.send_once().await;
+            let Some(delay) = self.state.retry_delay::<R>(attempt, &result) else {
+                return result;
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
 
-        let bytes = body
-            .collect()
+    /// Sends the XRPC request once, returning the raw response.
+    async fn send_once(&self) -> Result<(http::response::Parts, Bytes), ResponseError> {
+        let req = self.state.http_request::<R>()?;
+        let resp = self
+            .transport
+            .send(req)
             .await
-            .map_err(ResponseError::Http)?
-            .to_bytes();
+            .map_err(ResponseError::Http)?;
 
+        Ok(resp.into_parts())
+    }
+
+    /// Decodes a raw XRPC response.
+    pub(crate) fn decode(
+        parts: http::response::Parts,
+        bytes: Bytes,
+    ) -> Result<Response<R>, ResponseError> {
         let status = parts.status;
         if status.is_client_error() || status.is_server_error() {
-            let rpc_error = serde_json::from_slice(&bytes).map_err(|error| {
-                // Reconstruct the response.
-                let response = http::Response::from_parts(parts.clone(), Full::new(bytes));
-                ResponseError::InvalidXrpc(InvalidXrpcError {
-                    error: Box::new(error),
-                    response,
-                })
-            })?;
+            let rpc_error = match serde_json::from_slice(&bytes) {
+                Ok(rpc_error) => rpc_error,
+                // Proxies may respond without an XRPC error body.
+                Err(error) => match xrpc::ErrorCode::from_status(status) {
+                    Some(code) => xrpc::Error::new(code),
+                    None => {
+                        // Reconstruct the response.
+                        let response = http::Response::from_parts(parts.clone(), Full::new(bytes));
+                        return Err(ResponseError::InvalidXrpc(Box::new(InvalidXrpcError {
+                            error: Box::new(error),
+                            response,
+                        })));
+                    }
+                },
+            };
 
             return Ok(Response {
                 parts,
@@ -256,13 +613,33 @@ where
             });
         }
 
+        if let Some(expected) = R::output_content_type().filter(|&enc| enc != "*/*") {
+            let found = parts
+                .headers
+                .get(http::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok());
+
+            if !found.is_some_and(|found| xrpc::encoding_matches(expected, found)) {
+                let error = ContentTypeError {
+                    expected,
+                    found: found.map(str::to_owned),
+                };
+                // Reconstruct the response.
+                let response = http::Response::from_parts(parts, Full::new(bytes));
+                return Err(ResponseError::InvalidXrpc(Box::new(InvalidXrpcError {
+                    error: Box::new(error),
+                    response,
+                })));
+            }
+        }
+
         let output = R::deserialize_output(&bytes).map_err(|error| {
             // Reconstruct the response.
             let response = http::Response::from_parts(parts.clone(), Full::new(bytes));
-            ResponseError::InvalidXrpc(InvalidXrpcError {
+            ResponseError::InvalidXrpc(Box::new(InvalidXrpcError {
                 error: Box::new(error),
                 response,
-            })
+            }))
         })?;
 
         Ok(Response {
@@ -0,0 +1,240 @@
+//! An in-memory transport for testing code which calls XRPC methods.
+//!
+//! # Example
+//!
+//! ```
+//! # async fn async_main() {
+//! use atmo_api::com::atproto::identity::{resolve_handle, ResolveHandle};
+//! use atmo_api::{mock::MockTransport, XrpcClient};
+//! use url::Url;
+//!
+//! let mock = MockTransport::new();
+//! let params = resolve_handle::Params {
+//!     handle: "alice.test".parse().unwrap(),
+//! };
+//! mock.respond(
+//!     ResolveHandle,
+//!     Some(&params),
+//!     Ok(resolve_handle::Output {
+//!         did: "did:plc:ewvi7nxzyoun6zhxrhs64oiz".parse().unwrap(),
+//!     }),
+//! );
+//!
+//! let client = XrpcClient::with_transport(mock.clone());
+//! let url = Url::parse("https://pds.example.com/").unwrap();
+//! let resp = client
+//!     .request(&url, ResolveHandle)
+//!     .params(&params)
+//!     .unwrap()
+//!     .send()
+//!     .await
+//!     .unwrap();
+//!
+//! assert!(resp.result().is_ok());
+//! assert_eq!(mock.calls().len(), 1);
+//! # }
+//! ```
+
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use atmo_core::xrpc::{self, ErrorCode, Request};
+use bytes::Bytes;
+use http::{header, HeaderMap};
+use serde::Serialize;
+
+#[cfg(feature = "blocking")]
+use crate::blocking::BlockingTransport;
+use crate::transport::HttpTransport;
+
+/// A request received by a [`MockTransport`].
+#[derive(Clone, Debug)]
+pub struct MockCall {
+    /// The HTTP method of the request.
+    pub method: http::Method,
+    /// The URI of the request.
+    pub uri: http::Uri,
+    /// The NSID of the XRPC method, or an empty string if the request is not an XRPC request.
+    pub nsid: String,
+    /// The query string of the request, if any.
+    pub query: Option<String>,
+    /// The headers of the request.
+    pub headers: HeaderMap,
+    /// The body of the request.
+    pub body: Bytes,
+}
+
+/// A registered response, which answers the requests for which it returns `Some`.
+type Rule = Arc<dyn Fn(&MockCall) -> Option<http::Response<Bytes>> + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    rules: Vec<Rule>,
+    calls: Vec<MockCall>,
+}
+
+/// An [`HttpTransport`] which answers XRPC requests with canned responses.
+///
+/// Responses are registered with [`respond`](Self::respond), or computed from each request by a
+/// function registered with [`respond_with`](Self::respond_with). Each request is answered by the
+/// first registered response which matches it; requests with no matching response are answered
+/// with a `MethodNotImplemented` error. Clones of a `MockTransport` share their responses and
+/// calls.
+///
+/// With the `blocking` feature, `MockTransport` is also a
+/// [`BlockingTransport`](crate::blocking::BlockingTransport).
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockTransport {
+    /// Creates a transport with no responses.
+    #[inline]
+    pub fn new() -> Self {
+        MockTransport::default()
+    }
+
+    /// Registers a response to requests for the XRPC method `R`.
+    ///
+    /// If `params` is `None`, requests with any parameters match.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params` or `result` cannot be serialized.
+    pub fn respond<R>(
+        &self,
+        req: R,
+        params: Option<&R::Params>,
+        result: Result<R::Output, xrpc::Error<R::RpcError>>,
+    ) -> &Self
+    where
+        R: Request,
+        R::RpcError: Serialize,
+    {
+        let _ = req;
+
+        let params = params.map(|params| {
+            let query = R::serialize_params(params).expect("params should serialize");
+            sorted_pairs(&query)
+        });
+
+        // Outputs are encoded as by a server.
+        let response = match result {
+            Ok(output) => xrpc::Response::new(output)
+                .encode::<R>()
+                .unwrap_or_else(|e| panic!("output should serialize: {e}")),
+            Err(error) => error_response(&error),
+        };
+
+        self.respond_with(move |call| {
+            let matches = call.nsid == R::nsid()
+                && params.as_ref().is_none_or(|params| {
+                    *params == sorted_pairs(call.query.as_deref().unwrap_or_default())
+                });
+
+            matches.then(|| response.clone())
+        })
+    }
+
+    /// Registers a function which answers the requests for which it returns `Some`.
+    ///
+    /// This allows responses to depend on the request or on earlier requests, to set arbitrary
+    /// headers, and to answer requests other than XRPC requests, such as those of an OAuth
+    /// client. The function must not call other methods of this transport.
+    pub fn respond_with<F>(&self, f: F) -> &Self
+    where
+        F: Fn(&MockCall) -> Option<http::Response<Bytes>> + Send + Sync + 'static,
+    {
+        self.lock().rules.push(Arc::new(f));
+        self
+    }
+
+    /// Returns the requests received so far.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Records `request` and answers it with the first matching response.
+    fn answer(&self, request: http::Request<Bytes>) -> http::Response<Bytes> {
+        let (parts, body) = request.into_parts();
+        let nsid = parts
+            .uri
+            .path()
+            .rsplit_once("/xrpc/")
+            .map_or("", |(_, nsid)| nsid)
+            .to_owned();
+        let call = MockCall {
+            method: parts.method,
+            query: parts.uri.query().map(str::to_owned),
+            uri: parts.uri,
+            nsid,
+            headers: parts.headers,
+            body,
+        };
+
+        // The rules are applied without holding the lock, so that they may be called concurrently.
+        let rules = {
+            let mut state = self.lock();
+            state.calls.push(call.clone());
+            state.rules.clone()
+        };
+
+        rules
+            .iter()
+            .find_map(|rule| rule(&call))
+            .unwrap_or_else(|| {
+                error_response(
+                    &xrpc::Error::<()>::new(ErrorCode::MethodNotImplemented)
+                        .with_message(format!("no mock response for {}", call.uri.path())),
+                )
+            })
+    }
+}
+
+impl HttpTransport for MockTransport {
+    type Error = Infallible;
+
+    async fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Bytes>, Infallible> {
+        Ok(self.answer(request))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl BlockingTransport for MockTransport {
+    type Error = Infallible;
+
+    fn send(&self, request: http::Request<Bytes>) -> Result<http::Response<Bytes>, Infallible> {
+        Ok(self.answer(request))
+    }
+}
+
+fn error_response<E>(error: &xrpc::Error<E>) -> http::Response<Bytes>
+where
+    E: Serialize,
+{
+    let body = serde_json::to_vec(error).expect("error should serialize");
+
+    http::Response::builder()
+        .status(error.status())
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(body.into())
+        .expect("response should be valid")
+}
+
+fn sorted_pairs(query: &str) -> Vec<(String, String)> {
+    let mut pairs: Vec<_> = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    pairs.sort();
+    pairs
+}
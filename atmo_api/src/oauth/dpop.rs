@@ -0,0 +1,122 @@
+//! DPoP proofs.
+//!
+//! DPoP binds OAuth tokens to a key held by the client. Each request carries a short-lived JWT,
+//! signed by that key, which covers the request method and URL. See [RFC 9449].
+//!
+//! [RFC 9449]: https://datatracker.ietf.org/doc/html/rfc9449
+
+use std::fmt;
+
+use atmo_core::crypto::CryptoError;
+use data_encoding::BASE64URL_NOPAD;
+use p256::ecdsa::{signature::Signer as _, Signature, SigningKey};
+use rand_core::{OsRng, RngCore};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use url::Url;
+
+/// A P-256 key used to sign DPoP proofs with ES256.
+#[derive(Clone)]
+pub struct DpopKey {
+    key: SigningKey,
+}
+
+impl DpopKey {
+    /// Generates a new random key.
+    pub fn generate() -> DpopKey {
+        DpopKey {
+            key: SigningKey::random(&mut OsRng),
+        }
+    }
+
+    /// Creates a key from a 32-byte private key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<DpopKey, CryptoError> {
+        SigningKey::from_slice(bytes)
+            .map(|key| DpopKey { key })
+            .map_err(|_| CryptoError::InvalidKey)
+    }
+
+    /// Returns the 32-byte private key.
+    ///
+    /// Tokens are bound to this key, so it must be stored alongside them.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.key.to_bytes().to_vec()
+    }
+
+    /// Returns the public key as a JSON Web Key.
+    pub fn public_jwk(&self) -> serde_json::Value {
+        let point = self.key.verifying_key().to_encoded_point(false);
+
+        json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": BASE64URL_NOPAD.encode(point.x().expect("point should not be identity")),
+            "y": BASE64URL_NOPAD.encode(point.y().expect("point should not be compressed")),
+        })
+    }
+
+    /// Creates a DPoP proof for a request.
+    ///
+    /// `nonce` is the latest nonce provided by the server, if any. `access_token` must be given
+    /// when the request carries a DPoP-bound access token.
+    pub fn proof(
+        &self,
+        method: &http::Method,
+        url: &Url,
+        nonce: Option<&str>,
+        access_token: Option<&str>,
+    ) -> String {
+        // The URL is compared without its query and fragment.
+        let mut htu = url.clone();
+        htu.set_query(None);
+        htu.set_fragment(None);
+
+        let header = json!({
+            "typ": "dpop+jwt",
+            "alg": "ES256",
+            "jwk": self.public_jwk(),
+        });
+
+        let mut claims = json!({
+            "jti": random_string(16),
+            "htm": method.as_str(),
+            "htu": htu.as_str(),
+            "iat": jiff::Timestamp::now().as_second(),
+        });
+
+        if let Some(nonce) = nonce {
+            claims["nonce"] = nonce.into();
+        }
+
+        if let Some(token) = access_token {
+            claims["ath"] = BASE64URL_NOPAD.encode(&Sha256::digest(token)).into();
+        }
+
+        let signing_input = format!(
+            "{}.{}",
+            BASE64URL_NOPAD.encode(header.to_string().as_bytes()),
+            BASE64URL_NOPAD.encode(claims.to_string().as_bytes()),
+        );
+        let sig: Signature = self.key.sign(signing_input.as_bytes());
+
+        format!(
+            "{signing_input}.{}",
+            BASE64URL_NOPAD.encode(&sig.to_bytes())
+        )
+    }
+}
+
+impl fmt::Debug for DpopKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DpopKey")
+            .field("jwk", &self.public_jwk())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns `len` random bytes, base64url-encoded.
+pub(crate) fn random_string(len: usize) -> String {
+    let mut bytes = vec![0; len];
+    OsRng.fill_bytes(&mut bytes);
+    BASE64URL_NOPAD.encode(&bytes)
+}
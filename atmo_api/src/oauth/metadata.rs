@@ -0,0 +1,46 @@
+//! OAuth server metadata.
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Metadata published by a PDS at `/.well-known/oauth-protected-resource`.
+///
+/// See [RFC 9728].
+///
+/// [RFC 9728]: https://datatracker.ietf.org/doc/html/rfc9728
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ProtectedResourceMetadata {
+    /// The URL of the PDS.
+    pub resource: Url,
+    /// The issuers of the authorization servers for the PDS.
+    ///
+    /// ATProto requires exactly one.
+    #[serde(default)]
+    pub authorization_servers: Vec<String>,
+}
+
+/// Metadata published by an authorization server at `/.well-known/oauth-authorization-server`.
+///
+/// Only the fields used by ATProto clients are included. See [RFC 8414].
+///
+/// [RFC 8414]: https://datatracker.ietf.org/doc/html/rfc8414
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AuthorizationServerMetadata {
+    /// The issuer identifier of the server.
+    ///
+    /// This is compared exactly, so it is not parsed as a URL.
+    pub issuer: String,
+    pub authorization_endpoint: Url,
+    pub token_endpoint: Url,
+    pub pushed_authorization_request_endpoint: Url,
+    #[serde(default)]
+    pub require_pushed_authorization_requests: bool,
+    #[serde(default)]
+    pub authorization_response_iss_parameter_supported: bool,
+    #[serde(default)]
+    pub code_challenge_methods_supported: Vec<String>,
+    #[serde(default)]
+    pub dpop_signing_alg_values_supported: Vec<String>,
+    #[serde(default)]
+    pub scopes_supported: Vec<String>,
+}
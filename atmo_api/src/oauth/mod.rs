@@ -0,0 +1,816 @@
+//! OAuth client.
+//!
+//! ATProto uses OAuth 2.1 with Pushed Authorization Requests (PAR), PKCE and DPoP-bound tokens.
+//! An [`OAuthClient`] discovers the authorization server of a PDS, starts an authorization with
+//! [`OAuthClient::authorize`], and exchanges the resulting code for an [`OAuthSession`] with
+//! [`OAuthClient::callback`]. See the [OAuth] section of the ATProto specification.
+//!
+//! Only public clients are supported, which authenticate with a `client_id` alone.
+//!
+//! [OAuth]: https://atproto.com/specs/oauth
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use atmo_core::{did::DidUrl, xrpc::Request, DateTime, Did};
+use atmo_identity::{DidError, DidResolver};
+use bytes::Bytes;
+use data_encoding::BASE64URL_NOPAD;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+mod dpop;
+mod metadata;
+
+pub use dpop::DpopKey;
+pub use metadata::{AuthorizationServerMetadata, ProtectedResourceMetadata};
+
+use crate::{retry::RetryPolicy, RequestBuilder, Response, ResponseError, XrpcClient};
+
+/// The default scope requested by an [`OAuthClient`].
+pub const DEFAULT_SCOPE: &str = "atproto transition:generic";
+
+/// A builder for an [`OAuthClient`].
+pub struct OAuthClientBuilder {
+    client_id: String,
+    redirect_uri: Url,
+    scope: String,
+    client: Option<XrpcClient>,
+}
+
+impl OAuthClientBuilder {
+    /// Sets the scope requested during authorization.
+    ///
+    /// The default is [`DEFAULT_SCOPE`].
+    #[inline]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    /// Sets the client used to send requests.
+    ///
+    /// The client's transport and retry policy are used for all requests, including those sent to
+    /// the authorization server and the XRPC requests of each [`OAuthSession`].
+    #[inline]
+    pub fn client(mut self, client: XrpcClient) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Creates an `OAuthClient` with the configured options.
+    pub fn build(self) -> OAuthClient {
+        OAuthClient {
+            client_id: self.client_id,
+            redirect_uri: self.redirect_uri,
+            scope: self.scope,
+            client: self.client.unwrap_or_default(),
+        }
+    }
+}
+
+/// An ATProto OAuth client.
+#[derive(Clone, Debug)]
+pub struct OAuthClient {
+    client_id: String,
+    redirect_uri: Url,
+    scope: String,
+    client: XrpcClient,
+}
+
+impl OAuthClient {
+    /// Returns a builder for an `OAuthClient`.
+    ///
+    /// `client_id` is the URL of the client's metadata document, and `redirect_uri` must be one of
+    /// the redirect URIs it lists.
+    #[inline]
+    pub fn builder(client_id: impl Into<String>, redirect_uri: Url) -> OAuthClientBuilder {
+        OAuthClientBuilder {
+            client_id: client_id.into(),
+            redirect_uri,
+            scope: DEFAULT_SCOPE.into(),
+            client: None,
+        }
+    }
+
+    /// Fetches the metadata of the authorization server for the PDS at `pds`.
+    pub async fn discover(&self, pds: &Url) -> Result<AuthorizationServerMetadata, OAuthError> {
+        let url = pds
+            .join("/.well-known/oauth-protected-resource")
+            .map_err(|_| OAuthError::InvalidResponse("invalid PDS URL"))?;
+        let resource: ProtectedResourceMetadata = self.get_json(url).await?;
+
+        let [issuer] = resource.authorization_servers.as_slice() else {
+            return Err(OAuthError::InvalidResponse(
+                "PDS must have exactly one authorization server",
+            ));
+        };
+
+        self.server_metadata(issuer).await
+    }
+
+    /// Fetches the metadata of the authorization server identified by `issuer`.
+    pub async fn server_metadata(
+        &self,
+        issuer: &str,
+    ) -> Result<AuthorizationServerMetadata, OAuthError> {
+        let url = Url::parse(issuer)
+            .and_then(|url| url.join("/.well-known/oauth-authorization-server"))
+            .map_err(|_| OAuthError::InvalidResponse("invalid issuer"))?;
+        let metadata: AuthorizationServerMetadata = self.get_json(url).await?;
+
+        if metadata.issuer != issuer {
+            return Err(OAuthError::IssuerMismatch);
+        }
+
+        if !metadata
+            .code_challenge_methods_supported
+            .iter()
+            .any(|m| m == "S256")
+        {
+            return Err(OAuthError::Unsupported("PKCE S256"));
+        }
+
+        if !metadata
+            .dpop_signing_alg_values_supported
+            .iter()
+            .any(|alg| alg == "ES256")
+        {
+            return Err(OAuthError::Unsupported("DPoP ES256"));
+        }
+
+        Ok(metadata)
+    }
+
+    /// Starts authorizing access to an account on the PDS at `pds`.
+    ///
+    /// `login_hint` is the account's handle or DID, if known. Returns the URL to which the user
+    /// should be sent, and the state of the authorization, which must be kept until the user is
+    /// redirected back to the client.
+    pub async fn authorize(
+        &self,
+        pds: &Url,
+        login_hint: Option<&str>,
+    ) -> Result<(Url, PendingAuthorization), OAuthError> {
+        let server = self.discover(pds).await?;
+
+        let dpop_key = DpopKey::generate();
+        let state = dpop::random_string(16);
+        let verifier = dpop::random_string(32);
+        let challenge = BASE64URL_NOPAD.encode(&Sha256::digest(&verifier));
+
+        let mut form = vec![
+            ("client_id", self.client_id.as_str()),
+            ("response_type", "code"),
+            ("code_challenge", challenge.as_str()),
+            ("code_challenge_method", "S256"),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("scope", self.scope.as_str()),
+            ("state", state.as_str()),
+        ];
+
+        if let Some(hint) = login_hint {
+            form.push(("login_hint", hint));
+        }
+
+        let (par, nonce): (ParResponse, _) = post_form(
+            &self.client,
+            &server.pushed_authorization_request_endpoint,
+            &form,
+            &dpop_key,
+            None,
+        )
+        .await?;
+
+        let mut url = server.authorization_endpoint.clone();
+        url.query_pairs_mut()
+            .append_pair("client_id", &self.client_id)
+            .append_pair("request_uri", &par.request_uri);
+
+        let pending = PendingAuthorization {
+            state,
+            verifier,
+            dpop_key,
+            server,
+            pds: with_trailing_slash(pds.clone()),
+            nonce,
+        };
+
+        Ok((url, pending))
+    }
+
+    /// Completes an authorization with the parameters of the redirect back to the client.
+    ///
+    /// The returned session's [`sub`](TokenSet::sub) is the DID of the account. It is resolved
+    /// with `resolver`, and must name the PDS which was authorized as its own. That PDS was
+    /// already checked to name the authorization server in [`authorize`](Self::authorize).
+    pub async fn callback<D>(
+        &self,
+        pending: PendingAuthorization,
+        params: &CallbackParams,
+        resolver: &D,
+    ) -> Result<OAuthSession, OAuthError>
+    where
+        D: DidResolver,
+    {
+        if params.state != pending.state {
+            return Err(OAuthError::StateMismatch);
+        }
+
+        match &params.iss {
+            Some(iss) if *iss != pending.server.issuer => return Err(OAuthError::IssuerMismatch),
+            None if pending
+                .server
+                .authorization_response_iss_parameter_supported =>
+            {
+                return Err(OAuthError::IssuerMismatch)
+            }
+            _ => {}
+        }
+
+        let form = [
+            ("client_id", self.client_id.as_str()),
+            ("grant_type", "authorization_code"),
+            ("code", params.code.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("code_verifier", pending.verifier.as_str()),
+        ];
+
+        let (resp, nonce): (TokenResponse, _) = post_form(
+            &self.client,
+            &pending.server.token_endpoint,
+            &form,
+            &pending.dpop_key,
+            pending.nonce,
+        )
+        .await?;
+
+        let tokens = resp.into_tokens().map_err(OAuthError::InvalidResponse)?;
+
+        // The token response may be for an account hosted elsewhere.
+        let doc = resolver.resolve(&tokens.sub).await?;
+        let pds = doc.pds_service_url().cloned().map(with_trailing_slash);
+        if pds.as_ref() != Some(&pending.pds) {
+            return Err(OAuthError::IssuerMismatch);
+        }
+
+        Ok(OAuthSession {
+            client: self.clone(),
+            server: pending.server,
+            pds: pending.pds,
+            dpop_key: pending.dpop_key,
+            tokens: Mutex::new(tokens),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            server_nonce: Mutex::new(nonce),
+            resource_nonce: Mutex::new(None),
+        })
+    }
+
+    async fn get_json<T>(&self, url: Url) -> Result<T, OAuthError>
+    where
+        T: DeserializeOwned,
+    {
+        let resp = self
+            .client
+            .send_http(|| {
+                let mut req = http::Request::new(Bytes::new());
+                *req.uri_mut() = url.as_str().parse().expect("URL should be a valid URI");
+                req.headers_mut().insert(
+                    http::header::ACCEPT,
+                    http::HeaderValue::from_static("application/json"),
+                );
+                req
+            })
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(OAuthError::Status(resp.status()));
+        }
+
+        serde_json::from_slice(resp.body()).map_err(OAuthError::Json)
+    }
+}
+
+/// The state of an authorization in progress.
+#[derive(Debug)]
+pub struct PendingAuthorization {
+    /// The `state` parameter sent to the authorization server.
+    pub state: String,
+    verifier: String,
+    dpop_key: DpopKey,
+    server: AuthorizationServerMetadata,
+    pds: Url,
+    nonce: Option<String>,
+}
+
+/// The query parameters of the redirect back to the client.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CallbackParams {
+    pub code: String,
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+}
+
+/// The tokens of an [`OAuthSession`].
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TokenSet {
+    /// The DID of the account.
+    pub sub: Did,
+    /// The granted scope.
+    pub scope: String,
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+    /// When the access token expires, if known.
+    pub expires_at: Option<DateTime>,
+}
+
+impl TokenSet {
+    fn is_expired(&self) -> bool {
+        self.expires_at
+            .as_ref()
+            .is_some_and(|at| at.timestamp() <= jiff::Timestamp::now())
+    }
+}
+
+#[derive(Deserialize)]
+struct ParResponse {
+    request_uri: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: String,
+    #[serde(default)]
+    expires_in: Option<i64>,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    scope: String,
+    sub: Did,
+}
+
+impl TokenResponse {
+    fn into_tokens(self) -> Result<TokenSet, &'static str> {
+        if !self.token_type.eq_ignore_ascii_case("DPoP") {
+            return Err("token type must be DPoP");
+        }
+
+        if !self.scope.split(' ').any(|s| s == "atproto") {
+            return Err("scope must include atproto");
+        }
+
+        let expires_at = self.expires_in.and_then(|secs| {
+            jiff::Timestamp::now()
+                .checked_add(jiff::SignedDuration::from_secs(secs))
+                .ok()
+                .map(DateTime::from)
+        });
+
+        Ok(TokenSet {
+            sub: self.sub,
+            scope: self.scope,
+            access_token: self.access_token,
+            refresh_token: self.refresh_token,
+            expires_at,
+        })
+    }
+}
+
+/// An authorized OAuth session.
+///
+/// Requests made with [`OAuthSession::request`] are sent to the account's PDS with DPoP
+/// authorization. The access token is refreshed when it expires.
+#[derive(Debug)]
+pub struct OAuthSession {
+    client: OAuthClient,
+    server: AuthorizationServerMetadata,
+    pds: Url,
+    dpop_key: DpopKey,
+    tokens: Mutex<TokenSet>,
+    /// Held while refreshing the tokens, since refresh tokens are single-use.
+    refresh_lock: tokio::sync::Mutex<()>,
+    server_nonce: Mutex<Option<String>>,
+    resource_nonce: Mutex<Option<String>>,
+}
+
+impl OAuthSession {
+    /// Restores a session from its stored parts.
+    pub fn restore(
+        client: OAuthClient,
+        server: AuthorizationServerMetadata,
+        pds: Url,
+        dpop_key: DpopKey,
+        tokens: TokenSet,
+    ) -> Self {
+        OAuthSession {
+            client,
+            server,
+            pds: with_trailing_slash(pds),
+            dpop_key,
+            tokens: Mutex::new(tokens),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            server_nonce: Mutex::new(None),
+            resource_nonce: Mutex::new(None),
+        }
+    }
+
+    /// Returns the metadata of the authorization server.
+    #[inline]
+    pub fn server(&self) -> &AuthorizationServerMetadata {
+        &self.server
+    }
+
+    /// Returns the URL of the account's PDS.
+    #[inline]
+    pub fn pds(&self) -> &Url {
+        &self.pds
+    }
+
+    /// Returns the key to which the session's tokens are bound.
+    #[inline]
+    pub fn dpop_key(&self) -> &DpopKey {
+        &self.dpop_key
+    }
+
+    /// Returns the session's current tokens.
+    #[inline]
+    pub fn tokens(&self) -> TokenSet {
+        lock(&self.tokens).clone()
+    }
+
+    /// Refreshes the session's tokens.
+    pub async fn refresh(&self) -> Result<TokenSet, OAuthError> {
+        let _guard = self.refresh_lock.lock().await;
+        self.refresh_locked().await
+    }
+
+    /// Refreshes the tokens after `stale` was rejected, unless they have been refreshed already.
+    async fn refresh_stale(&self, stale: &TokenSet) -> Result<TokenSet, OAuthError> {
+        // Another request may have refreshed the tokens while this one waited for the lock.
+        let _guard = self.refresh_lock.lock().await;
+        let current = self.tokens();
+        if current.access_token != stale.access_token {
+            return Ok(current);
+        }
+
+        self.refresh_locked().await
+    }
+
+    /// Refreshes the session's tokens. The caller must hold `refresh_lock`.
+    async fn refresh_locked(&self) -> Result<TokenSet, OAuthError> {
+        let current = self.tokens();
+        let refresh_token = current
+            .refresh_token
+            .as_deref()
+            .ok_or(OAuthError::NoRefreshToken)?;
+
+        let form = [
+            ("client_id", self.client.client_id.as_str()),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ];
+
+        let nonce = lock(&self.server_nonce).clone();
+        let (resp, nonce): (TokenResponse, _) = post_form(
+            &self.client.client,
+            &self.server.token_endpoint,
+            &form,
+            &self.dpop_key,
+            nonce,
+        )
+        .await?;
+        *lock(&self.server_nonce) = nonce;
+
+        let tokens = resp.into_tokens().map_err(OAuthError::InvalidResponse)?;
+
+        if tokens.sub != current.sub {
+            return Err(OAuthError::InvalidResponse(
+                "refreshed session has a different sub",
+            ));
+        }
+
+        *lock(&self.tokens) = tokens.clone();
+        Ok(tokens)
+    }
+
+    /// Creates a builder for an XRPC request to the account's PDS.
+    pub fn request<R>(&self, req: R) -> OAuthRequestBuilder<'_, R>
+    where
+        R: Request,
+    {
+        OAuthRequestBuilder {
+            session: self,
+            inner: self.client.client.request(&self.pds, req),
+        }
+    }
+}
+
+/// A builder for an XRPC request sent by an [`OAuthSession`].
+pub struct OAuthRequestBuilder<'a, R> {
+    session: &'a OAuthSession,
+    inner: RequestBuilder<R>,
+}
+
+impl<R> OAuthRequestBuilder<'_, R>
+where
+    R: Request,
+{
+    /// Sets the query parameters of the request.
+    pub fn params(mut self, params: &R::Params) -> Result<Self, serde_urlencoded_xrpc::ser::Error> {
+        self.inner = self.inner.params(params)?;
+        Ok(self)
+    }
+
+    /// Sets the body of the request.
+    pub fn input(mut self, input: &R::Input) -> Result<Self, R::InputError> {
+        self.inner = self.inner.input(input)?;
+        Ok(self)
+    }
+
+    /// Sets the value of the `Content-Type` header for the request.
+    #[inline]
+    pub fn content_type(mut self, content_type: &str) -> Self {
+        self.inner = self.inner.content_type(content_type);
+        self
+    }
+
+    /// Asks the PDS to forward this request to another service, such as an AppView.
+    ///
+    /// See [`RequestBuilder::proxy`].
+    #[inline]
+    pub fn proxy(mut self, service: &DidUrl) -> Self {
+        self.inner = self.inner.proxy(service);
+        self
+    }
+
+    /// Sets the policy for retrying this request if it fails, overriding the client's policy.
+    ///
+    /// See [`RequestBuilder::retry_policy`].
+    #[inline]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.inner = self.inner.retry_policy(policy);
+        self
+    }
+
+    /// Sends the XRPC request with DPoP authorization.
+    ///
+    /// If the PDS requires a new DPoP nonce, the request is sent again with that nonce. If the PDS
+    /// rejects the access token, the tokens are refreshed and the request is sent again. Failed
+    /// requests are retried according to the retry policy, with a new DPoP proof for each attempt.
+    pub async fn send(self) -> Result<Response<R>, OAuthError> {
+        let session = self.session;
+
+        let mut tokens = session.tokens();
+        if tokens.is_expired() {
+            tokens = session.refresh_stale(&tokens).await?;
+        }
+
+        let mut attempt = 1;
+        let mut nonce_retried = false;
+        let mut refreshed = false;
+        loop {
+            let nonce = lock(&session.resource_nonce).clone();
+            let result = self
+                .inner
+                .clone()
+                .dpop_auth(&tokens.access_token, &session.dpop_key, nonce.as_deref())
+                .send_raw()
+                .await;
+
+            if let Ok((parts, bytes)) = &result {
+                update_nonce(&session.resource_nonce, &parts.headers);
+
+                if !nonce_retried && is_use_dpop_nonce(parts, bytes) {
+                    nonce_retried = true;
+                    continue;
+                }
+
+                if !refreshed && is_invalid_token(parts, bytes) {
+                    refreshed = true;
+                    tokens = session.refresh_stale(&tokens).await?;
+                    continue;
+                }
+            }
+
+            let Some(delay) = self.inner.retry_delay(attempt, &result) else {
+                let (parts, bytes) = result?;
+                return Ok(RequestBuilder::decode(parts, bytes)?);
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Sends a form to an authorization server endpoint with a DPoP proof, retrying once if the
+/// server requires a new nonce.
+///
+/// Returns the response, and the latest nonce.
+async fn post_form<T>(
+    client: &XrpcClient,
+    url: &Url,
+    form: &[(&str, &str)],
+    key: &DpopKey,
+    mut nonce: Option<String>,
+) -> Result<(T, Option<String>), OAuthError>
+where
+    T: DeserializeOwned,
+{
+    let body = Bytes::from(
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(form)
+            .finish(),
+    );
+    let mut retried = false;
+
+    loop {
+        let resp = client
+            .send_http(|| {
+                let proof = key.proof(&http::Method::POST, url, nonce.as_deref(), None);
+                http::Request::post(url.as_str())
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        "application/x-www-form-urlencoded",
+                    )
+                    .header("DPoP", proof)
+                    .body(body.clone())
+                    .expect("request should be valid")
+            })
+            .await?;
+
+        if let Some(new) = dpop_nonce(resp.headers()) {
+            nonce = Some(new);
+        }
+
+        let status = resp.status();
+        if status.is_success() {
+            let resp = serde_json::from_slice(resp.body()).map_err(OAuthError::Json)?;
+            return Ok((resp, nonce));
+        }
+
+        let Ok(error) = serde_json::from_slice::<ErrorResponse>(resp.body()) else {
+            return Err(OAuthError::Status(status));
+        };
+
+        if error.error == "use_dpop_nonce" && !retried {
+            retried = true;
+            continue;
+        }
+
+        return Err(OAuthError::Server(error));
+    }
+}
+
+fn dpop_nonce(headers: &http::HeaderMap) -> Option<String> {
+    headers
+        .get("DPoP-Nonce")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned)
+}
+
+fn update_nonce(nonce: &Mutex<Option<String>>, headers: &http::HeaderMap) {
+    if let Some(new) = dpop_nonce(headers) {
+        *lock(nonce) = Some(new);
+    }
+}
+
+fn is_use_dpop_nonce(parts: &http::response::Parts, bytes: &Bytes) -> bool {
+    if !parts.status.is_client_error() {
+        return false;
+    }
+
+    let in_header = parts
+        .headers
+        .get(http::header::WWW_AUTHENTICATE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("use_dpop_nonce"));
+
+    in_header
+        || serde_json::from_slice::<ErrorResponse>(bytes).is_ok_and(|e| e.error == "use_dpop_nonce")
+}
+
+fn is_invalid_token(parts: &http::response::Parts, bytes: &Bytes) -> bool {
+    if parts.status != http::StatusCode::UNAUTHORIZED {
+        return false;
+    }
+
+    let in_header = parts
+        .headers
+        .get(http::header::WWW_AUTHENTICATE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("invalid_token"));
+
+    in_header
+        || serde_json::from_slice::<ErrorResponse>(bytes).is_ok_and(|e| e.error == "invalid_token")
+}
+
+fn with_trailing_slash(mut url: Url) -> Url {
+    // Request URLs are resolved relative to this one.
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+
+    url
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An error response from an authorization server.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+}
+
+impl fmt::Display for ErrorResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error_description {
+            Some(desc) => write!(f, "{}: {desc}", self.error),
+            None => f.write_str(&self.error),
+        }
+    }
+}
+
+/// An error produced by an OAuth client or session.
+#[derive(Debug)]
+pub enum OAuthError {
+    /// The account's DID could not be resolved.
+    Did(DidError),
+    /// The server's response was invalid.
+    InvalidResponse(&'static str),
+    /// The authorization server's issuer did not match, or does not serve the account's PDS.
+    IssuerMismatch,
+    /// The body of the server's response could not be deserialized.
+    Json(serde_json::Error),
+    /// The session has no refresh token.
+    NoRefreshToken,
+    /// An error occurred while sending a request to the PDS or authorization server.
+    Response(ResponseError),
+    /// The authorization server returned an error.
+    Server(ErrorResponse),
+    /// The `state` parameter of the callback did not match.
+    StateMismatch,
+    /// The server returned an unexpected HTTP status.
+    Status(http::StatusCode),
+    /// The authorization server does not support a required feature.
+    Unsupported(&'static str),
+}
+
+impl From<DidError> for OAuthError {
+    #[inline]
+    fn from(e: DidError) -> Self {
+        OAuthError::Did(e)
+    }
+}
+
+impl From<ResponseError> for OAuthError {
+    #[inline]
+    fn from(e: ResponseError) -> Self {
+        OAuthError::Response(e)
+    }
+}
+
+impl fmt::Display for OAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OAuthError::Did(e) => fmt::Display::fmt(e, f),
+            OAuthError::InvalidResponse(msg) => write!(f, "invalid OAuth response: {msg}"),
+            OAuthError::IssuerMismatch => f.write_str("authorization server issuer mismatch"),
+            OAuthError::Json(e) => write!(f, "invalid OAuth response: {e}"),
+            OAuthError::NoRefreshToken => f.write_str("session has no refresh token"),
+            OAuthError::Response(e) => fmt::Display::fmt(e, f),
+            OAuthError::Server(e) => write!(f, "authorization server error: {e}"),
+            OAuthError::StateMismatch => f.write_str("OAuth state mismatch"),
+            OAuthError::Status(status) => write!(f, "unexpected HTTP status: {status}"),
+            OAuthError::Unsupported(feature) => {
+                write!(f, "authorization server does not support {feature}")
+            }
+        }
+    }
+}
+
+impl Error for OAuthError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            OAuthError::Did(e) => Some(e),
+            OAuthError::Json(e) => Some(e),
+            OAuthError::Response(e) => Some(e),
+            OAuthError::InvalidResponse(_)
+            | OAuthError::IssuerMismatch
+            | OAuthError::NoRefreshToken
+            | OAuthError::Server(_)
+            | OAuthError::StateMismatch
+            | OAuthError::Status(_)
+            | OAuthError::Unsupported(_) => None,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Streams over paginated XRPC methods.
+//!
+//! Methods which list many items, such as `app.bsky.graph.getFollows` or
+//! `com.atproto.repo.listRecords`, return them a page at a time. Each page has a cursor which is
+//! passed back to fetch the next page. The methods implement [`Paginated`], and
+//! [`RequestBuilder::pages`] and [`RequestBuilder::items`] follow the cursors automatically.
+
+use std::{error::Error, fmt, future::Future};
+
+use atmo_core::xrpc::{self, Paginated};
+use futures::{stream, Stream, TryStreamExt};
+
+use crate::{RequestBuilder, ResponseError};
+
+/// An error which occurred while fetching a page.
+#[derive(Debug)]
+pub enum PaginationError<E> {
+    /// The query parameters could not be serialized.
+    Params(serde_urlencoded_xrpc::ser::Error),
+    /// An error occurred while sending the request.
+    Response(ResponseError),
+    /// The server responded with an error.
+    Xrpc(xrpc::Error<E>),
+}
+
+impl<E> fmt::Display for PaginationError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaginationError::Params(e) => write!(f, "failed to serialize parameters: {e}"),
+            PaginationError::Response(e) => fmt::Display::fmt(e, f),
+            PaginationError::Xrpc(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E> Error for PaginationError<E>
+where
+    E: fmt::Debug + fmt::Display + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PaginationError::Params(e) => Some(e),
+            PaginationError::Response(e) => Some(e),
+            PaginationError::Xrpc(e) => Some(e),
+        }
+    }
+}
+
+impl<R> RequestBuilder<R>
+where
+    R: Paginated,
+{
+    /// Turns this request into a stream of pages, starting from the page selected by `params`.
+    ///
+    /// The stream ends after the first error, or when a page has no cursor or repeats the cursor
+    /// of the previous page.
+    pub fn pages(
+        self,
+        params: R::Params,
+    ) -> impl Stream<Item = Result<R::Output, PaginationError<R::RpcError>>> {
+        paginate::<R, _, _, _>(params, move |params| {
+            let builder = self.clone().params(params);
+            async move {
+                let resp = builder
+                    .map_err(PaginationError::Params)?
+                    .send()
+                    .await
+                    .map_err(PaginationError::Response)?;
+                resp.result.map_err(PaginationError::Xrpc)
+            }
+        })
+    }
+
+    /// Turns this request into a stream of the items of each page, starting from the page selected
+    /// by `params`.
+    ///
+    /// See [`pages`](Self::pages).
+    pub fn items(
+        self,
+        params: R::Params,
+    ) -> impl Stream<Item = Result<R::Item, PaginationError<R::RpcError>>> {
+        self.pages(params)
+            .map_ok(|page| stream::iter(R::into_items(page).into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+/// Follows the cursors of the paginated method `R`, starting from the page selected by `params`
+/// and fetching each page with `fetch`.
+///
+/// The stream ends after the first error, or when a page has no cursor or repeats the cursor of the
+/// previous page.
+pub(crate) fn paginate<R, E, F, Fut>(
+    params: R::Params,
+    fetch: F,
+) -> impl Stream<Item = Result<R::Output, E>>
+where
+    R: Paginated,
+    F: FnMut(&R::Params) -> Fut,
+    Fut: Future<Output = Result<R::Output, E>>,
+{
+    stream::unfold(Some((fetch, params, None)), |state| async move {
+        let (mut fetch, mut params, prev) = state?;
+
+        let output = match fetch(&params).await {
+            Ok(output) => output,
+            Err(e) => return Some((Err(e), None)),
+        };
+
+        let state = match R::cursor(&output) {
+            Some(cursor) if prev.as_deref() != Some(cursor) => {
+                let cursor = cursor.to_owned();
+                R::set_cursor(&mut params, Some(cursor.clone()));
+                Some((fetch, params, Some(cursor)))
+            }
+            _ => None,
+        };
+
+        Some((Ok(output), state))
+    })
+}
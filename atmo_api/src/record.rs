@@ -0,0 +1,359 @@
+//! Typed access to the records of a repository.
+//!
+//! The `com.atproto.repo.*` methods read and write records as untyped values. The methods added to
+//! [`Agent`] here convert between those values and types implementing [`Record`], such as
+//! [`Post`](crate::app::bsky::feed::Post), setting each record's `$type` and checking that the
+//! records returned by the server belong to the expected collection.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # async fn async_main() {
+//! use atmo_api::{app::bsky::feed::Post, Agent};
+//! use url::Url;
+//!
+//! let agent = Agent::new(Url::parse("https://atproto.example.com").unwrap());
+//! agent.login("username", "password").await.unwrap();
+//!
+//! let post = Post {
+//!     text: "Hello, world!".into(),
+//!     created_at: "2024-11-01T12:00:00Z".parse().unwrap(),
+//!     embed: None,
+//!     entities: None,
+//!     facets: None,
+//!     labels: None,
+//!     langs: None,
+//!     reply: None,
+//!     tags: None,
+//! };
+//!
+//! let created = agent.create_record(&post).await.unwrap();
+//! # }
+//! ```
+
+use std::{error::Error, fmt, str::FromStr};
+
+use atmo_core::{
+    record::{KeyType, Record},
+    xrpc::{self, Paginated, Request},
+    AtIdentifier, AtUri, CidString, Nsid, Unknown,
+};
+use futures::{future, stream, Stream, TryStreamExt};
+
+use crate::{
+    agent::{AgentError, SessionStore},
+    com::atproto::repo::{
+        create_record, delete_record, get_record, list_records, put_record, CreateRecord,
+        DeleteRecord, GetRecord, ListRecords, PutRecord, StrongRef,
+    },
+    pagination::paginate,
+    Agent,
+};
+
+/// A record of type `R`, along with its location in a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypedRecord<R> {
+    /// The AT-URI of the record.
+    pub uri: AtUri,
+    /// The CID of the record, if known.
+    pub cid: Option<CidString>,
+    /// The record.
+    pub value: R,
+}
+
+/// An error produced by the typed record methods of an [`Agent`].
+#[derive(Debug)]
+pub enum RecordError<E> {
+    /// An error occurred while sending the request.
+    Agent(AgentError),
+    /// The record key is not valid for the record type.
+    InvalidKey {
+        /// The NSID of the record type.
+        collection: &'static str,
+        /// The invalid record key.
+        rkey: String,
+    },
+    /// The record could not be converted to or from its untyped form.
+    Record(serde_json::Error),
+    /// The server returned a record from another collection.
+    WrongCollection {
+        /// The NSID of the record type.
+        expected: &'static str,
+        /// The collection or `$type` of the returned record, if any.
+        found: Option<String>,
+    },
+    /// The server responded with an error.
+    Xrpc(xrpc::Error<E>),
+}
+
+impl<E> From<AgentError> for RecordError<E> {
+    #[inline]
+    fn from(e: AgentError) -> Self {
+        RecordError::Agent(e)
+    }
+}
+
+impl<E> fmt::Display for RecordError<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Agent(e) => fmt::Display::fmt(e, f),
+            RecordError::InvalidKey { collection, rkey } => {
+                write!(f, "invalid record key for {collection}: {rkey}")
+            }
+            RecordError::Record(e) => write!(f, "invalid record: {e}"),
+            RecordError::WrongCollection {
+                expected,
+                found: Some(found),
+            } => write!(f, "expected a record in {expected}, found {found}"),
+            RecordError::WrongCollection {
+                expected,
+                found: None,
+            } => write!(f, "expected a record in {expected}, found none"),
+            RecordError::Xrpc(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E> Error for RecordError<E>
+where
+    E: fmt::Debug + fmt::Display + 'static,
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RecordError::Agent(e) => Some(e),
+            RecordError::Record(e) => Some(e),
+            RecordError::Xrpc(e) => Some(e),
+            RecordError::InvalidKey { .. } | RecordError::WrongCollection { .. } => None,
+        }
+    }
+}
+
+impl<S> Agent<S>
+where
+    S: SessionStore,
+{
+    /// Creates a record in the account's repository with `com.atproto.repo.createRecord`.
+    ///
+    /// The record key is generated by the PDS, unless the record type has a literal key such as
+    /// `self`.
+    pub async fn create_record<R>(
+        &self,
+        record: &R,
+    ) -> Result<StrongRef, RecordError<<CreateRecord as Request>::RpcError>>
+    where
+        R: Record,
+    {
+        let input = create_record::Input {
+            collection: collection::<R>(),
+            record: record.to_unknown().map_err(RecordError::Record)?,
+            repo: self.repo().ok_or(AgentError::NoSession)?,
+            rkey: match R::KEY {
+                KeyType::Literal(rkey) => Some(rkey.into()),
+                _ => None,
+            },
+            swap_commit: None,
+            validate: None,
+        };
+
+        let resp = self
+            .request(CreateRecord)
+            .input(&input)
+            .expect("createRecord input serialization should never fail")
+            .send()
+            .await?;
+        let output = resp.result.map_err(RecordError::Xrpc)?;
+
+        check_collection::<R, _>(output.uri.collection())?;
+        Ok(StrongRef {
+            cid: output.cid,
+            uri: output.uri,
+        })
+    }
+
+    /// Gets a record from a repository with `com.atproto.repo.getRecord`.
+    pub async fn get_record<R>(
+        &self,
+        repo: &AtIdentifier,
+        rkey: &str,
+    ) -> Result<TypedRecord<R>, RecordError<<GetRecord as Request>::RpcError>>
+    where
+        R: Record,
+    {
+        let params = get_record::Params {
+            cid: None,
+            collection: collection::<R>(),
+            repo: repo.clone(),
+            rkey: rkey.into(),
+        };
+
+        let resp = self
+            .request(GetRecord)
+            .params(&params)
+            .expect("getRecord params serialization should never fail")
+            .send()
+            .await?;
+        let output = resp.result.map_err(RecordError::Xrpc)?;
+
+        typed_record(output.uri, output.cid, &output.value)
+    }
+
+    /// Creates or replaces a record in the account's repository with `com.atproto.repo.putRecord`.
+    pub async fn put_record<R>(
+        &self,
+        rkey: &str,
+        record: &R,
+    ) -> Result<StrongRef, RecordError<<PutRecord as Request>::RpcError>>
+    where
+        R: Record,
+    {
+        check_key::<R, _>(rkey)?;
+
+        let input = put_record::Input {
+            collection: collection::<R>(),
+            record: record.to_unknown().map_err(RecordError::Record)?,
+            repo: self.repo().ok_or(AgentError::NoSession)?,
+            rkey: rkey.into(),
+            swap_commit: None,
+            swap_record: None,
+            validate: None,
+        };
+
+        let resp = self
+            .request(PutRecord)
+            .input(&input)
+            .expect("putRecord input serialization should never fail")
+            .send()
+            .await?;
+        let output = resp.result.map_err(RecordError::Xrpc)?;
+
+        check_collection::<R, _>(output.uri.collection())?;
+        Ok(StrongRef {
+            cid: output.cid,
+            uri: output.uri,
+        })
+    }
+
+    /// Deletes a record from the account's repository with `com.atproto.repo.deleteRecord`.
+    pub async fn delete_record<R>(
+        &self,
+        rkey: &str,
+    ) -> Result<(), RecordError<<DeleteRecord as Request>::RpcError>>
+    where
+        R: Record,
+    {
+        check_key::<R, _>(rkey)?;
+
+        let input = delete_record::Input {
+            collection: collection::<R>(),
+            repo: self.repo().ok_or(AgentError::NoSession)?,
+            rkey: rkey.into(),
+            swap_commit: None,
+            swap_record: None,
+        };
+
+        let resp = self
+            .request(DeleteRecord)
+            .input(&input)
+            .expect("deleteRecord input serialization should never fail")
+            .send()
+            .await?;
+
+        resp.result.map(|_| ()).map_err(RecordError::Xrpc)
+    }
+
+    /// Lists the records of type `R` in a repository with `com.atproto.repo.listRecords`.
+    ///
+    /// Pages are fetched as the stream is polled. The stream ends after the first error returned
+    /// while fetching a page. A record which cannot be converted to `R` is returned as an error,
+    /// and the stream continues with the next record.
+    pub fn list_records<'a, R>(
+        &'a self,
+        repo: &AtIdentifier,
+    ) -> impl Stream<Item = Result<TypedRecord<R>, RecordError<<ListRecords as Request>::RpcError>>> + 'a
+    where
+        R: Record + 'a,
+    {
+        let params = list_records::Params {
+            collection: collection::<R>(),
+            cursor: None,
+            limit: None,
+            repo: repo.clone(),
+            reverse: None,
+            rkey_end: None,
+            rkey_start: None,
+        };
+
+        paginate::<ListRecords, _, _, _>(params, move |params| {
+            let req = self
+                .request(ListRecords)
+                .params(params)
+                .expect("listRecords params serialization should never fail");
+            async move { req.send().await?.result.map_err(RecordError::Xrpc) }
+        })
+        .map_ok(|page| stream::iter(ListRecords::into_items(page).into_iter().map(Ok)))
+        .try_flatten()
+        .and_then(|record| future::ready(typed_record(record.uri, Some(record.cid), &record.value)))
+    }
+
+    /// Returns the DID of the account as a repository identifier.
+    fn repo(&self) -> Option<AtIdentifier> {
+        self.session().map(|session| AtIdentifier::Did(session.did))
+    }
+}
+
+fn collection<R>() -> Nsid
+where
+    R: Record,
+{
+    Nsid::from_str(R::NSID).expect("record NSID should be valid")
+}
+
+fn check_key<R, E>(rkey: &str) -> Result<(), RecordError<E>>
+where
+    R: Record,
+{
+    if R::KEY.accepts(rkey) {
+        Ok(())
+    } else {
+        Err(RecordError::InvalidKey {
+            collection: R::NSID,
+            rkey: rkey.into(),
+        })
+    }
+}
+
+fn check_collection<R, E>(found: Option<&str>) -> Result<(), RecordError<E>>
+where
+    R: Record,
+{
+    if found == Some(R::NSID) {
+        Ok(())
+    } else {
+        Err(RecordError::WrongCollection {
+            expected: R::NSID,
+            found: found.map(str::to_owned),
+        })
+    }
+}
+
+/// Converts a record returned by the server, checking its collection and `$type`.
+fn typed_record<R, E>(
+    uri: AtUri,
+    cid: Option<CidString>,
+    value: &Unknown,
+) -> Result<TypedRecord<R>, RecordError<E>>
+where
+    R: Record,
+{
+    check_collection::<R, E>(uri.collection())?;
+    if let Some(ty) = value.ty() {
+        check_collection::<R, E>(Some(ty.as_str()))?;
+    }
+
+    let value = R::from_unknown(value).map_err(RecordError::Record)?;
+
+    Ok(TypedRecord { uri, cid, value })
+}
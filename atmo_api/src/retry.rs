@@ -0,0 +1,304 @@
+//! Retries and rate limits.
+//!
+//! An [`XrpcClient`](crate::XrpcClient) makes a single attempt at each request by default. With a
+//! [`RetryPolicy`], failed requests are sent again after a delay:
+//!
+//! - Connection failures and `5xx` responses are retried with exponential backoff and jitter.
+//! - `429 Too Many Requests` responses are retried once the rate limit window resets, as reported
+//!   by the `ratelimit-reset` header, or after the delay given by the `Retry-After` header.
+//! - Only queries are retried, unless [`RetryPolicy::retry_procedures`] is set, since procedures
+//!   may not be idempotent.
+
+use std::{str::FromStr, time::Duration};
+
+use atmo_core::DateTime;
+use http::{header, HeaderMap, StatusCode};
+use rand_core::{OsRng, RngCore};
+
+/// The rate limit of a service, as reported in the headers of a response.
+///
+/// See the [Rate Limits] section of the Bluesky documentation.
+///
+/// [Rate Limits]: https://docs.bsky.app/docs/advanced-guides/rate-limits
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The number of requests allowed in the current window.
+    pub limit: u64,
+    /// The number of requests remaining in the current window.
+    pub remaining: u64,
+    /// When the current window ends.
+    pub reset: DateTime,
+    /// The policy of the rate limit, such as `3000;w=300` for 3000 requests per 300 seconds.
+    pub policy: Option<String>,
+}
+
+impl RateLimit {
+    /// Reads the rate limit from the `ratelimit-*` headers of a response.
+    ///
+    /// Returns `None` if the headers are missing or invalid.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let number = |name: &str| header(name).and_then(|v| u64::from_str(v.trim()).ok());
+
+        let reset = i64::try_from(number("ratelimit-reset")?).ok()?;
+
+        Some(RateLimit {
+            limit: number("ratelimit-limit")?,
+            remaining: number("ratelimit-remaining")?,
+            reset: DateTime::from_unix_seconds(reset)?,
+            policy: header("ratelimit-policy").map(str::to_owned),
+        })
+    }
+}
+
+/// When and how often to retry failed requests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retry_procedures: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy which never retries.
+    pub const fn never() -> Self {
+        RetryPolicy {
+            max_retries: 0,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            retry_procedures: false,
+        }
+    }
+
+    /// Sets the maximum number of retries of a request.
+    ///
+    /// [`RetryPolicy::default()`] allows 3 retries, and [`RetryPolicy::never()`] allows none.
+    #[inline]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Sets the delay before the first retry, which doubles after each retry.
+    ///
+    /// The actual delay is randomized between half and all of this delay. The default is 500
+    /// milliseconds.
+    #[inline]
+    pub fn base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Sets the maximum delay before a retry.
+    ///
+    /// A rate-limited request whose window resets later than this is not retried. The default is
+    /// 30 seconds.
+    #[inline]
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets whether procedures are retried.
+    ///
+    /// Procedures may have side effects, so they are not retried by default. Only enable this for
+    /// procedures which are safe to send more than once.
+    #[inline]
+    pub fn retry_procedures(mut self, retry: bool) -> Self {
+        self.retry_procedures = retry;
+        self
+    }
+
+    /// Returns whether a request with HTTP method `method` may be retried at all.
+    pub(crate) fn allows(&self, method: &http::Method) -> bool {
+        self.max_retries > 0 && (*method == http::Method::GET || self.retry_procedures)
+    }
+
+    /// Returns how long to wait before sending a request with HTTP method `method` again, after
+    /// attempt `attempt` (starting at 1) produced `response`, or `None` if it should not be retried.
+    ///
+    /// `response` is the status and headers of the response, or `None` if none was received.
+    pub(crate) fn delay_after(
+        &self,
+        method: &http::Method,
+        attempt: u32,
+        response: Option<(StatusCode, &HeaderMap)>,
+    ) -> Option<Duration> {
+        if !self.allows(method) {
+            return None;
+        }
+
+        match response {
+            Some((status, headers)) => self.delay(attempt, Some(status), Some(headers)),
+            None => self.delay(attempt, None, None),
+        }
+    }
+
+    /// Returns how long to wait before sending attempt `attempt` (starting at 1) again, after it
+    /// failed with `status`, or `None` if it should not be retried.
+    ///
+    /// `status` is `None` if no response was received.
+    pub(crate) fn delay(
+        &self,
+        attempt: u32,
+        status: Option<StatusCode>,
+        headers: Option<&HeaderMap>,
+    ) -> Option<Duration> {
+        if attempt > self.max_retries {
+            return None;
+        }
+
+        match status {
+            Some(StatusCode::TOO_MANY_REQUESTS) => {
+                let wait = headers
+                    .and_then(|headers| {
+                        RateLimit::from_headers(headers)
+                            .map(|limit| until(limit.reset.timestamp()))
+                            .or_else(|| retry_after(headers))
+                    })
+                    .unwrap_or_else(|| self.backoff(attempt));
+
+                (wait <= self.max_delay).then_some(wait)
+            }
+            Some(StatusCode::NOT_IMPLEMENTED) => None,
+            Some(status) if !status.is_server_error() => None,
+            _ => Some(self.backoff(attempt)),
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.max_delay);
+
+        // Full jitter over the upper half of the delay.
+        let jitter = OsRng.next_u64() as f64 / u64::MAX as f64;
+        delay.mul_f64(0.5 + jitter / 2.0)
+    }
+}
+
+/// Reads the delay from the `Retry-After` header of a response, which is either a number of
+/// seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = u64::from_str(value) {
+        return Some(Duration::from_secs(secs));
+    }
+
+    jiff::fmt::rfc2822::DateTimeParser::new()
+        .parse_timestamp(value)
+        .ok()
+        .map(until)
+}
+
+/// Returns the time remaining until `time`, or zero if it has passed.
+fn until(time: jiff::Timestamp) -> Duration {
+    let wait = time.as_millisecond() - jiff::Timestamp::now().as_millisecond();
+    Duration::from_millis(wait.max(0) as u64)
+}
+
+impl Default for RetryPolicy {
+    /// Creates a policy which retries queries up to 3 times.
+    #[inline]
+    fn default() -> Self {
+        RetryPolicy::never().max_retries(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderValue;
+
+    use super::*;
+
+    fn headers(reset: i64) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit-limit", HeaderValue::from_static("3000"));
+        headers.insert("ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("ratelimit-reset", reset.to_string().parse().unwrap());
+        headers.insert("ratelimit-policy", HeaderValue::from_static("3000;w=300"));
+        headers
+    }
+
+    #[test]
+    fn rate_limit() {
+        let limit = RateLimit::from_headers(&headers(1_700_000_000)).unwrap();
+        assert_eq!(limit.limit, 3000);
+        assert_eq!(limit.remaining, 0);
+        assert_eq!(limit.reset.timestamp().as_second(), 1_700_000_000);
+        assert_eq!(limit.policy.as_deref(), Some("3000;w=300"));
+
+        assert_eq!(RateLimit::from_headers(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn delays() {
+        let policy = RetryPolicy::default();
+        assert!(policy.allows(&http::Method::GET));
+        assert!(!policy.allows(&http::Method::POST));
+        assert!(policy.retry_procedures(true).allows(&http::Method::POST));
+        assert!(!RetryPolicy::never().allows(&http::Method::GET));
+
+        for attempt in 1..=3 {
+            let delay = policy.delay(attempt, None, None).unwrap();
+            let max = Duration::from_millis(500 << (attempt - 1));
+            assert!(delay >= max / 2 && delay <= max, "{delay:?}");
+        }
+        assert_eq!(policy.delay(4, None, None), None);
+
+        let status = |s| Some(StatusCode::from_u16(s).unwrap());
+        assert!(policy.delay(1, status(503), None).is_some());
+        assert_eq!(policy.delay(1, status(501), None), None);
+        assert_eq!(policy.delay(1, status(400), None), None);
+
+        let now = jiff::Timestamp::now().as_second();
+        let wait = policy
+            .delay(1, status(429), Some(&headers(now + 10)))
+            .unwrap();
+        assert!(wait > Duration::from_secs(8) && wait <= Duration::from_secs(10));
+        assert_eq!(policy.delay(1, status(429), Some(&headers(now + 60))), None);
+        assert_eq!(
+            policy.delay(1, status(429), Some(&headers(now - 10))),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn retry_after() {
+        let policy = RetryPolicy::default();
+        let status = Some(StatusCode::TOO_MANY_REQUESTS);
+        let retry_after = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::RETRY_AFTER, value.parse().unwrap());
+            headers
+        };
+
+        assert_eq!(
+            policy.delay(1, status, Some(&retry_after("5"))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(policy.delay(1, status, Some(&retry_after("60"))), None);
+
+        let date = jiff::Timestamp::now() + jiff::SignedDuration::from_secs(10);
+        let date = date.strftime("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let wait = policy.delay(1, status, Some(&retry_after(&date))).unwrap();
+        assert!(wait > Duration::from_secs(8) && wait <= Duration::from_secs(10));
+
+        assert_eq!(
+            policy.delay(
+                1,
+                status,
+                Some(&retry_after("Sun, 06 Nov 1994 08:49:37 GMT"))
+            ),
+            Some(Duration::ZERO)
+        );
+
+        // The rate limit headers take precedence.
+        let mut headers = headers(jiff::Timestamp::now().as_second() + 60);
+        headers.insert(header::RETRY_AFTER, "5".parse().unwrap());
+        assert_eq!(policy.delay(1, status, Some(&headers)), None);
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use atmo_core::{did::DidUrl, xrpc::ErrorCode};
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    agent::{AgentBuilder, AgentError, MemorySessionStore, Session},
+    app::bsky::actor::GetPreferences,
+    mock::{MockCall, MockTransport},
+    tests::response,
+    Agent, XrpcClient,
+};
+
+fn session(n: u32) -> String {
+    json!({
+        "accessJwt": format!("access-{n}"),
+        "refreshJwt": format!("refresh-{n}"),
+        "did": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+        "handle": "alice.test",
+    })
+    .to_string()
+}
+
+fn expired() -> (u16, String) {
+    (400, json!({"error": "ExpiredToken"}).to_string())
+}
+
+fn authorization(call: &MockCall) -> &str {
+    call.headers
+        .get("authorization")
+        .map_or("", |value| value.to_str().unwrap())
+}
+
+/// Mocks a PDS which accepts `valid` as its only access token, and issues the second session on
+/// refresh.
+fn pds(valid: Arc<Mutex<String>>) -> MockTransport {
+    let mock = MockTransport::new();
+    mock.respond_with(move |call| {
+        let auth = authorization(call);
+
+        let (status, body) = match call.nsid.as_str() {
+            "com.atproto.server.createSession" => (200, session(1)),
+            "com.atproto.server.refreshSession" if auth == "Bearer refresh-1" => (200, session(2)),
+            "com.atproto.server.refreshSession" => expired(),
+            "com.atproto.server.deleteSession" => (200, String::new()),
+            "app.bsky.actor.getPreferences" if auth == *valid.lock().unwrap() => {
+                (200, json!({"preferences": []}).to_string())
+            }
+            "app.bsky.actor.getPreferences" if auth == "Bearer access-0" => {
+                (401, json!({"error": "InvalidToken"}).to_string())
+            }
+            _ => expired(),
+        };
+        Some(response(status, &[], &body))
+    });
+    mock
+}
+
+fn agent(url: &str, mock: &MockTransport) -> AgentBuilder<MemorySessionStore> {
+    Agent::builder(Url::parse(url).unwrap()).client(XrpcClient::with_transport(mock.clone()))
+}
+
+#[tokio::test]
+async fn login_and_request() {
+    let mock = pds(Arc::new(Mutex::new("Bearer access-1".into())));
+    let sessions = Arc::new(Mutex::new(Vec::new()));
+    let log = sessions.clone();
+    let agent = agent("https://pds.example.com/", &mock)
+        .on_session(move |s: &Session| log.lock().unwrap().push(s.access_jwt.clone()))
+        .build();
+
+    assert!(matches!(
+        agent.request(GetPreferences).send().await,
+        Err(AgentError::NoSession)
+    ));
+
+    let session = agent.login("alice.test", "hunter2").await.unwrap();
+    assert_eq!(session.access_jwt, "access-1");
+    assert_eq!(agent.session(), Some(session));
+    assert_eq!(*sessions.lock().unwrap(), ["access-1"]);
+
+    let resp = agent.request(GetPreferences).send().await.unwrap();
+    assert!(resp.result().is_ok());
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2);
+    assert!(String::from_utf8_lossy(&calls[0].body).contains("hunter2"));
+    assert_eq!(calls[1].method, http::Method::GET);
+    assert_eq!(authorization(&calls[1]), "Bearer access-1");
+
+    agent.logout().await.unwrap();
+    assert_eq!(agent.session(), None);
+    assert_eq!(authorization(&mock.calls()[2]), "Bearer refresh-1");
+}
+
+#[tokio::test]
+async fn refresh_expired_token() {
+    let valid = Arc::new(Mutex::new("Bearer access-1".into()));
+    let mock = pds(valid.clone());
+    let sessions = Arc::new(Mutex::new(Vec::new()));
+    let log = sessions.clone();
+    let agent = agent("https://pds.example.com/", &mock)
+        .on_session(move |s: &Session| log.lock().unwrap().push(s.refresh_jwt.clone()))
+        .build();
+
+    agent.login("alice.test", "hunter2").await.unwrap();
+
+    // The access token expires, so the session is refreshed and the request is retried.
+    *valid.lock().unwrap() = "Bearer access-2".into();
+    let resp = agent.request(GetPreferences).send().await.unwrap();
+    assert!(resp.result().is_ok());
+    assert_eq!(agent.session().unwrap().access_jwt, "access-2");
+    assert_eq!(*sessions.lock().unwrap(), ["refresh-1", "refresh-2"]);
+
+    let auth: Vec<_> = mock
+        .calls()
+        .iter()
+        .skip(1)
+        .map(|call| authorization(call).to_owned())
+        .collect();
+    assert_eq!(
+        auth,
+        ["Bearer access-1", "Bearer refresh-1", "Bearer access-2"]
+    );
+
+    // Now the refresh token is rejected too.
+    *valid.lock().unwrap() = "Bearer access-3".into();
+    assert!(matches!(
+        agent.request(GetPreferences).send().await,
+        Err(AgentError::Refresh(e)) if e.error == ErrorCode::ExpiredToken
+    ));
+}
+
+#[tokio::test]
+async fn concurrent_refresh() {
+    let valid = Arc::new(Mutex::new("Bearer access-1".into()));
+    let mock = pds(valid.clone());
+    let agent = agent("https://pds.example.com/", &mock).build();
+
+    agent.login("alice.test", "hunter2").await.unwrap();
+
+    // Both requests are rejected, but only one refreshes the session.
+    *valid.lock().unwrap() = "Bearer access-2".into();
+    let (a, b) = tokio::join!(
+        agent.request(GetPreferences).send(),
+        agent.request(GetPreferences).send(),
+    );
+    assert!(a.unwrap().result().is_ok());
+    assert!(b.unwrap().result().is_ok());
+
+    let refreshes = mock
+        .calls()
+        .iter()
+        .filter(|call| call.nsid == "com.atproto.server.refreshSession")
+        .count();
+    assert_eq!(refreshes, 1);
+}
+
+#[tokio::test]
+async fn other_errors() {
+    let mock = pds(Arc::new(Mutex::new("Bearer access-1".into())));
+    let agent = agent("https://pds.example.com/", &mock).build();
+
+    agent.resume(serde_json::from_str(&session(0)).unwrap());
+
+    // Only expired tokens are refreshed.
+    let resp = agent.request(GetPreferences).send().await.unwrap();
+    assert_eq!(resp.http_status(), 401);
+    assert_eq!(resp.result().unwrap_err().error, ErrorCode::InvalidToken);
+    assert_eq!(mock.calls().len(), 1);
+}
+
+#[tokio::test]
+async fn pds_routing() {
+    let mock = MockTransport::new();
+    mock.respond_with(|call| {
+        let (status, body) = match (call.uri.host(), call.uri.path()) {
+            (Some("entryway.example.com"), _) => {
+                let mut session: serde_json::Value = serde_json::from_str(&session(1)).unwrap();
+                session["didDoc"] = json!({
+                    "id": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+                    "service": [{
+                        "id": "#atproto_pds",
+                        "type": "AtprotoPersonalDataServer",
+                        "serviceEndpoint": "https://pds.example.com/base",
+                    }],
+                });
+                (200, session.to_string())
+            }
+            (Some("pds.example.com"), "/base/xrpc/app.bsky.actor.getPreferences") => {
+                (200, json!({"preferences": []}).to_string())
+            }
+            _ => expired(),
+        };
+        Some(response(status, &[], &body))
+    });
+
+    let agent = agent("https://entryway.example.com/", &mock).build();
+    let session = agent.login("alice.test", "hunter2").await.unwrap();
+    assert_eq!(
+        session.pds.unwrap().as_str(),
+        "https://pds.example.com/base/"
+    );
+
+    let appview = DidUrl::from_str("did:web:api.bsky.app#bsky_appview").unwrap();
+    let resp = agent
+        .request(GetPreferences)
+        .proxy(&appview)
+        .send()
+        .await
+        .unwrap();
+    assert!(resp.result().is_ok());
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].uri.host(), Some("entryway.example.com"));
+    assert_eq!(calls[1].uri.host(), Some("pds.example.com"));
+    assert_eq!(
+        calls[1].headers["atproto-proxy"],
+        "did:web:api.bsky.app#bsky_appview"
+    );
+}
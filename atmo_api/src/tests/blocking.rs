@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use atmo_core::xrpc::{self, ErrorCode};
+use url::Url;
+
+use crate::{
+    blocking,
+    com::atproto::identity::{resolve_handle, ResolveHandle},
+    com::atproto::server::{create_session, CreateSession},
+    mock::MockTransport,
+    retry::RetryPolicy,
+    XrpcClient,
+};
+
+const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+
+fn url() -> Url {
+    Url::parse("https://pds.example.com/").unwrap()
+}
+
+#[test]
+fn blocking_responses() {
+    let mock = MockTransport::new();
+    let params = resolve_handle::Params {
+        handle: "alice.test".parse().unwrap(),
+    };
+    mock.respond(
+        ResolveHandle,
+        Some(&params),
+        Ok(resolve_handle::Output {
+            did: DID.parse().unwrap(),
+        }),
+    );
+
+    let resp = blocking::XrpcClient::with_transport(mock.clone())
+        .request(&url(), ResolveHandle)
+        .params(&params)
+        .unwrap()
+        .send()
+        .unwrap();
+    assert_eq!(resp.result().unwrap().did.as_str(), DID);
+
+    let resp = blocking::XrpcClient::with_transport(mock.clone())
+        .request(&url(), CreateSession)
+        .send()
+        .unwrap();
+    assert_eq!(
+        resp.result().unwrap_err().error,
+        ErrorCode::MethodNotImplemented
+    );
+}
+
+#[tokio::test]
+async fn blocking_matches_async() {
+    let mock = MockTransport::new();
+    let input = create_session::Input {
+        identifier: "alice.test".into(),
+        password: "hunter2".into(),
+        auth_factor_token: None,
+    };
+    let proxy = "did:web:api.bsky.app#bsky_appview".parse().unwrap();
+
+    XrpcClient::with_transport(mock.clone())
+        .request(&url(), CreateSession)
+        .input(&input)
+        .unwrap()
+        .proxy(&proxy)
+        .admin_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    blocking::XrpcClient::with_transport(mock.clone())
+        .request(&url(), CreateSession)
+        .input(&input)
+        .unwrap()
+        .proxy(&proxy)
+        .admin_auth("secret")
+        .send()
+        .unwrap();
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].method, calls[1].method);
+    assert_eq!(calls[0].nsid, calls[1].nsid);
+    assert_eq!(calls[0].query, calls[1].query);
+    assert_eq!(calls[0].headers, calls[1].headers);
+    assert_eq!(calls[0].body, calls[1].body);
+}
+
+#[test]
+fn blocking_retry() {
+    let mock = MockTransport::new();
+    mock.respond(
+        ResolveHandle,
+        None,
+        Err(xrpc::Error::new(ErrorCode::InternalServerError)),
+    );
+
+    let policy = RetryPolicy::default()
+        .max_retries(2)
+        .base_delay(Duration::from_millis(1));
+    let resp = blocking::XrpcClient::with_transport(mock.clone())
+        .retry_policy(policy)
+        .request(&url(), ResolveHandle)
+        .params(&resolve_handle::Params {
+            handle: "alice.test".parse().unwrap(),
+        })
+        .unwrap()
+        .send()
+        .unwrap();
+    assert_eq!(resp.http_status(), 500);
+    assert_eq!(mock.calls().len(), 3);
+}
@@ -1 +1,3 @@
+mod repo;
 mod server;
+mod sync;
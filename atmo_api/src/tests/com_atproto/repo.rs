@@ -0,0 +1,137 @@
+use atmo_core::xrpc::{ErrorCode, Request};
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::{
+    com::atproto::repo::{list_records, ListRecords, UploadBlob},
+    mock::MockTransport,
+    pagination::PaginationError,
+    tests::response,
+    XrpcClient,
+};
+
+fn url() -> Url {
+    Url::parse("https://pds.example.com/").unwrap()
+}
+
+#[tokio::test]
+async fn upload_blob_content_type() {
+    assert_eq!(UploadBlob::input_content_type(), Some("*/*"));
+    assert_eq!(UploadBlob::output_content_type(), Some("application/json"));
+
+    let mock = MockTransport::new();
+    mock.respond_with(|call| {
+        let blob = json!({
+            "blob": {
+                "$type": "blob",
+                "ref": { "$link": "bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy" },
+                "mimeType": call.headers["content-type"].to_str().unwrap(),
+                "size": call.body.len(),
+            },
+        });
+        Some(response(200, &[], &blob.to_string()))
+    });
+
+    let resp = XrpcClient::with_transport(mock.clone())
+        .request(&url(), UploadBlob)
+        .input(&Bytes::from_static(b"image data"))
+        .unwrap()
+        .content_type("image/png")
+        .send()
+        .await
+        .unwrap();
+
+    let calls = mock.calls();
+    let values: Vec<_> = calls[0]
+        .headers
+        .get_all("content-type")
+        .iter()
+        .map(|value| value.to_str().unwrap())
+        .collect();
+    assert_eq!(values, ["image/png"]);
+    assert!(resp.result().is_ok());
+}
+
+fn records(rkeys: &[u32]) -> Vec<Value> {
+    rkeys
+        .iter()
+        .map(|rkey| {
+            json!({
+                "uri": format!("at://did:plc:ewvi7nxzyoun6zhxrhs64oiz/app.bsky.feed.post/{rkey}"),
+                "cid": "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm",
+                "value": {},
+            })
+        })
+        .collect()
+}
+
+fn params() -> list_records::Params {
+    list_records::Params {
+        collection: "app.bsky.feed.post".parse().unwrap(),
+        cursor: None,
+        limit: Some(2),
+        repo: "alice.test".parse().unwrap(),
+        reverse: None,
+        rkey_end: None,
+        rkey_start: None,
+    }
+}
+
+#[tokio::test]
+async fn list_records_pages() {
+    let mock = MockTransport::new();
+    mock.respond_with(|call| {
+        let query = call.query.as_deref().unwrap();
+        let cursor = url::form_urlencoded::parse(query.as_bytes())
+            .find(|(name, _)| name == "cursor")
+            .map(|(_, value)| value.into_owned());
+
+        // The last page repeats its cursor.
+        let (records, cursor) = match cursor.as_deref() {
+            None => (records(&[1, 2]), "a"),
+            Some("a") => (records(&[3]), "b"),
+            _ => (records(&[]), "b"),
+        };
+        let body = json!({ "records": records, "cursor": cursor });
+        Some(response(200, &[], &body.to_string()))
+    });
+
+    let items: Vec<_> = XrpcClient::with_transport(mock.clone())
+        .request(&url(), ListRecords)
+        .items(params())
+        .try_collect()
+        .await
+        .unwrap();
+
+    let rkeys: Vec<_> = items.iter().map(|r| r.uri.rkey().unwrap()).collect();
+    assert_eq!(rkeys, ["1", "2", "3"]);
+    assert_eq!(mock.calls().len(), 3);
+}
+
+#[tokio::test]
+async fn list_records_error() {
+    let mock = MockTransport::new();
+    mock.respond_with(|call| {
+        let (status, body) = if call.query.as_deref().unwrap().contains("cursor=") {
+            (400, json!({ "error": "InvalidRequest" }))
+        } else {
+            (200, json!({ "records": records(&[1]), "cursor": "a" }))
+        };
+        Some(response(status, &[], &body.to_string()))
+    });
+
+    let pages: Vec<_> = XrpcClient::with_transport(mock)
+        .request(&url(), ListRecords)
+        .pages(params())
+        .collect()
+        .await;
+
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].as_ref().unwrap().records.len(), 1);
+    assert!(matches!(
+        &pages[1],
+        Err(PaginationError::Xrpc(e)) if e.error == ErrorCode::InvalidRequest
+    ));
+}
@@ -1,9 +1,18 @@
 use std::str::FromStr;
 
-use atmo_core::{Did, Handle};
+use atmo_core::{
+    xrpc::{self, ErrorCode},
+    Did, Handle,
+};
 use serde_json::json;
+use url::Url;
 
-use crate::com::atproto::server::create_session;
+use crate::{
+    com::atproto::server::{create_session, CreateSession},
+    mock::MockTransport,
+    tests::response,
+    XrpcClient,
+};
 
 #[test]
 fn create_session_input() {
@@ -54,3 +63,52 @@ fn create_session_output() {
     let deserialized: create_session::Output = serde_json::from_value(serialized).unwrap();
     assert_eq!(&output, &deserialized);
 }
+
+#[test]
+fn create_session_error() {
+    let errors = [
+        (
+            "AuthFactorTokenRequired",
+            ErrorCode::Method(create_session::Error::AuthFactorTokenRequired),
+        ),
+        ("RateLimitExceeded", ErrorCode::RateLimitExceeded),
+        (
+            "SomethingElse",
+            ErrorCode::Method(create_session::Error::Other("SomethingElse".into())),
+        ),
+    ];
+
+    for (code, expected) in errors {
+        let error: xrpc::Error<create_session::Error> =
+            serde_json::from_value(json!({ "error": code })).unwrap();
+        assert_eq!(error.error, expected);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({ "error": code })
+        );
+    }
+}
+
+#[tokio::test]
+async fn error_without_body() {
+    let mock = MockTransport::new();
+    mock.respond_with(|_| Some(response(429, &[], "slow down")));
+
+    let input = create_session::Input {
+        identifier: "alice.test".into(),
+        password: "hunter2".into(),
+        auth_factor_token: None,
+    };
+    let url = Url::parse("https://pds.example.com/").unwrap();
+    let resp = XrpcClient::with_transport(mock)
+        .request(&url, CreateSession)
+        .input(&input)
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+
+    let error = resp.result().unwrap_err();
+    assert_eq!(error.error, ErrorCode::RateLimitExceeded);
+    assert_eq!(error.status(), resp.http_status());
+}
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use atmo_core::{event_stream::Frame, xrpc::Subscription, DateTime, Did};
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    com::atproto::sync::{
+        get_repo,
+        subscribe_repos::{Error, Identity, Message, Params},
+        GetRepo, SubscribeRepos,
+    },
+    mock::MockTransport,
+    tests::response,
+    ContentTypeError, ResponseError, XrpcClient,
+};
+
+type SubscribeReposFrame = Frame<Message, Error>;
+
+#[test]
+fn subscribe_repos_params() {
+    let params = Params { cursor: Some(42) };
+
+    let query = SubscribeRepos::serialize_params(&params).unwrap();
+    assert_eq!(query, "cursor=42");
+
+    let deserialized = SubscribeRepos::deserialize_params(&query).unwrap();
+    assert_eq!(params, deserialized);
+}
+
+#[test]
+fn subscribe_repos_message() {
+    let time_str = "2024-11-20T12:00:00.000Z";
+
+    let message = Message::Identity(Identity {
+        did: Did::from_str("did:plc:z72i7hdynmk6r22z27h6tvur").unwrap(),
+        handle: None,
+        seq: 1234,
+        time: DateTime::from_str(time_str).unwrap(),
+    });
+
+    let serialized = serde_json::to_value(&message).unwrap();
+
+    let expected = json!({
+        "$type": "com.atproto.sync.subscribeRepos#identity",
+        "did": "did:plc:z72i7hdynmk6r22z27h6tvur",
+        "seq": 1234,
+        "time": time_str,
+    });
+
+    assert_eq!(serialized, expected);
+
+    let deserialized: Message = serde_json::from_value(serialized).unwrap();
+    assert_eq!(message, deserialized);
+}
+
+#[test]
+fn subscribe_repos_error() {
+    let error: Error = serde_json::from_value(json!("ConsumerTooSlow")).unwrap();
+    assert_eq!(error, Error::ConsumerTooSlow);
+
+    let other: Error = serde_json::from_value(json!("SomethingElse")).unwrap();
+    assert_eq!(other, Error::Other("SomethingElse".into()));
+}
+
+#[test]
+fn subscribe_repos_commit_frame() {
+    let commit = json!({
+        "$type": "com.atproto.sync.subscribeRepos#commit",
+        "blobs": [],
+        "blocks": { "$bytes": "AAEC" },
+        "commit": { "$link": "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm" },
+        "ops": [
+            {
+                "action": "create",
+                "cid": { "$link": "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm" },
+                "path": "app.bsky.feed.post/3l3qo2vutsw2b",
+            }
+        ],
+        "rebase": false,
+        "repo": "did:plc:z72i7hdynmk6r22z27h6tvur",
+        "rev": "3l3qo2vuowo2b",
+        "seq": 5678,
+        "since": null,
+        "time": "2024-11-20T12:00:00.000Z",
+        "tooBig": false,
+    });
+
+    let message: Message = serde_json::from_value(commit).unwrap();
+    assert!(matches!(message, Message::Commit(_)));
+
+    let frame = SubscribeReposFrame::Message(message);
+    let bytes = frame.encode::<SubscribeRepos>().unwrap();
+    let decoded = SubscribeReposFrame::decode::<SubscribeRepos>(&bytes).unwrap();
+
+    assert_eq!(frame, decoded);
+}
+
+#[test]
+fn subscribe_repos_error_frame() {
+    let frame = SubscribeReposFrame::Error(atmo_core::xrpc::Error {
+        error: atmo_core::xrpc::ErrorCode::Method(Error::FutureCursor),
+        message: None,
+    });
+
+    let bytes = frame.encode::<SubscribeRepos>().unwrap();
+    let decoded = SubscribeReposFrame::decode::<SubscribeRepos>(&bytes).unwrap();
+
+    assert_eq!(frame, decoded);
+}
+
+#[tokio::test]
+async fn get_repo_content_type() {
+    let mock = MockTransport::new();
+    mock.respond_with(|call| {
+        let content_type = if call.query.as_deref().unwrap().contains("since=") {
+            "application/json"
+        } else {
+            "application/vnd.ipld.car"
+        };
+
+        Some(response(200, &[("content-type", content_type)], "car"))
+    });
+    let url = Url::parse("https://pds.example.com/").unwrap();
+
+    let get_repo = |since: Option<&str>| {
+        let params = get_repo::Params {
+            did: Did::from_str("did:plc:z72i7hdynmk6r22z27h6tvur").unwrap(),
+            since: since.map(Into::into),
+        };
+        XrpcClient::with_transport(mock.clone())
+            .request(&url, GetRepo)
+            .params(&params)
+            .unwrap()
+            .send()
+    };
+
+    let resp = get_repo(None).await.unwrap();
+    assert_eq!(resp.result().unwrap().as_ref(), b"car");
+
+    let Err(ResponseError::InvalidXrpc(e)) = get_repo(Some("rev")).await else {
+        panic!("mismatched content type should be rejected");
+    };
+    let e = e.error.downcast_ref::<ContentTypeError>().unwrap();
+    assert_eq!(e.expected, "application/vnd.ipld.car");
+    assert_eq!(e.found.as_deref(), Some("application/json"));
+}
@@ -0,0 +1,143 @@
+use atmo_core::xrpc::{self, ErrorCode};
+use url::Url;
+
+use crate::{
+    com::atproto::identity::{resolve_handle, ResolveHandle},
+    com::atproto::server::{create_session, CreateSession},
+    mock::MockTransport,
+    tests::response,
+    XrpcClient,
+};
+
+const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+
+fn params(handle: &str) -> resolve_handle::Params {
+    resolve_handle::Params {
+        handle: handle.parse().unwrap(),
+    }
+}
+
+fn url() -> Url {
+    Url::parse("https://pds.example.com/").unwrap()
+}
+
+#[tokio::test]
+async fn mock_responses() {
+    let mock = MockTransport::new();
+    mock.respond(
+        ResolveHandle,
+        Some(&params("alice.test")),
+        Ok(resolve_handle::Output {
+            did: DID.parse().unwrap(),
+        }),
+    )
+    .respond(
+        ResolveHandle,
+        None,
+        Err(xrpc::Error::new(ErrorCode::Method("HandleNotFound".into()))),
+    );
+
+    let client = XrpcClient::with_transport(mock.clone());
+    let resolve = |handle: &str| {
+        client
+            .request(&url(), ResolveHandle)
+            .params(&params(handle))
+            .unwrap()
+            .send()
+    };
+
+    let resp = resolve("alice.test").await.unwrap();
+    assert_eq!(resp.result().unwrap().did.as_str(), DID);
+
+    let resp = resolve("bob.test").await.unwrap();
+    assert_eq!(resp.http_status(), 400);
+    assert_eq!(
+        resp.result().unwrap_err().error,
+        ErrorCode::Method("HandleNotFound".into())
+    );
+
+    let calls = mock.calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].method, http::Method::GET);
+    assert_eq!(calls[0].nsid, "com.atproto.identity.resolveHandle");
+    assert_eq!(calls[1].query.as_deref(), Some("handle=bob.test"));
+}
+
+#[tokio::test]
+async fn mock_respond_with() {
+    let mock = MockTransport::new();
+    mock.respond(
+        ResolveHandle,
+        Some(&params("alice.test")),
+        Ok(resolve_handle::Output {
+            did: DID.parse().unwrap(),
+        }),
+    )
+    .respond_with(|call| {
+        let handle = call.query.as_deref()?.strip_prefix("handle=")?;
+        Some(response(
+            429,
+            &[
+                ("ratelimit-limit", "10"),
+                ("ratelimit-remaining", "0"),
+                ("ratelimit-reset", "1700000000"),
+            ],
+            &format!(r#"{{"error": "RateLimitExceeded", "message": "{handle}"}}"#),
+        ))
+    });
+
+    let client = XrpcClient::with_transport(mock.clone());
+    let resolve = |handle: &str| {
+        client
+            .request(&url(), ResolveHandle)
+            .params(&params(handle))
+            .unwrap()
+            .send()
+    };
+
+    // Responses registered earlier take precedence.
+    let resp = resolve("alice.test").await.unwrap();
+    assert!(resp.result().is_ok());
+
+    let resp = resolve("bob.test").await.unwrap();
+    assert_eq!(resp.rate_limit().unwrap().remaining, 0);
+    let error = resp.result().unwrap_err();
+    assert_eq!(error.error, ErrorCode::RateLimitExceeded);
+    assert_eq!(error.message.as_deref(), Some("bob.test"));
+}
+
+#[tokio::test]
+async fn mock_unmatched() {
+    let mock = MockTransport::new();
+    let input = create_session::Input {
+        identifier: "alice.test".into(),
+        password: "hunter2".into(),
+        auth_factor_token: None,
+    };
+
+    let resp = XrpcClient::with_transport(mock.clone())
+        .request(&url(), CreateSession)
+        .input(&input)
+        .unwrap()
+        .content_type("application/json; charset=utf-8")
+        .bearer_auth("token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        resp.result().unwrap_err().error,
+        ErrorCode::MethodNotImplemented
+    );
+
+    let call = &mock.calls()[0];
+    assert_eq!(call.headers["authorization"], "Bearer token");
+    assert_eq!(
+        call.headers
+            .get_all("content-type")
+            .iter()
+            .collect::<Vec<_>>(),
+        ["application/json; charset=utf-8"]
+    );
+    let body: create_session::Input = serde_json::from_slice(&call.body).unwrap();
+    assert_eq!(body, input);
+}
@@ -1,2 +1,32 @@
+use bytes::Bytes;
+
+mod agent;
 mod app_bsky;
+#[cfg(feature = "blocking")]
+mod blocking;
 mod com_atproto;
+mod mock;
+#[cfg(feature = "oauth")]
+mod oauth;
+mod record;
+mod retry;
+
+/// Builds a response for [`MockTransport::respond_with`](crate::mock::MockTransport::respond_with).
+///
+/// The body is labelled as JSON unless `headers` has a `content-type`.
+fn response(status: u16, headers: &[(&str, &str)], body: &str) -> http::Response<Bytes> {
+    let mut builder = http::Response::builder().status(status);
+    if !headers
+        .iter()
+        .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+    {
+        builder = builder.header(http::header::CONTENT_TYPE, "application/json");
+    }
+    for (name, value) in headers {
+        builder = builder.header(*name, *value);
+    }
+
+    builder
+        .body(Bytes::copy_from_slice(body.as_bytes()))
+        .unwrap()
+}
@@ -0,0 +1,464 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use atmo_core::{did::DidDoc, Did};
+use atmo_identity::{DidError, DidResolver};
+use bytes::Bytes;
+use data_encoding::BASE64URL_NOPAD;
+use p256::{
+    ecdsa::{signature::Verifier as _, Signature, VerifyingKey},
+    EncodedPoint,
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::{
+    app::bsky::actor::GetPreferences,
+    com::atproto::server::GetSession,
+    mock::{MockCall, MockTransport},
+    oauth::{CallbackParams, DpopKey, OAuthClient, OAuthError, OAuthSession, TokenSet},
+    retry::RetryPolicy,
+    tests::response,
+    XrpcClient,
+};
+
+const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+
+/// The URL of the PDS, which is also its authorization server.
+const PDS: &str = "https://pds.example.com";
+
+/// Verifies the DPoP proof of `call`, returning its claims.
+fn verify_proof(call: &MockCall) -> Value {
+    let proof = call
+        .headers
+        .get("dpop")
+        .expect("request should have a DPoP proof")
+        .to_str()
+        .unwrap();
+    let parts: Vec<_> = proof.split('.').collect();
+    let decode = |s: &str| -> Vec<u8> { BASE64URL_NOPAD.decode(s.as_bytes()).unwrap() };
+
+    let header: Value = serde_json::from_slice(&decode(parts[0])).unwrap();
+    assert_eq!(header["typ"], "dpop+jwt");
+    assert_eq!(header["alg"], "ES256");
+
+    let coord = |c: &str| decode(header["jwk"][c].as_str().unwrap());
+    let point =
+        EncodedPoint::from_affine_coordinates(coord("x")[..].into(), coord("y")[..].into(), false);
+    let key = VerifyingKey::from_encoded_point(&point).unwrap();
+    let sig = Signature::from_slice(&decode(parts[2])).unwrap();
+    key.verify(format!("{}.{}", parts[0], parts[1]).as_bytes(), &sig)
+        .expect("DPoP proof should have a valid signature");
+
+    let claims: Value = serde_json::from_slice(&decode(parts[1])).unwrap();
+    let uri = &call.uri;
+    let htu = format!(
+        "{}://{}{}",
+        uri.scheme_str().unwrap(),
+        uri.authority().unwrap(),
+        uri.path()
+    );
+    assert_eq!(claims["htm"], call.method.as_str());
+    assert_eq!(claims["htu"], htu);
+    claims
+}
+
+fn form(call: &MockCall) -> HashMap<String, String> {
+    url::form_urlencoded::parse(&call.body)
+        .into_owned()
+        .collect()
+}
+
+fn json(status: u16, body: Value) -> http::Response<Bytes> {
+    response(status, &[], &body.to_string())
+}
+
+fn use_nonce(nonce: &str) -> http::Response<Bytes> {
+    response(
+        400,
+        &[("DPoP-Nonce", nonce)],
+        &json!({"error": "use_dpop_nonce"}).to_string(),
+    )
+}
+
+fn tokens(n: u32, expires_in: i64) -> http::Response<Bytes> {
+    let body = json!({
+        "access_token": format!("access-{n}"),
+        "token_type": "DPoP",
+        "expires_in": expires_in,
+        "refresh_token": format!("refresh-{n}"),
+        "scope": "atproto transition:generic",
+        "sub": DID,
+    });
+    response(200, &[("DPoP-Nonce", "server-nonce")], &body.to_string())
+}
+
+/// Mocks a server acting as both a PDS and its authorization server.
+fn server() -> MockTransport {
+    let challenge = Arc::new(Mutex::new(None::<String>));
+
+    let mock = MockTransport::new();
+    mock.respond_with(move |call| {
+        if call.uri.host() != Some("pds.example.com") {
+            return Some(response(404, &[], ""));
+        }
+
+        let resp = match call.uri.path() {
+            "/.well-known/oauth-protected-resource" => json(
+                200,
+                json!({
+                    "resource": PDS,
+                    "authorization_servers": [PDS],
+                }),
+            ),
+            "/.well-known/oauth-authorization-server" => json(
+                200,
+                json!({
+                    "issuer": PDS,
+                    "authorization_endpoint": format!("{PDS}/oauth/authorize"),
+                    "token_endpoint": format!("{PDS}/oauth/token"),
+                    "pushed_authorization_request_endpoint": format!("{PDS}/oauth/par"),
+                    "require_pushed_authorization_requests": true,
+                    "authorization_response_iss_parameter_supported": true,
+                    "code_challenge_methods_supported": ["S256"],
+                    "dpop_signing_alg_values_supported": ["ES256"],
+                    "scopes_supported": ["atproto", "transition:generic"],
+                }),
+            ),
+            "/oauth/par" => {
+                if verify_proof(call)["nonce"] != "server-nonce" {
+                    return Some(use_nonce("server-nonce"));
+                }
+
+                let form = form(call);
+                assert_eq!(form["client_id"], "http://localhost");
+                assert_eq!(form["response_type"], "code");
+                assert_eq!(form["code_challenge_method"], "S256");
+                assert_eq!(form["login_hint"], "alice.test");
+                *challenge.lock().unwrap() = Some(form["code_challenge"].clone());
+
+                json(
+                    201,
+                    json!({"request_uri": "urn:request:1", "expires_in": 60}),
+                )
+            }
+            "/oauth/token" => {
+                if verify_proof(call)["nonce"] != "server-nonce" {
+                    return Some(use_nonce("server-nonce"));
+                }
+
+                let form = form(call);
+
+                match form["grant_type"].as_str() {
+                    "authorization_code" => {
+                        let verifier = &form["code_verifier"];
+                        let expected = BASE64URL_NOPAD.encode(&Sha256::digest(verifier));
+                        assert_eq!(challenge.lock().unwrap().as_ref(), Some(&expected));
+                        assert_eq!(form["code"], "code");
+                        tokens(1, 3600)
+                    }
+                    "refresh_token" if form["refresh_token"] == "refresh-1" => tokens(2, 3600),
+                    _ => json(400, json!({"error": "invalid_grant"})),
+                }
+            }
+            "/xrpc/app.bsky.actor.getPreferences" => {
+                let claims = verify_proof(call);
+                let auth = call.headers["authorization"].to_str().unwrap();
+                let token = auth.strip_prefix("DPoP ").unwrap();
+                assert_eq!(
+                    claims["ath"],
+                    BASE64URL_NOPAD.encode(&Sha256::digest(token))
+                );
+
+                if claims["nonce"] != "resource-nonce" {
+                    return Some(response(
+                        401,
+                        &[
+                            ("WWW-Authenticate", r#"DPoP error="use_dpop_nonce""#),
+                            ("DPoP-Nonce", "resource-nonce"),
+                        ],
+                        &json!({"error": "use_dpop_nonce"}).to_string(),
+                    ));
+                }
+
+                if token == "access-revoked" {
+                    return Some(response(
+                        401,
+                        &[("WWW-Authenticate", r#"DPoP error="invalid_token""#)],
+                        &json!({"error": "InvalidToken"}).to_string(),
+                    ));
+                }
+
+                json(200, json!({"preferences": []}))
+            }
+            "/xrpc/com.atproto.server.getSession" => {
+                verify_proof(call);
+                json(503, json!({"error": "NotEnoughResources"}))
+            }
+            _ => response(404, &[], ""),
+        };
+        Some(resp)
+    });
+    mock
+}
+
+/// A resolver which places every account on the PDS at its URL.
+struct Hosted(Url);
+
+impl DidResolver for Hosted {
+    async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+        Ok(serde_json::from_value(json!({
+            "id": did,
+            "service": [{
+                "id": "#atproto_pds",
+                "type": "AtprotoPersonalDataServer",
+                "serviceEndpoint": self.0,
+            }],
+        }))
+        .unwrap())
+    }
+}
+
+fn url() -> Url {
+    Url::parse(PDS).unwrap()
+}
+
+fn client(server: &MockTransport) -> OAuthClient {
+    client_with(XrpcClient::with_transport(server.clone()))
+}
+
+fn client_with(xrpc: XrpcClient) -> OAuthClient {
+    OAuthClient::builder(
+        "http://localhost",
+        Url::parse("http://127.0.0.1/callback").unwrap(),
+    )
+    .client(xrpc)
+    .build()
+}
+
+async fn authorize(server: &MockTransport) -> OAuthSession {
+    authorize_with(client(server)).await
+}
+
+async fn authorize_with(client: OAuthClient) -> OAuthSession {
+    let (redirect, pending) = client.authorize(&url(), Some("alice.test")).await.unwrap();
+
+    let query: HashMap<_, _> = redirect.query_pairs().into_owned().collect();
+    assert_eq!(redirect.path(), "/oauth/authorize");
+    assert_eq!(query["client_id"], "http://localhost");
+    assert_eq!(query["request_uri"], "urn:request:1");
+
+    let params = CallbackParams {
+        code: "code".into(),
+        state: pending.state.clone(),
+        iss: Some(PDS.into()),
+    };
+    client
+        .callback(pending, &params, &Hosted(url()))
+        .await
+        .unwrap()
+}
+
+/// Restores `session` with different tokens.
+fn restore(server: &MockTransport, session: &OAuthSession, tokens: TokenSet) -> OAuthSession {
+    OAuthSession::restore(
+        client(server),
+        session.server().clone(),
+        session.pds().clone(),
+        session.dpop_key().clone(),
+        tokens,
+    )
+}
+
+/// Counts the refresh requests which carried the server's nonce, and so used the refresh token.
+fn refreshes(server: &MockTransport) -> usize {
+    server
+        .calls()
+        .iter()
+        .filter(|call| {
+            call.uri.path() == "/oauth/token"
+                && form(call)["grant_type"] == "refresh_token"
+                && verify_proof(call)["nonce"] == "server-nonce"
+        })
+        .count()
+}
+
+#[test]
+fn dpop_key() {
+    let key = DpopKey::from_bytes(&[7; 32]).unwrap();
+    let restored = DpopKey::from_bytes(&key.to_bytes()).unwrap();
+    assert_eq!(restored.public_jwk(), key.public_jwk());
+    assert_eq!(key.public_jwk()["crv"], "P-256");
+
+    let url = Url::parse("https://pds.example.com/xrpc/com.example.get?x=1#y").unwrap();
+    let proof = key.proof(&http::Method::GET, &url, Some("nonce"), None);
+    let claims: Value = serde_json::from_slice(
+        &BASE64URL_NOPAD
+            .decode(proof.split('.').nth(1).unwrap().as_bytes())
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        claims["htu"],
+        "https://pds.example.com/xrpc/com.example.get"
+    );
+    assert_eq!(claims["nonce"], "nonce");
+    assert!(claims.get("ath").is_none());
+}
+
+#[tokio::test]
+async fn authorization_flow() {
+    let server = server();
+    let session = authorize(&server).await;
+
+    let tokens = session.tokens();
+    assert_eq!(tokens.sub.as_str(), DID);
+    assert_eq!(tokens.access_token, "access-1");
+    assert!(tokens.expires_at.is_some());
+
+    // The PDS requires a nonce, so the first attempt is retried.
+    let resp = session.request(GetPreferences).send().await.unwrap();
+    assert!(resp.result().is_ok());
+
+    // The nonce is remembered.
+    session.request(GetPreferences).send().await.unwrap();
+    let xrpc = server
+        .calls()
+        .iter()
+        .filter(|call| call.uri.path().starts_with("/xrpc/"))
+        .count();
+    assert_eq!(xrpc, 3);
+
+    let refreshed = session.refresh().await.unwrap();
+    assert_eq!(refreshed.access_token, "access-2");
+    assert_eq!(session.tokens(), refreshed);
+    assert!(matches!(
+        session.refresh().await,
+        Err(OAuthError::Server(e)) if e.error == "invalid_grant"
+    ));
+}
+
+#[tokio::test]
+async fn expired_session() {
+    let server = server();
+    let session = authorize(&server).await;
+
+    let tokens = TokenSet {
+        expires_at: Some(atmo_core::DateTime::from_unix_seconds(0).unwrap()),
+        ..session.tokens()
+    };
+    let session = restore(&server, &session, tokens);
+
+    // The access token is refreshed before sending.
+    session.request(GetPreferences).send().await.unwrap();
+    assert_eq!(session.tokens().access_token, "access-2");
+}
+
+#[tokio::test]
+async fn revoked_token() {
+    let server = server();
+    let session = authorize(&server).await;
+
+    let tokens = TokenSet {
+        access_token: "access-revoked".into(),
+        expires_at: None,
+        ..session.tokens()
+    };
+    let session = restore(&server, &session, tokens);
+
+    // The PDS rejects the access token, so it is refreshed and the request is sent again.
+    let resp = session.request(GetPreferences).send().await.unwrap();
+    assert!(resp.result().is_ok());
+    assert_eq!(session.tokens().access_token, "access-2");
+    assert_eq!(refreshes(&server), 1);
+}
+
+#[tokio::test]
+async fn concurrent_refresh() {
+    let server = server();
+    let session = authorize(&server).await;
+
+    let tokens = TokenSet {
+        expires_at: Some(atmo_core::DateTime::from_unix_seconds(0).unwrap()),
+        ..session.tokens()
+    };
+    let session = restore(&server, &session, tokens);
+
+    // Refresh tokens are single-use, so only one request may refresh the session.
+    let (a, b) = tokio::join!(
+        session.request(GetPreferences).send(),
+        session.request(GetPreferences).send(),
+    );
+    assert!(a.unwrap().result().is_ok());
+    assert!(b.unwrap().result().is_ok());
+    assert_eq!(session.tokens().access_token, "access-2");
+    assert_eq!(refreshes(&server), 1);
+}
+
+#[tokio::test]
+async fn callback_errors() {
+    let server = server();
+    let client = client(&server);
+
+    let (_, pending) = client.authorize(&url(), Some("alice.test")).await.unwrap();
+    let params = CallbackParams {
+        code: "code".into(),
+        state: "wrong".into(),
+        iss: Some(PDS.into()),
+    };
+    assert!(matches!(
+        client.callback(pending, &params, &Hosted(url())).await,
+        Err(OAuthError::StateMismatch)
+    ));
+
+    let (_, pending) = client.authorize(&url(), Some("alice.test")).await.unwrap();
+    let params = CallbackParams {
+        code: "code".into(),
+        state: pending.state.clone(),
+        iss: None,
+    };
+    assert!(matches!(
+        client.callback(pending, &params, &Hosted(url())).await,
+        Err(OAuthError::IssuerMismatch)
+    ));
+
+    // The account is hosted on another PDS, which the authorization server doesn't serve.
+    let (_, pending) = client.authorize(&url(), Some("alice.test")).await.unwrap();
+    let params = CallbackParams {
+        code: "code".into(),
+        state: pending.state.clone(),
+        iss: Some(PDS.into()),
+    };
+    let other = Hosted(Url::parse("https://other.example.com").unwrap());
+    assert!(matches!(
+        client.callback(pending, &params, &other).await,
+        Err(OAuthError::IssuerMismatch)
+    ));
+}
+
+#[tokio::test]
+async fn retried_proofs() {
+    let server = server();
+    let client = client_with(
+        XrpcClient::with_transport(server.clone())
+            .retry_policy(RetryPolicy::default().base_delay(Duration::from_millis(1))),
+    );
+
+    let session = authorize_with(client).await;
+    session.request(GetPreferences).send().await.unwrap();
+
+    // Failed requests are retried, each with a new proof.
+    let resp = session.request(GetSession).send().await.unwrap();
+    assert_eq!(resp.http_status(), 503);
+    let proofs: HashSet<_> = server
+        .calls()
+        .iter()
+        .filter(|call| call.nsid == "com.atproto.server.getSession")
+        .map(|call| verify_proof(call)["jti"].as_str().unwrap().to_owned())
+        .collect();
+    assert_eq!(proofs.len(), 4);
+}
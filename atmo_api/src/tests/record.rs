@@ -0,0 +1,191 @@
+use atmo_core::{record::Record, xrpc, AtIdentifier};
+use futures::TryStreamExt;
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    agent::Session,
+    app::bsky::{actor::Profile, feed::Post},
+    com::atproto::repo::{
+        create_record, get_record, list_records, CreateRecord, GetRecord, ListRecords, PutRecord,
+    },
+    mock::MockTransport,
+    record::RecordError,
+    Agent, XrpcClient,
+};
+
+const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+const CID: &str = "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm";
+
+fn agent(mock: &MockTransport) -> Agent {
+    let agent = Agent::builder(Url::parse("https://pds.example.com/").unwrap())
+        .client(XrpcClient::with_transport(mock.clone()))
+        .build();
+    agent.resume(Session {
+        did: DID.parse().unwrap(),
+        handle: "alice.test".parse().unwrap(),
+        access_jwt: "access".into(),
+        refresh_jwt: "refresh".into(),
+        pds: None,
+    });
+    agent
+}
+
+fn repo() -> AtIdentifier {
+    AtIdentifier::Did(DID.parse().unwrap())
+}
+
+fn post(text: &str) -> Post {
+    serde_json::from_value(json!({
+        "text": text,
+        "createdAt": "2024-11-01T12:00:00.000Z",
+    }))
+    .unwrap()
+}
+
+fn record(collection: &str, rkey: &str, text: &str) -> serde_json::Value {
+    json!({
+        "uri": format!("at://{DID}/{collection}/{rkey}"),
+        "cid": CID,
+        "value": {
+            "$type": collection,
+            "text": text,
+            "createdAt": "2024-11-01T12:00:00.000Z",
+        },
+    })
+}
+
+#[tokio::test]
+async fn create_and_get_record() {
+    let mock = MockTransport::new();
+    let created: create_record::Output = serde_json::from_value(json!({
+        "uri": format!("at://{DID}/app.bsky.feed.post/3jzfcijpj2z2a"),
+        "cid": CID,
+    }))
+    .unwrap();
+    let fetched: get_record::Output =
+        serde_json::from_value(record(Post::NSID, "3jzfcijpj2z2a", "hello")).unwrap();
+    mock.respond(CreateRecord, None, Ok(created))
+        .respond(GetRecord, None, Ok(fetched));
+    let agent = agent(&mock);
+
+    let created = agent.create_record(&post("hello")).await.unwrap();
+    assert_eq!(created.uri.rkey(), Some("3jzfcijpj2z2a"));
+
+    let body: serde_json::Value = serde_json::from_slice(&mock.calls()[0].body).unwrap();
+    assert_eq!(body["collection"], "app.bsky.feed.post");
+    assert_eq!(body["repo"], DID);
+    assert_eq!(body["record"]["$type"], "app.bsky.feed.post");
+    assert_eq!(body["record"]["text"], "hello");
+    assert!(body.get("rkey").is_none());
+
+    let fetched = agent
+        .get_record::<Post>(&repo(), "3jzfcijpj2z2a")
+        .await
+        .unwrap();
+    assert_eq!(fetched.value, post("hello"));
+    assert!(fetched.cid.is_some());
+    assert_eq!(
+        mock.calls()[1].query.as_deref(),
+        Some(
+            "collection=app.bsky.feed.post&repo=did%3Aplc%3Aewvi7nxzyoun6zhxrhs64oiz\
+             &rkey=3jzfcijpj2z2a"
+        )
+    );
+}
+
+#[tokio::test]
+async fn wrong_collection() {
+    let mock = MockTransport::new();
+    let fetched: get_record::Output =
+        serde_json::from_value(record("app.bsky.feed.like", "3jzfcijpj2z2a", "hello")).unwrap();
+    mock.respond(GetRecord, None, Ok(fetched));
+
+    let err = agent(&mock)
+        .get_record::<Post>(&repo(), "3jzfcijpj2z2a")
+        .await
+        .unwrap_err();
+    match err {
+        RecordError::WrongCollection { expected, found } => {
+            assert_eq!(expected, "app.bsky.feed.post");
+            assert_eq!(found.as_deref(), Some("app.bsky.feed.like"));
+        }
+        e => panic!("unexpected error: {e}"),
+    }
+}
+
+#[tokio::test]
+async fn record_keys() {
+    let mock = MockTransport::new();
+    mock.respond(
+        PutRecord,
+        None,
+        Err(xrpc::Error::new(xrpc::ErrorCode::InvalidRequest)),
+    );
+    let agent = agent(&mock);
+    let profile: Profile = serde_json::from_value(json!({ "displayName": "Alice" })).unwrap();
+
+    let err = agent.put_record("other", &profile).await.unwrap_err();
+    assert!(matches!(err, RecordError::InvalidKey { rkey, .. } if rkey == "other"));
+    assert!(mock.calls().is_empty());
+
+    let err = agent.put_record("self", &profile).await.unwrap_err();
+    assert!(matches!(err, RecordError::Xrpc(_)));
+    let body: serde_json::Value = serde_json::from_slice(&mock.calls()[0].body).unwrap();
+    assert_eq!(body["rkey"], "self");
+    assert_eq!(body["record"]["$type"], "app.bsky.actor.profile");
+
+    agent.create_record(&profile).await.unwrap_err();
+    let body: serde_json::Value = serde_json::from_slice(&mock.calls()[1].body).unwrap();
+    assert_eq!(body["rkey"], "self");
+}
+
+#[tokio::test]
+async fn list_records() {
+    let mock = MockTransport::new();
+    let params = |cursor: Option<&str>| list_records::Params {
+        collection: Post::NSID.parse().unwrap(),
+        cursor: cursor.map(str::to_owned),
+        limit: None,
+        repo: repo(),
+        reverse: None,
+        rkey_end: None,
+        rkey_start: None,
+    };
+    let page = |records: Vec<serde_json::Value>, cursor: Option<&str>| {
+        serde_json::from_value::<list_records::Output>(json!({
+            "records": records,
+            "cursor": cursor,
+        }))
+        .unwrap()
+    };
+    mock.respond(
+        ListRecords,
+        Some(&params(None)),
+        Ok(page(
+            vec![
+                record(Post::NSID, "3jzfcijpj2z2a", "one"),
+                record(Post::NSID, "3jzfcijpj2z2b", "two"),
+            ],
+            Some("2"),
+        )),
+    )
+    .respond(
+        ListRecords,
+        Some(&params(Some("2"))),
+        Ok(page(
+            vec![record(Post::NSID, "3jzfcijpj2z2c", "three")],
+            None,
+        )),
+    );
+
+    let agent = agent(&mock);
+    let posts: Vec<_> = agent
+        .list_records::<Post>(&repo())
+        .try_collect()
+        .await
+        .unwrap();
+    let texts: Vec<_> = posts.iter().map(|p| p.value.text.as_str()).collect();
+    assert_eq!(texts, ["one", "two", "three"]);
+    assert_eq!(mock.calls().len(), 2);
+}
@@ -0,0 +1,146 @@
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use serde_json::json;
+use url::Url;
+
+use crate::{
+    com::atproto::{
+        identity::{resolve_handle, ResolveHandle},
+        server::{create_session, CreateSession},
+    },
+    mock::MockTransport,
+    retry::RetryPolicy,
+    tests::response,
+    XrpcClient,
+};
+
+/// Mocks a server which fails the first `failures` requests with `status`.
+fn flaky(failures: u32, status: u16) -> MockTransport {
+    let count = AtomicU32::new(0);
+
+    let mock = MockTransport::new();
+    mock.respond_with(move |call| {
+        if count.fetch_add(1, Ordering::SeqCst) < failures {
+            let reset = jiff::Timestamp::now().as_second().to_string();
+            return Some(response(
+                status,
+                &[
+                    ("ratelimit-limit", "10"),
+                    ("ratelimit-remaining", "0"),
+                    ("ratelimit-reset", &reset),
+                ],
+                &json!({"error": "Unavailable"}).to_string(),
+            ));
+        }
+
+        let body = if call.method == http::Method::GET {
+            json!({"did": "did:plc:ewvi7nxzyoun6zhxrhs64oiz"})
+        } else {
+            json!({
+                "accessJwt": "access",
+                "refreshJwt": "refresh",
+                "handle": "alice.test",
+                "did": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+            })
+        };
+        Some(response(
+            200,
+            &[
+                ("ratelimit-limit", "10"),
+                ("ratelimit-remaining", "9"),
+                ("ratelimit-reset", "1700000000"),
+            ],
+            &body.to_string(),
+        ))
+    });
+    mock
+}
+
+fn client(mock: &MockTransport) -> XrpcClient {
+    XrpcClient::with_transport(mock.clone())
+        .retry_policy(RetryPolicy::default().base_delay(Duration::from_millis(1)))
+}
+
+fn url() -> Url {
+    Url::parse("https://pds.example.com/").unwrap()
+}
+
+async fn resolve_handle(client: &XrpcClient) -> crate::Response<ResolveHandle> {
+    let params = resolve_handle::Params {
+        handle: "alice.test".parse().unwrap(),
+    };
+    client
+        .request(&url(), ResolveHandle)
+        .params(&params)
+        .unwrap()
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn create_session(client: &XrpcClient) -> crate::Response<CreateSession> {
+    let input = create_session::Input {
+        identifier: "alice.test".into(),
+        password: "hunter2".into(),
+        auth_factor_token: None,
+    };
+    client
+        .request(&url(), CreateSession)
+        .input(&input)
+        .unwrap()
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn retry_queries() {
+    let mock = flaky(2, 503);
+    let resp = resolve_handle(&client(&mock)).await;
+    assert!(resp.result().is_ok());
+    assert_eq!(mock.calls().len(), 3);
+
+    let limit = resp.rate_limit().unwrap();
+    assert_eq!((limit.limit, limit.remaining), (10, 9));
+
+    // The default client makes a single attempt.
+    let mock = flaky(1, 503);
+    let resp = resolve_handle(&XrpcClient::with_transport(mock.clone())).await;
+    assert!(resp.result().is_err());
+    assert_eq!(mock.calls().len(), 1);
+
+    // Attempts are limited.
+    let mock = flaky(5, 502);
+    let resp = resolve_handle(&client(&mock)).await;
+    assert_eq!(resp.http_status(), 502);
+    assert_eq!(mock.calls().len(), 4);
+}
+
+#[tokio::test]
+async fn retry_rate_limited() {
+    let mock = flaky(1, 429);
+    let resp = resolve_handle(&client(&mock)).await;
+    assert!(resp.result().is_ok());
+    assert_eq!(mock.calls().len(), 2);
+}
+
+#[tokio::test]
+async fn retry_procedures() {
+    let mock = flaky(1, 429);
+    let resp = create_session(&client(&mock)).await;
+    assert_eq!(resp.http_status(), 429);
+    assert_eq!(mock.calls().len(), 1);
+
+    let mock = flaky(1, 500);
+    let client = XrpcClient::with_transport(mock.clone()).retry_policy(
+        RetryPolicy::default()
+            .base_delay(Duration::from_millis(1))
+            .retry_procedures(true),
+    );
+    let resp = create_session(&client).await;
+    assert!(resp.result().is_ok());
+    assert_eq!(mock.calls().len(), 2);
+}
@@ -0,0 +1,67 @@
+//! HTTP transports for [`XrpcClient`](crate::XrpcClient).
+//!
+//! By default, requests are sent with a [`reqwest::Client`]. Any other HTTP client can be used by
+//! implementing [`HttpTransport`] and passing it to
+//! [`XrpcClient::with_transport`](crate::XrpcClient::with_transport). For tests, the `mock`
+//! feature provides an in-memory [`MockTransport`](crate::mock::MockTransport).
+
+use std::{error::Error as StdError, future::Future, pin::Pin};
+
+use bytes::Bytes;
+use http_body_util::BodyExt;
+
+/// A trait for HTTP clients which can send XRPC requests.
+pub trait HttpTransport {
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Sends `request`, returning the response with its full body.
+    ///
+    /// Error statuses are returned as responses, not as errors.
+    fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> impl Future<Output = Result<http::Response<Bytes>, Self::Error>> + Send;
+}
+
+impl HttpTransport for reqwest::Client {
+    type Error = reqwest::Error;
+
+    async fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> Result<http::Response<Bytes>, Self::Error> {
+        let request = reqwest::Request::try_from(request)?;
+
+        let resp: http::Response<_> = self.execute(request).await?.into();
+        let (parts, body) = resp.into_parts();
+        let bytes = body.collect().await?.to_bytes();
+
+        Ok(http::Response::from_parts(parts, bytes))
+    }
+}
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// An object-safe version of [`HttpTransport`].
+pub(crate) trait DynTransport: Send + Sync {
+    fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> BoxFuture<'_, Result<http::Response<Bytes>, Box<dyn StdError + Send + Sync>>>;
+}
+
+impl<T> DynTransport for T
+where
+    T: HttpTransport + Send + Sync,
+{
+    fn send(
+        &self,
+        request: http::Request<Bytes>,
+    ) -> BoxFuture<'_, Result<http::Response<Bytes>, Box<dyn StdError + Send + Sync>>> {
+        Box::pin(async move {
+            HttpTransport::send(self, request)
+                .await
+                .map_err(|e| e.into())
+        })
+    }
+}
@@ -83,6 +83,7 @@
 //!
 //!     - If the object appears as the `record` field of a `record` definition, it should be emitted
 //!       with the `PascalCase` equivalent of the record type name.
+//!       It should also implement `atmo_core::record::Record`, with the record's NSID and key type.
 
 use atmo_lexicon::{Lexicon, StringFormat};
 use namespace::{BuiltinTy, NamespaceTree};
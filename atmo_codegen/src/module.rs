@@ -10,7 +10,7 @@ use quote::{quote, ToTokens};
 
 use crate::{
     enum_::{RustStringEnumDef, RustUnionEnumDef},
-    rpc::RustRpcDef,
+    rpc::{RustRpcDef, RustSubscriptionDef},
     struct_::RustStructDef,
 };
 
@@ -72,6 +72,7 @@ pub enum ItemTy {
     Rpc(RustRpcDef),
     Struct(RustStructDef),
     StringEnum(RustStringEnumDef),
+    Subscription(RustSubscriptionDef),
     UnionEnum(RustUnionEnumDef),
 }
 
@@ -96,6 +97,13 @@ impl From<RustStringEnumDef> for ItemTy {
     }
 }
 
+impl From<RustSubscriptionDef> for ItemTy {
+    #[inline]
+    fn from(s: RustSubscriptionDef) -> Self {
+        ItemTy::Subscription(s)
+    }
+}
+
 impl From<RustUnionEnumDef> for ItemTy {
     #[inline]
     fn from(u: RustUnionEnumDef) -> Self {
@@ -109,6 +117,7 @@ impl ToTokens for ItemTy {
             ItemTy::Rpc(r) => r.to_tokens(tokens),
             ItemTy::Struct(s) => s.to_tokens(tokens),
             ItemTy::StringEnum(e) => e.to_tokens(tokens),
+            ItemTy::Subscription(s) => s.to_tokens(tokens),
             ItemTy::UnionEnum(e) => e.to_tokens(tokens),
         }
     }
@@ -5,7 +5,7 @@ use std::{
 
 use atmo_core::{
     nsid::{self, FullReference},
-    Nsid,
+    xrpc, Nsid,
 };
 use atmo_lexicon::{
     Blob, Boolean, Bytes, FieldSchema, Input, Integer, IoSchema, Lexicon, Object, Output, Ref,
@@ -16,8 +16,8 @@ use heck::{ToPascalCase, ToSnakeCase};
 use crate::{
     enum_::{RustStringEnumDef, RustUnionEnumDef, StringEnumVariant, UnionEnumVariant},
     module::{Item, ItemPath, ItemTy, ModulePath, ModuleTree},
-    rpc::{RpcType, RustRpcDef, RustRpcIo},
-    struct_::{RustStructDef, RustStructField},
+    rpc::{RpcType, RustPagination, RustRpcDef, RustRpcIo, RustSubscriptionDef},
+    struct_::{RustRecord, RustStructDef, RustStructField},
     Type,
 };
 
@@ -57,6 +57,17 @@ impl NamespaceTree {
                         parent_mod.add_item(name, Item::new(rs.into())).unwrap();
                     }
 
+                    MainDef::Record(record) => {
+                        let mut rs = self.create_rust_struct(nsid, "main", &record.object);
+                        rs.record = Some(RustRecord {
+                            nsid: nsid.to_string(),
+                            key: record.key.clone(),
+                        });
+                        let name = rs.name.to_string();
+                        let parent_mod = mod_tree.get_or_create_mut(&parent_mod_path);
+                        parent_mod.add_item(name, Item::new(rs.into())).unwrap();
+                    }
+
                     MainDef::Rpc(rpc) => {
                         let module = mod_tree.get_or_create_mut(&mod_path);
 
@@ -110,6 +121,49 @@ impl NamespaceTree {
                             .add_item(nsid.name().to_pascal_case(), Item::new(rpc.into()))
                             .unwrap();
                     }
+
+                    MainDef::Subscription(sub) => {
+                        let module = mod_tree.get_or_create_mut(&mod_path);
+
+                        // Emit params, if any.
+                        if let Some(params) = sub
+                            .params
+                            .as_ref()
+                            .map(|p| self.create_rust_struct(nsid, "params", p))
+                        {
+                            module
+                                .add_item("Params".into(), Item::new(params.into()))
+                                .unwrap();
+                        }
+
+                        // Emit message union, if any.
+                        if let Some(message) = sub
+                            .message
+                            .as_ref()
+                            .map(|m| self.create_rust_union_enum(nsid, None, "Message", m))
+                        {
+                            module
+                                .add_item("Message".into(), Item::new(message.into()))
+                                .unwrap();
+                        }
+
+                        if let Some(error) = sub
+                            .error
+                            .as_ref()
+                            .map(|e| self.create_rust_string_enum("Error", e))
+                        {
+                            module
+                                .add_item("Error".into(), Item::new(error.into()))
+                                .unwrap();
+                        }
+
+                        let parent_mod = mod_tree.get_or_create_mut(&parent_mod_path);
+
+                        let sub = self.create_rust_subscription(nsid, sub);
+                        parent_mod
+                            .add_item(nsid.name().to_pascal_case(), Item::new(sub.into()))
+                            .unwrap();
+                    }
                 }
             }
 
@@ -163,6 +217,10 @@ impl NamespaceTree {
             .params
             .is_some()
             .then(|| mod_path.item_path("Params".into()));
+        let error = rpc
+            .error
+            .is_some()
+            .then(|| mod_path.item_path("Error".into()));
         let input = rpc
             .input
             .as_ref()
@@ -187,6 +245,10 @@ impl NamespaceTree {
                 }
             });
 
+        let input_encoding = rpc.input.as_ref().map(|i| i.encoding.clone());
+        let output_encoding = rpc.output.as_ref().map(|o| o.encoding.clone());
+        let pagination = self.create_rust_pagination(nsid, rpc);
+
         let name = quote::format_ident!("{}", nsid.name().to_pascal_case());
 
         RustRpcDef {
@@ -195,8 +257,72 @@ impl NamespaceTree {
             nsid: nsid.clone(),
             params,
             input,
+            input_encoding,
             output,
-            error: None,
+            output_encoding,
+            error,
+            pagination,
+        }
+    }
+
+    /// Detects whether an RPC is paginated.
+    ///
+    /// An RPC is paginated if both its parameters and its output have an optional `cursor` string,
+    /// and its output has exactly one array, which holds the items of each page.
+    fn create_rust_pagination(&self, nsid: &Nsid, rpc: &RpcDef) -> Option<RustPagination> {
+        let is_cursor = |f: &&ObjectField| {
+            f.name == "cursor"
+                && f.is_optional
+                && !f.is_array
+                && !f.is_nullable
+                && matches!(&f.ty, ObjectFieldTy::Builtin(b) if matches!(Type::from(b), Type::String))
+        };
+
+        rpc.params.as_ref()?.fields.iter().find(is_cursor)?;
+
+        let Some(RpcIoTy::Object(output)) = rpc.output.as_ref().and_then(|o| o.ty.as_ref()) else {
+            return None;
+        };
+        output.fields.iter().find(is_cursor)?;
+
+        let mut arrays = output.fields.iter().filter(|f| f.is_array);
+        let (Some(items), None) = (arrays.next(), arrays.next()) else {
+            return None;
+        };
+        if items.is_nullable {
+            return None;
+        }
+
+        Some(RustPagination {
+            items: field_ident(&items.name),
+            item_ty: self.field_inner_ty(nsid, "output", items),
+            items_optional: items.is_optional,
+        })
+    }
+
+    fn create_rust_subscription(&self, nsid: &Nsid, sub: &SubscriptionDef) -> RustSubscriptionDef {
+        let mod_path = ModulePath::from(nsid);
+        let params = sub
+            .params
+            .is_some()
+            .then(|| mod_path.item_path("Params".into()));
+        let message = sub
+            .message
+            .is_some()
+            .then(|| mod_path.item_path("Message".into()));
+        let error = sub
+            .error
+            .is_some()
+            .then(|| mod_path.item_path("Error".into()));
+
+        let name = quote::format_ident!("{}", nsid.name().to_pascal_case());
+
+        RustSubscriptionDef {
+            name,
+            nsid: nsid.clone(),
+            params,
+            message,
+            error,
         }
     }
 
@@ -221,54 +347,13 @@ impl NamespaceTree {
     }
 
     fn create_rust_struct(&self, ns: &Nsid, def_name: &str, obj: &ObjectDef) -> RustStructDef {
-        let mod_path = ModulePath::from(ns);
-
         let fields = obj
             .fields
             .iter()
             .map(|f| {
-                let field_name: String = match f.name.as_str() {
-                    "type" => "ty".into(),
-                    "ref" => "ref_".into(),
-                    other => other.to_snake_case(),
-                };
-
-                let field_ident = quote::format_ident!("{field_name}");
+                let field_ident = field_ident(&f.name);
                 let rename = f.name.clone();
-                let inner_ty = match &f.ty {
-                    ObjectFieldTy::Builtin(b) => Type::from(b),
-                    ObjectFieldTy::Defined => {
-                        let mut submod_path = mod_path.clone();
-                        submod_path.push(def_name.to_snake_case());
-
-                        submod_path.item_path(f.name.to_pascal_case()).into()
-                    }
-                    ObjectFieldTy::Ref(r) => {
-                        let full =
-                            self.resolve_ref(ns, &nsid::Reference::from_str(&r.ref_).unwrap());
-
-                        let ref_ns = self.namespaces.get(&full.clone_nsid()).unwrap();
-
-                        match full.fragment_name() {
-                            Some(_) => {
-                                let referent = ref_ns
-                                    .other_defs
-                                    .get(full.fragment_name().unwrap())
-                                    .unwrap();
-
-                                let ty = ItemPath::from(full).into();
-
-                                if referent.is_array {
-                                    Type::Vec(Box::new(ty))
-                                } else {
-                                    ty
-                                }
-                            }
-
-                            None => ItemPath::from(full).into(),
-                        }
-                    }
-                };
+                let inner_ty = self.field_inner_ty(ns, def_name, f);
 
                 RustStructField {
                     doc: None,
@@ -290,7 +375,48 @@ impl NamespaceTree {
 
         let name = quote::format_ident!("{name_s}");
 
-        RustStructDef { name, fields }
+        RustStructDef {
+            name,
+            fields,
+            record: None,
+        }
+    }
+
+    /// Returns the type of a field of an object, or of its items if it is an array.
+    fn field_inner_ty(&self, ns: &Nsid, def_name: &str, f: &ObjectField) -> Type {
+        match &f.ty {
+            ObjectFieldTy::Builtin(b) => Type::from(b),
+            ObjectFieldTy::Defined => {
+                let mut submod_path = ModulePath::from(ns);
+                submod_path.push(def_name.to_snake_case());
+
+                submod_path.item_path(f.name.to_pascal_case()).into()
+            }
+            ObjectFieldTy::Ref(r) => {
+                let full = self.resolve_ref(ns, &nsid::Reference::from_str(&r.ref_).unwrap());
+
+                let ref_ns = self.namespaces.get(&full.clone_nsid()).unwrap();
+
+                match full.fragment_name() {
+                    Some(_) => {
+                        let referent = ref_ns
+                            .other_defs
+                            .get(full.fragment_name().unwrap())
+                            .unwrap();
+
+                        let ty = ItemPath::from(full).into();
+
+                        if referent.is_array {
+                            Type::Vec(Box::new(ty))
+                        } else {
+                            ty
+                        }
+                    }
+
+                    None => ItemPath::from(full).into(),
+                }
+            }
+        }
     }
 
     fn create_rust_string_enum(&self, type_name: &str, def: &StringEnumDef) -> RustStringEnumDef {
@@ -368,6 +494,17 @@ impl NamespaceTree {
     }
 }
 
+/// Returns the identifier of the Rust field for an object property.
+fn field_ident(name: &str) -> syn::Ident {
+    let field_name: String = match name {
+        "type" => "ty".into(),
+        "ref" => "ref_".into(),
+        other => other.to_snake_case(),
+    };
+
+    quote::format_ident!("{field_name}")
+}
+
 pub struct Namespace {
     nsid: Nsid,
 
@@ -414,13 +551,15 @@ impl Namespace {
 
                 let input = p.input.as_ref().map(|i| RpcIo {
                     ty: self.create_input_def(i),
+                    encoding: i.encoding.clone(),
                 });
 
                 let output = p.output.as_ref().map(|o| RpcIo {
                     ty: self.create_output_def(o),
+                    encoding: o.encoding.clone(),
                 });
 
-                let error = p.errors.as_ref().map(|e| self.create_error_def(e));
+                let error = p.errors.as_ref().and_then(|e| self.create_error_def(e));
 
                 MainDef::Rpc(RpcDef {
                     ty: RpcType::Procedure,
@@ -439,9 +578,10 @@ impl Namespace {
 
                 let output = q.output.as_ref().map(|o| RpcIo {
                     ty: self.create_output_def(o),
+                    encoding: o.encoding.clone(),
                 });
 
-                let error = q.errors.as_ref().map(|e| self.create_error_def(e));
+                let error = q.errors.as_ref().and_then(|e| self.create_error_def(e));
 
                 MainDef::Rpc(RpcDef {
                     ty: RpcType::Query,
@@ -452,11 +592,29 @@ impl Namespace {
                 })
             }
 
-            Schema::Record(r) => MainDef::Object(self.create_object_def("main", &r.record)),
+            Schema::Record(r) => MainDef::Record(RecordDef {
+                key: RecordKeyDef::from_lexicon(&r.key),
+                object: self.create_object_def("main", &r.record),
+            }),
+
+            Schema::Subscription(s) => {
+                let params = s.parameters.as_deref().map(|p| match p {
+                    Schema::Params(p) => self.create_object_def("params", p),
+                    other => panic!("unhandled subscription parameters: {other:?}"),
+                });
+
+                let message = s.message.as_ref().map(|m| match &*m.schema {
+                    Schema::Union(u) => UnionDef::from_lexicon(u),
+                    other => panic!("unhandled subscription message: {other:?}"),
+                });
+
+                let error = s.errors.as_ref().and_then(|e| self.create_error_def(e));
 
-            Schema::Subscription(_s) => {
-                eprintln!("subscriptions not handled");
-                return;
+                MainDef::Subscription(SubscriptionDef {
+                    params,
+                    message,
+                    error,
+                })
             }
 
             _ => unreachable!(),
@@ -529,11 +687,20 @@ impl Namespace {
     }
 
     /// Creates a definition for an RPC error enum.
-    fn create_error_def(&self, errors: &[atmo_lexicon::Error]) -> StringEnumDef {
-        StringEnumDef {
-            values: errors.iter().map(|e| e.name.clone()).collect(),
+    ///
+    /// Error codes shared by all methods are represented by [`xrpc::ErrorCode`], so they are left
+    /// out. Returns `None` if no other errors are declared.
+    fn create_error_def(&self, errors: &[atmo_lexicon::Error]) -> Option<StringEnumDef> {
+        let values: Vec<_> = errors
+            .iter()
+            .filter(|e| xrpc::ErrorCode::<()>::shared(&e.name).is_none())
+            .map(|e| e.name.clone())
+            .collect();
+
+        (!values.is_empty()).then_some(StringEnumDef {
+            values,
             is_open: true,
-        }
+        })
     }
 
     /// Creates a definition for an object.
@@ -585,7 +752,37 @@ impl Namespace {
 /// A primary item definition.
 pub enum MainDef {
     Object(ObjectDef),
+    Record(RecordDef),
     Rpc(RpcDef),
+    Subscription(SubscriptionDef),
+}
+
+pub struct RecordDef {
+    pub key: RecordKeyDef,
+    pub object: ObjectDef,
+}
+
+/// The format of the record keys of a record definition.
+#[derive(Clone, Debug)]
+pub enum RecordKeyDef {
+    Tid,
+    Nsid,
+    Literal(String),
+    Any,
+}
+
+impl RecordKeyDef {
+    fn from_lexicon(key: &str) -> Self {
+        match key {
+            "tid" => RecordKeyDef::Tid,
+            "nsid" => RecordKeyDef::Nsid,
+            "any" => RecordKeyDef::Any,
+            _ => match key.strip_prefix("literal:") {
+                Some(value) => RecordKeyDef::Literal(value.into()),
+                None => panic!("unhandled record key type: {key}"),
+            },
+        }
+    }
 }
 
 pub struct OtherDef {
@@ -707,6 +904,14 @@ pub struct RpcDef {
 
 pub struct RpcIo {
     pub ty: Option<RpcIoTy>,
+    /// The media type of the body, which may be a wildcard such as `*/*`.
+    pub encoding: String,
+}
+
+pub struct SubscriptionDef {
+    pub params: Option<ObjectDef>,
+    pub message: Option<UnionDef>,
+    pub error: Option<StringEnumDef>,
 }
 
 pub struct StringEnumDef {
@@ -1,7 +1,7 @@
 use atmo_core::nsid::Nsid;
 use quote::{quote, ToTokens};
 
-use crate::module::ItemPath;
+use crate::{module::ItemPath, Type};
 
 #[derive(Debug)]
 pub struct RustRpcDef {
@@ -10,8 +10,11 @@ pub struct RustRpcDef {
     pub nsid: Nsid,
     pub params: Option<ItemPath>,
     pub input: Option<RustRpcIo>,
+    pub input_encoding: Option<String>,
     pub output: Option<RustRpcIo>,
+    pub output_encoding: Option<String>,
     pub error: Option<ItemPath>,
+    pub pagination: Option<RustPagination>,
 }
 
 impl ToTokens for RustRpcDef {
@@ -31,7 +34,6 @@ impl ToTokens for RustRpcDef {
 
         let mut input_ty = quote! { () };
         let mut input_err = quote! { std::convert::Infallible };
-        let mut input_content_type = quote! { None };
         let mut serialize_input = quote! {
             let _ = input;
             Ok(bytes::Bytes::new())
@@ -43,7 +45,6 @@ impl ToTokens for RustRpcDef {
 
         let mut output_ty = quote! { () };
         let mut output_err = quote! { std::convert::Infallible };
-        let mut _output_content_type = quote! { None };
         let mut serialize_output = quote! {
             let _ = output;
             Ok(bytes::Bytes::new())
@@ -73,11 +74,6 @@ impl ToTokens for RustRpcDef {
                 RustRpcIo::Def(_) | RustRpcIo::Ref(_) => quote! { serde_json::Error },
             };
 
-            input_content_type = match i {
-                RustRpcIo::Bytes => quote! { None },
-                RustRpcIo::Def(_) | RustRpcIo::Ref(_) => quote! { Some("application/json") },
-            };
-
             serialize_input = match i {
                 RustRpcIo::Bytes => quote! {
                     Ok(input.clone())
@@ -102,11 +98,6 @@ impl ToTokens for RustRpcDef {
         if let Some(o) = &self.output {
             output_ty = o.to_token_stream();
 
-            _output_content_type = match o {
-                RustRpcIo::Bytes => quote! { None },
-                RustRpcIo::Def(_) | RustRpcIo::Ref(_) => quote! { Some("application/json") },
-            };
-
             output_err = match o {
                 RustRpcIo::Bytes => quote! { std::convert::Infallible },
                 RustRpcIo::Def(_) | RustRpcIo::Ref(_) => quote! { serde_json::Error },
@@ -133,6 +124,9 @@ impl ToTokens for RustRpcDef {
             };
         }
 
+        let input_content_type = content_type(self.input_encoding.as_deref());
+        let output_content_type = content_type(self.output_encoding.as_deref());
+
         let error_ty = self
             .error
             .as_ref()
@@ -146,6 +140,37 @@ impl ToTokens for RustRpcDef {
 
         let nsid = &self.nsid.as_str();
 
+        let pagination = self.pagination.as_ref().map(|p| {
+            let item_ty = &p.item_ty;
+            let items = &p.items;
+            let into_items = if p.items_optional {
+                quote! { output.#items.unwrap_or_default() }
+            } else {
+                quote! { output.#items }
+            };
+
+            quote! {
+                impl #crate_::xrpc::Paginated for #ident {
+                    type Item = #item_ty;
+
+                    #[inline]
+                    fn cursor(output: &Self::Output) -> Option<&str> {
+                        output.cursor.as_deref()
+                    }
+
+                    #[inline]
+                    fn set_cursor(params: &mut Self::Params, cursor: Option<String>) {
+                        params.cursor = cursor;
+                    }
+
+                    #[inline]
+                    fn into_items(output: Self::Output) -> Vec<Self::Item> {
+                        #into_items
+                    }
+                }
+            }
+        });
+
         quote! {
             #[derive(Debug)]
             pub struct #ident;
@@ -187,6 +212,10 @@ impl ToTokens for RustRpcDef {
                     #input_content_type
                 }
 
+                fn output_content_type() -> Option<&'static str> {
+                    #output_content_type
+                }
+
                 fn serialize_input(input: &Self::Input) -> Result<bytes::Bytes, Self::InputError> {
                     #serialize_input
                 }
@@ -203,11 +232,31 @@ impl ToTokens for RustRpcDef {
                     #deserialize_output
                 }
             }
+
+            #pagination
         }
         .to_tokens(tokens);
     }
 }
 
+fn content_type(encoding: Option<&str>) -> proc_macro2::TokenStream {
+    match encoding {
+        Some(encoding) => quote! { Some(#encoding) },
+        None => quote! { None },
+    }
+}
+
+/// The pagination of an RPC's output.
+#[derive(Debug)]
+pub struct RustPagination {
+    /// The output field holding the items of each page.
+    pub items: syn::Ident,
+    /// The type of each item.
+    pub item_ty: Type,
+    /// Whether the items field is optional.
+    pub items_optional: bool,
+}
+
 #[derive(Debug)]
 pub enum RustRpcIo {
     Bytes,
@@ -229,3 +278,86 @@ pub enum RpcType {
     Query,
     Procedure,
 }
+
+#[derive(Debug)]
+pub struct RustSubscriptionDef {
+    pub name: syn::Ident,
+    pub nsid: Nsid,
+    pub params: Option<ItemPath>,
+    pub message: Option<ItemPath>,
+    pub error: Option<ItemPath>,
+}
+
+impl ToTokens for RustSubscriptionDef {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let crate_ = crate::crate_name();
+        let ident = &self.name;
+
+        let mut params_ty = quote! { () };
+        let mut serialize_params = quote! {
+            let _ = params;
+            Ok(String::new())
+        };
+        let mut deserialize_params = quote! {
+            let _ = query;
+            Ok(())
+        };
+
+        if let Some(p) = &self.params {
+            params_ty = p.to_token_stream();
+
+            serialize_params = quote! {
+                serde_urlencoded_xrpc::to_string(params)
+            };
+
+            deserialize_params = quote! {
+                serde_urlencoded_xrpc::from_str(query)
+            }
+        }
+
+        let message_ty = self
+            .message
+            .as_ref()
+            .map(|m| quote! { #m })
+            .unwrap_or(quote! { #crate_::Unknown });
+
+        let error_ty = self
+            .error
+            .as_ref()
+            .map(|e| quote! { #e })
+            .unwrap_or(quote! { String });
+
+        let nsid = &self.nsid.as_str();
+
+        quote! {
+            #[derive(Debug)]
+            pub struct #ident;
+
+            impl #crate_::xrpc::Subscription for #ident {
+                type Params = #params_ty;
+
+                type Message = #message_ty;
+
+                type RpcError = #error_ty;
+
+                #[inline]
+                fn nsid() -> &'static str {
+                    #nsid
+                }
+
+                fn serialize_params(
+                    params: &Self::Params,
+                ) -> Result<String, serde_urlencoded_xrpc::ser::Error> {
+                    #serialize_params
+                }
+
+                fn deserialize_params(
+                    query: &str,
+                ) -> Result<Self::Params, serde_urlencoded_xrpc::de::Error> {
+                    #deserialize_params
+                }
+            }
+        }
+        .to_tokens(tokens);
+    }
+}
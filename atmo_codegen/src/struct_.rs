@@ -1,29 +1,57 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
-use crate::Type;
+use crate::{namespace::RecordKeyDef, Type};
 
 #[derive(Debug)]
 pub struct RustStructDef {
     pub name: syn::Ident,
     pub fields: Vec<RustStructField>,
+    /// The record definition this struct represents, if any.
+    pub record: Option<RustRecord>,
 }
 
 impl ToTokens for RustStructDef {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let crate_ = crate::crate_name();
         let name = &self.name;
         let fields = self.fields.iter();
 
+        let record = self.record.as_ref().map(|r| {
+            let nsid = &r.nsid;
+            let key = match &r.key {
+                RecordKeyDef::Tid => quote! { Tid },
+                RecordKeyDef::Nsid => quote! { Nsid },
+                RecordKeyDef::Literal(value) => quote! { Literal(#value) },
+                RecordKeyDef::Any => quote! { Any },
+            };
+
+            quote! {
+                impl #crate_::record::Record for #name {
+                    const NSID: &'static str = #nsid;
+                    const KEY: #crate_::record::KeyType = #crate_::record::KeyType::#key;
+                }
+            }
+        });
+
         quote! {
             #[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
             pub struct #name {
                 #(#fields,)*
             }
+            #record
         }
         .to_tokens(tokens)
     }
 }
 
+/// The record type of a struct.
+#[derive(Debug)]
+pub struct RustRecord {
+    pub nsid: String,
+    pub key: RecordKeyDef,
+}
+
 #[derive(Debug)]
 pub struct RustStructField {
     pub doc: Option<String>,
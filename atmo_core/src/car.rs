@@ -0,0 +1,622 @@
+//! Content-addressable archives (CAR files).
+//!
+//! ATProto uses [CAR v1] files to transfer repository blocks, e.g. in the output of
+//! `com.atproto.sync.getRepo` and in the `blocks` field of firehose `#commit` messages. A CAR file
+//! consists of a DAG-CBOR header listing the root CIDs, followed by a sequence of sections, each
+//! containing a CID and the block it addresses.
+//!
+//! [CAR v1]: https://ipld.io/specs/transport/car/carv1/
+
+use std::{
+    fmt,
+    io::{self, Read, Write},
+};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::CidLink;
+
+/// The default maximum size of a single CAR section, in bytes.
+///
+/// This bounds the amount of memory used to read a single block. ATProto limits records to 1 MiB,
+/// but blobs may appear in CAR files as well.
+pub const DEFAULT_MAX_SECTION_LEN: usize = 2 * 1024 * 1024;
+
+// A varint-encoded `u64` is at most 10 bytes long.
+const MAX_VARINT_LEN: usize = 10;
+
+/// The header of a CAR file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CarHeader {
+    /// The CAR format version. Only version 1 is supported.
+    pub version: u64,
+    /// The root CIDs of the archive.
+    pub roots: Vec<CidLink>,
+}
+
+impl CarHeader {
+    /// Creates a version 1 header with the given roots.
+    #[inline]
+    pub fn new(roots: Vec<CidLink>) -> Self {
+        CarHeader { version: 1, roots }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, CarError> {
+        let header: CarHeader = serde_ipld_dagcbor::from_slice(bytes).map_err(CarError::Header)?;
+
+        if header.version != 1 {
+            return Err(CarError::UnsupportedVersion(header.version));
+        }
+
+        Ok(header)
+    }
+}
+
+/// A streaming reader for CAR files.
+///
+/// `CarReader` iterates over the blocks of a CAR file as `(CidLink, Bytes)` pairs. Each block is
+/// verified against its CID as it is read, and only one section is held in memory at a time.
+pub struct CarReader<R> {
+    reader: R,
+    header: CarHeader,
+    max_section_len: usize,
+    done: bool,
+}
+
+impl<R> CarReader<R>
+where
+    R: Read,
+{
+    /// Creates a `CarReader`, reading the header from `reader`.
+    pub fn new(reader: R) -> Result<Self, CarError> {
+        Self::with_max_section_len(reader, DEFAULT_MAX_SECTION_LEN)
+    }
+
+    /// Creates a `CarReader` which rejects sections longer than `max_section_len` bytes.
+    pub fn with_max_section_len(mut reader: R, max_section_len: usize) -> Result<Self, CarError> {
+        let header = match read_section(&mut reader, max_section_len)? {
+            Some(buf) => CarHeader::from_bytes(&buf)?,
+            None => return Err(CarError::Io(io::ErrorKind::UnexpectedEof.into())),
+        };
+
+        Ok(CarReader {
+            reader,
+            header,
+            max_section_len,
+            done: false,
+        })
+    }
+
+    /// Returns the header of the CAR file.
+    #[inline]
+    pub fn header(&self) -> &CarHeader {
+        &self.header
+    }
+
+    /// Returns the root CIDs of the CAR file.
+    #[inline]
+    pub fn roots(&self) -> &[CidLink] {
+        &self.header.roots
+    }
+
+    /// Consumes the `CarReader`, returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for CarReader<R>
+where
+    R: Read,
+{
+    type Item = Result<(CidLink, Bytes), CarError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = match read_section(&mut self.reader, self.max_section_len) {
+            Ok(Some(buf)) => parse_block(buf),
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Err(e) => Err(e),
+        };
+
+        // Stop after the first error; the stream position is no longer meaningful.
+        self.done = res.is_err();
+
+        Some(res)
+    }
+}
+
+/// A writer for CAR files.
+pub struct CarWriter<W> {
+    writer: W,
+}
+
+impl<W> CarWriter<W>
+where
+    W: Write,
+{
+    /// Creates a `CarWriter`, writing a header with the given roots to `writer`.
+    pub fn new(mut writer: W, roots: Vec<CidLink>) -> Result<Self, CarError> {
+        let header = serde_ipld_dagcbor::to_vec(&CarHeader::new(roots))
+            .map_err(|e| CarError::Io(io::Error::other(e)))?;
+
+        write_varint(&mut writer, header.len() as u64)?;
+        writer.write_all(&header)?;
+
+        Ok(CarWriter { writer })
+    }
+
+    /// Writes a block to the CAR file.
+    ///
+    /// The caller is responsible for ensuring that `cid` addresses `data`.
+    pub fn write_block(&mut self, cid: &CidLink, data: &[u8]) -> Result<(), CarError> {
+        let cid = cid.to_bytes();
+
+        write_varint(&mut self.writer, (cid.len() + data.len()) as u64)?;
+        self.writer.write_all(&cid)?;
+        self.writer.write_all(data)?;
+
+        Ok(())
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, CarError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads all blocks from an in-memory CAR file.
+///
+/// This is a convenience wrapper around [`CarReader`] for small archives, such as the `blocks`
+/// field of a firehose `#commit` message.
+pub fn read_all(bytes: &[u8]) -> Result<(CarHeader, Vec<(CidLink, Bytes)>), CarError> {
+    let mut reader = CarReader::new(bytes)?;
+    let blocks = reader.by_ref().collect::<Result<_, _>>()?;
+
+    Ok((reader.header, blocks))
+}
+
+/// Reads a varint-length-prefixed section, returning `None` on a clean EOF.
+fn read_section<R>(reader: &mut R, max_len: usize) -> Result<Option<Vec<u8>>, CarError>
+where
+    R: Read,
+{
+    let Some(len) = read_varint(reader)? else {
+        return Ok(None);
+    };
+
+    let len = check_section_len(len, max_len)?;
+
+    let mut buf = vec![0; len];
+    reader.read_exact(&mut buf)?;
+
+    Ok(Some(buf))
+}
+
+fn check_section_len(len: u64, max_len: usize) -> Result<usize, CarError> {
+    match usize::try_from(len) {
+        Ok(len) if len <= max_len => Ok(len),
+        _ => Err(CarError::SectionTooLong(len)),
+    }
+}
+
+/// Splits a section into a CID and block, verifying the block's hash.
+fn parse_block(section: Vec<u8>) -> Result<(CidLink, Bytes), CarError> {
+    let mut rest = section.as_slice();
+    let cid = cid::Cid::read_bytes(&mut rest).map_err(CarError::Cid)?;
+    let cid_len = section.len() - rest.len();
+
+    let mut data = Bytes::from(section);
+    let data = data.split_off(cid_len);
+
+    let cid = CidLink::from(cid);
+
+    if !cid.matches(&data) {
+        return Err(CarError::HashMismatch(cid));
+    }
+
+    Ok((cid, data))
+}
+
+/// Reads an unsigned LEB128 varint, returning `None` on EOF before the first byte.
+fn read_varint<R>(reader: &mut R) -> Result<Option<u64>, CarError>
+where
+    R: Read,
+{
+    let mut value = 0_u64;
+
+    for i in 0..MAX_VARINT_LEN {
+        let mut byte = [0];
+        if let Err(e) = reader.read_exact(&mut byte) {
+            return match e.kind() {
+                io::ErrorKind::UnexpectedEof if i == 0 => Ok(None),
+                _ => Err(e.into()),
+            };
+        }
+
+        // The 10th byte holds only the highest bit of a `u64`.
+        if i == MAX_VARINT_LEN - 1 && byte[0] > 1 {
+            return Err(CarError::InvalidVarint);
+        }
+
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+
+    Err(CarError::InvalidVarint)
+}
+
+fn encode_varint(mut value: u64, buf: &mut [u8; MAX_VARINT_LEN]) -> &[u8] {
+    let mut i = 0;
+
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            buf[i] = byte;
+            return &buf[..=i];
+        }
+
+        buf[i] = byte | 0x80;
+        i += 1;
+    }
+}
+
+fn write_varint<W>(writer: &mut W, value: u64) -> Result<(), CarError>
+where
+    W: Write,
+{
+    let mut buf = [0; MAX_VARINT_LEN];
+    writer.write_all(encode_varint(value, &mut buf))?;
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+pub use self::async_::AsyncCarReader;
+
+#[cfg(feature = "async")]
+mod async_ {
+    use tokio::io::{AsyncRead, AsyncReadExt};
+
+    use super::*;
+
+    /// An asynchronous streaming reader for CAR files.
+    ///
+    /// This is the asynchronous equivalent of [`CarReader`]. Memory use is bounded by the maximum
+    /// section length.
+    pub struct AsyncCarReader<R> {
+        reader: R,
+        header: CarHeader,
+        max_section_len: usize,
+        done: bool,
+    }
+
+    impl<R> AsyncCarReader<R>
+    where
+        R: AsyncRead + Unpin,
+    {
+        /// Creates an `AsyncCarReader`, reading the header from `reader`.
+        pub async fn new(reader: R) -> Result<Self, CarError> {
+            Self::with_max_section_len(reader, DEFAULT_MAX_SECTION_LEN).await
+        }
+
+        /// Creates an `AsyncCarReader` which rejects sections longer than `max_section_len` bytes.
+        pub async fn with_max_section_len(
+            mut reader: R,
+            max_section_len: usize,
+        ) -> Result<Self, CarError> {
+            let header = match read_section(&mut reader, max_section_len).await? {
+                Some(buf) => CarHeader::from_bytes(&buf)?,
+                None => return Err(CarError::Io(io::ErrorKind::UnexpectedEof.into())),
+            };
+
+            Ok(AsyncCarReader {
+                reader,
+                header,
+                max_section_len,
+                done: false,
+            })
+        }
+
+        /// Returns the header of the CAR file.
+        #[inline]
+        pub fn header(&self) -> &CarHeader {
+            &self.header
+        }
+
+        /// Returns the root CIDs of the CAR file.
+        #[inline]
+        pub fn roots(&self) -> &[CidLink] {
+            &self.header.roots
+        }
+
+        /// Reads the next block, returning `None` at the end of the file.
+        pub async fn next_block(&mut self) -> Option<Result<(CidLink, Bytes), CarError>> {
+            if self.done {
+                return None;
+            }
+
+            let res = match read_section(&mut self.reader, self.max_section_len).await {
+                Ok(Some(buf)) => parse_block(buf),
+                Ok(None) => {
+                    self.done = true;
+                    return None;
+                }
+                Err(e) => Err(e),
+            };
+
+            self.done = res.is_err();
+
+            Some(res)
+        }
+
+        /// Consumes the `AsyncCarReader`, returning the underlying reader.
+        #[inline]
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+    }
+
+    async fn read_section<R>(reader: &mut R, max_len: usize) -> Result<Option<Vec<u8>>, CarError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let Some(len) = read_varint(reader).await? else {
+            return Ok(None);
+        };
+
+        let len = check_section_len(len, max_len)?;
+
+        let mut buf = vec![0; len];
+        reader.read_exact(&mut buf).await?;
+
+        Ok(Some(buf))
+    }
+
+    async fn read_varint<R>(reader: &mut R) -> Result<Option<u64>, CarError>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut value = 0_u64;
+
+        for i in 0..MAX_VARINT_LEN {
+            let byte = match reader.read_u8().await {
+                Ok(b) => b,
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && i == 0 => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+
+            // The 10th byte holds only the highest bit of a `u64`.
+            if i == MAX_VARINT_LEN - 1 && byte > 1 {
+                return Err(CarError::InvalidVarint);
+            }
+
+            value |= u64::from(byte & 0x7f) << (7 * i);
+
+            if byte & 0x80 == 0 {
+                return Ok(Some(value));
+            }
+        }
+
+        Err(CarError::InvalidVarint)
+    }
+}
+
+/// An error produced while reading or writing a CAR file.
+#[derive(Debug)]
+pub enum CarError {
+    /// A section contained an invalid CID.
+    Cid(cid::Error),
+    /// A block did not match the hash in its CID.
+    HashMismatch(CidLink),
+    /// The header could not be decoded.
+    Header(serde_ipld_dagcbor::DecodeError<std::convert::Infallible>),
+    /// A varint length prefix was malformed.
+    InvalidVarint,
+    /// An I/O error occurred.
+    Io(io::Error),
+    /// A section exceeded the maximum allowed length.
+    SectionTooLong(u64),
+    /// The header specified an unsupported CAR version.
+    UnsupportedVersion(u64),
+}
+
+impl From<io::Error> for CarError {
+    #[inline]
+    fn from(e: io::Error) -> Self {
+        CarError::Io(e)
+    }
+}
+
+impl fmt::Display for CarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CarError::Cid(e) => write!(f, "invalid CID: {e}"),
+            CarError::HashMismatch(cid) => write!(f, "block does not match CID {cid}"),
+            CarError::Header(e) => write!(f, "invalid CAR header: {e}"),
+            CarError::InvalidVarint => f.write_str("invalid varint"),
+            CarError::Io(e) => fmt::Display::fmt(e, f),
+            CarError::SectionTooLong(len) => write!(f, "CAR section too long: {len} bytes"),
+            CarError::UnsupportedVersion(v) => write!(f, "unsupported CAR version: {v}"),
+        }
+    }
+}
+
+impl std::error::Error for CarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CarError::Cid(e) => Some(e),
+            CarError::Header(e) => Some(e),
+            CarError::Io(e) => Some(e),
+            CarError::HashMismatch(_)
+            | CarError::InvalidVarint
+            | CarError::SectionTooLong(_)
+            | CarError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cid::Codec;
+
+    use super::*;
+
+    fn example_blocks() -> Vec<(CidLink, Bytes)> {
+        [&b"hello world"[..], b"", &[0xa5; 300]]
+            .into_iter()
+            .map(|data| {
+                (
+                    CidLink::compute(Codec::Raw, data),
+                    Bytes::copy_from_slice(data),
+                )
+            })
+            .collect()
+    }
+
+    fn write_example(blocks: &[(CidLink, Bytes)]) -> Vec<u8> {
+        let roots = vec![blocks[0].0.clone()];
+        let mut writer = CarWriter::new(Vec::new(), roots).unwrap();
+
+        for (cid, data) in blocks {
+            writer.write_block(cid, data).unwrap();
+        }
+
+        writer.finish().unwrap()
+    }
+
+    #[test]
+    fn varint_roundtrip() {
+        for value in [0, 1, 127, 128, 300, 16384, u64::from(u32::MAX), u64::MAX] {
+            let mut buf = [0; MAX_VARINT_LEN];
+            let mut encoded = encode_varint(value, &mut buf);
+
+            assert_eq!(read_varint(&mut encoded).unwrap(), Some(value));
+            assert!(encoded.is_empty());
+        }
+    }
+
+    #[test]
+    fn varint_overflow() {
+        let mut encoded: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert!(matches!(
+            read_varint(&mut encoded),
+            Err(CarError::InvalidVarint)
+        ));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let blocks = example_blocks();
+        let car = write_example(&blocks);
+
+        let (header, read) = read_all(&car).unwrap();
+
+        assert_eq!(header, CarHeader::new(vec![blocks[0].0.clone()]));
+        assert_eq!(read, blocks);
+    }
+
+    #[test]
+    fn hash_mismatch() {
+        let blocks = example_blocks();
+        let mut car = write_example(&blocks);
+
+        // Corrupt the last byte of the last block.
+        *car.last_mut().unwrap() ^= 0xff;
+
+        let mut reader = CarReader::new(car.as_slice()).unwrap();
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next().unwrap(),
+            Err(CarError::HashMismatch(_))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn section_too_long() {
+        let blocks = example_blocks();
+        let car = write_example(&blocks);
+
+        let mut reader = CarReader::with_max_section_len(car.as_slice(), 100).unwrap();
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next().unwrap(),
+            Err(CarError::SectionTooLong(_))
+        ));
+    }
+
+    #[test]
+    fn truncated() {
+        let blocks = example_blocks();
+        let car = write_example(&blocks);
+
+        let mut reader = CarReader::new(&car[..car.len() - 1]).unwrap();
+
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(reader.next().unwrap(), Err(CarError::Io(_))));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let header = serde_ipld_dagcbor::to_vec(&CarHeader {
+            version: 2,
+            roots: vec![],
+        })
+        .unwrap();
+
+        let mut car = Vec::new();
+        write_varint(&mut car, header.len() as u64).unwrap();
+        car.extend(header);
+
+        assert!(matches!(
+            CarReader::new(car.as_slice()),
+            Err(CarError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_varint_overflow() {
+        let car: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert!(matches!(
+            AsyncCarReader::new(car).await,
+            Err(CarError::InvalidVarint)
+        ));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_roundtrip() {
+        let blocks = example_blocks();
+        let car = write_example(&blocks);
+
+        let mut reader = AsyncCarReader::new(car.as_slice()).await.unwrap();
+        assert_eq!(reader.roots(), &[blocks[0].0.clone()]);
+
+        let mut read = Vec::new();
+        while let Some(res) = reader.next_block().await {
+            read.push(res.unwrap());
+        }
+
+        assert_eq!(read, blocks);
+    }
+}
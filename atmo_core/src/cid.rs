@@ -1,16 +1,94 @@
 //! Content Identifiers.
 
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-use cid::multibase;
+use cid::{multibase, multihash::Multihash};
 use serde::{de::Error as _, ser::SerializeStruct, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 const BASE: multibase::Base = multibase::Base::Base32Lower;
 
+/// Multicodec code for SHA2-256.
+const SHA2_256: u64 = 0x12;
+
+/// Multicodec code for identity (inline) hashes.
+const IDENTITY: u64 = 0x00;
+
+/// The content codecs used by ATProto CIDs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u64)]
+pub enum Codec {
+    /// Raw binary data, used for blobs.
+    Raw = 0x55,
+    /// DAG-CBOR, used for records and repository nodes.
+    DagCbor = 0x71,
+}
+
 /// CID link value, corresponding to the `cid-link` Lexicon type.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CidLink(Box<cid::Cid>);
 
+impl CidLink {
+    /// Computes the CIDv1 of `data` using SHA2-256 and the given codec.
+    pub fn compute(codec: Codec, data: &[u8]) -> CidLink {
+        let digest = Sha256::digest(data);
+        let multihash =
+            Multihash::wrap(SHA2_256, &digest).expect("SHA2-256 digest should fit in a multihash");
+
+        CidLink(Box::new(cid::Cid::new_v1(codec as u64, multihash)))
+    }
+
+    /// Returns the multicodec code of the content addressed by this CID.
+    #[inline]
+    pub fn codec(&self) -> u64 {
+        self.0.codec()
+    }
+
+    /// Returns `true` if `data` hashes to this CID.
+    ///
+    /// Only SHA2-256 and identity hashes are supported; CIDs using any other hash function never
+    /// match.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        let hash = self.0.hash();
+
+        match hash.code() {
+            SHA2_256 => Sha256::digest(data).as_slice() == hash.digest(),
+            IDENTITY => data == hash.digest(),
+            _ => false,
+        }
+    }
+
+    /// Returns the binary encoding of this CID.
+    #[inline]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    /// Returns a reference to the underlying [`cid::Cid`].
+    #[inline]
+    pub fn as_cid(&self) -> &cid::Cid {
+        &self.0
+    }
+}
+
+impl From<cid::Cid> for CidLink {
+    #[inline]
+    fn from(cid: cid::Cid) -> Self {
+        CidLink(Box::new(cid))
+    }
+}
+
+impl fmt::Display for CidLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = self
+            .0
+            .to_string_of_base(BASE)
+            .expect("CIDv1 serialization should never fail");
+
+        f.write_str(&s)
+    }
+}
+
 impl Serialize for CidLink {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
@@ -97,11 +175,24 @@ impl<'de> Deserialize<'de> for CidString {
     }
 }
 
+impl From<CidLink> for CidString {
+    #[inline]
+    fn from(link: CidLink) -> Self {
+        CidString(link.0)
+    }
+}
+
+impl From<CidString> for CidLink {
+    #[inline]
+    fn from(s: CidString) -> Self {
+        CidLink(s.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::iter;
 
-    use cid::multihash::Multihash;
     use proptest::prelude::*;
 
     use super::*;
@@ -129,17 +220,11 @@ mod tests {
         }
     }
 
-    // TODO(dp): expose
-    enum Codec {
-        Raw = 0x55,
-        // DagCbor = 0x71,
-    }
-
     fn gen_cid(codec: Codec) -> cid::CidGeneric<64> {
         let mut bytes = Vec::new();
         bytes.push(0x12); // sha2-256
         bytes.push(0x20); // 256-bit digest
-        bytes.extend(iter::repeat(0).take(0x20));
+        bytes.extend(iter::repeat_n(0, 0x20));
 
         let multihash = Multihash::from_bytes(&bytes).unwrap();
 
@@ -150,6 +235,19 @@ mod tests {
         gen_cid(codec).to_string_of_base(base).unwrap()
     }
 
+    #[test]
+    fn cid_link_compute() {
+        let cid = CidLink::compute(Codec::Raw, b"hello world");
+
+        assert_eq!(
+            cid.to_string(),
+            "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"
+        );
+        assert_eq!(cid.codec(), Codec::Raw as u64);
+        assert!(cid.matches(b"hello world"));
+        assert!(!cid.matches(b"goodbye world"));
+    }
+
     #[test]
     fn cid_string_bad_base() {
         let s = gen_cid_string(Codec::Raw, multibase::Base::Base58Btc);
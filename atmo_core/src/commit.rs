@@ -0,0 +1,323 @@
+//! Signed repository commits.
+//!
+//! A commit is the root object of an ATProto repository. It links to the root of the repository's
+//! [MST](crate::mst) and is signed by the account's signing key. See the [Commit Objects] section
+//! of the ATProto specification.
+//!
+//! [Commit Objects]: https://atproto.com/specs/repository#commit-objects
+
+use std::{convert::Infallible, fmt};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cid::Codec,
+    crypto::{CryptoError, PublicKey, Signer},
+    did::DidDoc,
+    CidLink, Did, Tid,
+};
+
+/// The current repository format version.
+pub const VERSION: u64 = 3;
+
+/// A repository commit, without its signature.
+///
+/// The signature of a [`Commit`] is computed over the DAG-CBOR encoding of this object.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UnsignedCommit {
+    /// The DID of the account which owns the repository.
+    pub did: Did,
+    /// The repository format version.
+    pub version: u64,
+    /// A link to the root of the repository's MST.
+    pub data: CidLink,
+    /// The revision of the repository, which increases with each commit.
+    pub rev: Tid,
+    /// A link to the previous commit, if any.
+    ///
+    /// This is usually `None`, but is always encoded.
+    pub prev: Option<CidLink>,
+}
+
+impl UnsignedCommit {
+    /// Creates a commit using the current repository format version.
+    #[inline]
+    pub fn new(did: Did, data: CidLink, rev: Tid, prev: Option<CidLink>) -> Self {
+        UnsignedCommit {
+            did,
+            version: VERSION,
+            data,
+            rev,
+            prev,
+        }
+    }
+
+    /// Encodes this commit as DAG-CBOR, producing the bytes to be signed.
+    pub fn encode(&self) -> Vec<u8> {
+        serde_ipld_dagcbor::to_vec(self).expect("commit serialization should never fail")
+    }
+
+    /// Signs this commit with `signer`.
+    pub fn sign<S>(self, signer: &S) -> Result<Commit, S::Error>
+    where
+        S: Signer + ?Sized,
+    {
+        let sig = signer.sign(&self.encode())?;
+
+        Ok(Commit {
+            did: self.did,
+            version: self.version,
+            data: self.data,
+            rev: self.rev,
+            prev: self.prev,
+            sig: sig.into(),
+        })
+    }
+}
+
+/// A signed repository commit.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Commit {
+    /// The DID of the account which owns the repository.
+    pub did: Did,
+    /// The repository format version.
+    pub version: u64,
+    /// A link to the root of the repository's MST.
+    pub data: CidLink,
+    /// The revision of the repository, which increases with each commit.
+    pub rev: Tid,
+    /// A link to the previous commit, if any.
+    pub prev: Option<CidLink>,
+    /// The signature of the DAG-CBOR encoded [`UnsignedCommit`].
+    #[serde(with = "crate::bytes::serde")]
+    pub sig: Bytes,
+}
+
+impl Commit {
+    /// Decodes a commit from a DAG-CBOR block.
+    ///
+    /// The signature is not verified.
+    pub fn decode(bytes: &[u8]) -> Result<Commit, CommitError> {
+        let commit: Commit = serde_ipld_dagcbor::from_slice(bytes).map_err(CommitError::Decode)?;
+
+        if commit.version != VERSION {
+            return Err(CommitError::UnsupportedVersion(commit.version));
+        }
+
+        Ok(commit)
+    }
+
+    /// Encodes this commit as a DAG-CBOR block.
+    pub fn encode(&self) -> Vec<u8> {
+        serde_ipld_dagcbor::to_vec(self).expect("commit serialization should never fail")
+    }
+
+    /// Encodes this commit, returning the resulting block and its CID.
+    pub fn to_block(&self) -> (CidLink, Bytes) {
+        let bytes = self.encode();
+
+        (CidLink::compute(Codec::DagCbor, &bytes), bytes.into())
+    }
+
+    /// Returns this commit without its signature.
+    pub fn unsigned(&self) -> UnsignedCommit {
+        UnsignedCommit {
+            did: self.did.clone(),
+            version: self.version,
+            data: self.data.clone(),
+            rev: self.rev,
+            prev: self.prev.clone(),
+        }
+    }
+
+    /// Verifies the signature of this commit against `key`.
+    pub fn verify(&self, key: &PublicKey) -> Result<(), CommitError> {
+        key.verify(&self.unsigned().encode(), &self.sig)
+            .map_err(CommitError::Crypto)
+    }
+
+    /// Verifies the signature of this commit against the ATProto signing key in `doc`.
+    ///
+    /// `doc` must be the DID document of the commit's DID.
+    pub fn verify_with_doc(&self, doc: &DidDoc) -> Result<(), CommitError> {
+        if doc.id != self.did {
+            return Err(CommitError::DidMismatch);
+        }
+
+        let key = doc
+            .signing_key()
+            .ok_or(CommitError::MissingSigningKey)?
+            .public_key()
+            .map_err(CommitError::Crypto)?;
+
+        self.verify(&key)
+    }
+}
+
+/// An error produced while decoding or verifying a commit.
+#[derive(Debug)]
+pub enum CommitError {
+    /// The signing key was invalid, or the signature did not match.
+    Crypto(CryptoError),
+    /// The commit could not be decoded.
+    Decode(serde_ipld_dagcbor::DecodeError<Infallible>),
+    /// The DID document belongs to a different DID than the commit.
+    DidMismatch,
+    /// The DID document does not contain an ATProto signing key.
+    MissingSigningKey,
+    /// The commit uses an unsupported repository format version.
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for CommitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitError::Crypto(e) => fmt::Display::fmt(e, f),
+            CommitError::Decode(e) => write!(f, "invalid commit: {e}"),
+            CommitError::DidMismatch => f.write_str("DID document does not match commit DID"),
+            CommitError::MissingSigningKey => f.write_str("DID document has no signing key"),
+            CommitError::UnsupportedVersion(v) => write!(f, "unsupported commit version: {v}"),
+        }
+    }
+}
+
+impl std::error::Error for CommitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CommitError::Crypto(e) => Some(e),
+            CommitError::Decode(e) => Some(e),
+            CommitError::DidMismatch
+            | CommitError::MissingSigningKey
+            | CommitError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use p256::ecdsa::{signature::Signer as _, Signature, SigningKey};
+    use serde_json::json;
+
+    use super::*;
+
+    struct TestKey(SigningKey);
+
+    impl Signer for TestKey {
+        type Error = Infallible;
+
+        fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Infallible> {
+            let sig: Signature = self.0.sign(msg);
+            Ok(sig.normalize_s().unwrap_or(sig).to_vec())
+        }
+    }
+
+    impl TestKey {
+        fn public_key(&self) -> PublicKey {
+            PublicKey::P256(*self.0.verifying_key())
+        }
+
+        fn multibase(&self) -> String {
+            self.public_key().to_multibase()
+        }
+    }
+
+    fn did() -> Did {
+        Did::from_str("did:plc:z72i7hdynmk6r22z27h6tvur").unwrap()
+    }
+
+    fn example() -> UnsignedCommit {
+        UnsignedCommit::new(
+            did(),
+            CidLink::compute(Codec::DagCbor, b"mst"),
+            Tid::from_str("3jzfcijpj2z2a").unwrap(),
+            None,
+        )
+    }
+
+    fn doc(key: &str) -> DidDoc {
+        serde_json::from_value(json!({
+            "@context": ["https://www.w3.org/ns/did/v1"],
+            "id": did(),
+            "alsoKnownAs": [],
+            "verificationMethod": [{
+                "id": format!("{}#atproto", did().as_str()),
+                "type": "Multikey",
+                "controller": did(),
+                "publicKeyMultibase": key,
+            }],
+            "service": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let key = TestKey(SigningKey::from_slice(&[7; 32]).unwrap());
+        let commit = example().sign(&key).unwrap();
+
+        assert_eq!(commit.unsigned(), example());
+        assert!(commit.verify(&key.public_key()).is_ok());
+        assert!(commit.verify_with_doc(&doc(&key.multibase())).is_ok());
+
+        let other = TestKey(SigningKey::from_slice(&[8; 32]).unwrap());
+        assert!(matches!(
+            commit.verify(&other.public_key()),
+            Err(CommitError::Crypto(CryptoError::InvalidSignature))
+        ));
+        assert!(matches!(
+            commit.verify_with_doc(&doc(&other.multibase())),
+            Err(CommitError::Crypto(CryptoError::InvalidSignature))
+        ));
+
+        let mut tampered = commit.clone();
+        tampered.rev = Tid::from_str("3jzfcijpj2z2b").unwrap();
+        assert!(tampered.verify(&key.public_key()).is_err());
+    }
+
+    #[test]
+    fn encoding() {
+        let key = TestKey(SigningKey::from_slice(&[7; 32]).unwrap());
+        let commit = example().sign(&key).unwrap();
+
+        let (cid, bytes) = commit.to_block();
+        assert!(cid.matches(&bytes));
+        assert_eq!(Commit::decode(&bytes).unwrap(), commit);
+
+        // `prev` is encoded as null rather than omitted.
+        let ipld: ipld_core::ipld::Ipld = serde_ipld_dagcbor::from_slice(&bytes).unwrap();
+        assert_eq!(
+            ipld.get("prev").unwrap(),
+            Some(&ipld_core::ipld::Ipld::Null)
+        );
+
+        let mut v2 = commit;
+        v2.version = 2;
+        assert!(matches!(
+            Commit::decode(&v2.encode()),
+            Err(CommitError::UnsupportedVersion(2))
+        ));
+    }
+
+    #[test]
+    fn verify_with_doc_errors() {
+        let key = TestKey(SigningKey::from_slice(&[7; 32]).unwrap());
+        let commit = example().sign(&key).unwrap();
+
+        let mut other_did = doc(&key.multibase());
+        other_did.id = Did::from_str("did:plc:ewvi7nxzyoun6zhxrhs64oiz").unwrap();
+        assert!(matches!(
+            commit.verify_with_doc(&other_did),
+            Err(CommitError::DidMismatch)
+        ));
+
+        let mut no_key = doc(&key.multibase());
+        no_key.verification_method = None;
+        assert!(matches!(
+            commit.verify_with_doc(&no_key),
+            Err(CommitError::MissingSigningKey)
+        ));
+    }
+}
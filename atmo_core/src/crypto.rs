@@ -0,0 +1,449 @@
+//! Cryptographic keys and signatures.
+//!
+//! ATProto uses ECDSA signatures over two curves: NIST P-256 and secp256k1 (K-256). Signatures are
+//! computed over the SHA-256 hash of the message, encoded in the 64-byte compact `r || s` form, and
+//! must be in "low-S" form. See the [Cryptography] section of the ATProto specification.
+//!
+//! [Cryptography]: https://atproto.com/specs/cryptography
+
+use std::{fmt, str::FromStr};
+
+use cid::multibase;
+
+use crate::Did;
+
+/// Multicodec prefix of a compressed P-256 public key (`0x1200`), varint-encoded.
+const P256_PREFIX: [u8; 2] = [0x80, 0x24];
+
+/// Multicodec prefix of a compressed secp256k1 public key (`0xe7`), varint-encoded.
+const K256_PREFIX: [u8; 2] = [0xe7, 0x01];
+
+/// Length of a compressed curve point.
+const COMPRESSED_LEN: usize = 33;
+
+const DID_KEY_PREFIX: &str = "did:key:";
+
+/// An elliptic curve supported by ATProto.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Curve {
+    /// NIST P-256, also known as secp256r1.
+    P256,
+    /// secp256k1, also known as K-256.
+    K256,
+}
+
+/// A public key used to verify ATProto signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PublicKey {
+    /// A NIST P-256 public key.
+    P256(p256::ecdsa::VerifyingKey),
+    /// A secp256k1 public key.
+    K256(k256::ecdsa::VerifyingKey),
+}
+
+impl PublicKey {
+    /// Returns the curve of this key.
+    #[inline]
+    pub fn curve(&self) -> Curve {
+        match self {
+            PublicKey::P256(_) => Curve::P256,
+            PublicKey::K256(_) => Curve::K256,
+        }
+    }
+
+    /// Parses a `did:key` DID, such as `did:key:zQ3shqwJEJyMBsBXCWyCBpUBMqxcon9oHB7mCvx4sSpMdLJwc`.
+    pub fn from_did_key(s: &str) -> Result<PublicKey, CryptoError> {
+        let multibase = s
+            .strip_prefix(DID_KEY_PREFIX)
+            .ok_or(CryptoError::InvalidEncoding)?;
+
+        PublicKey::from_multibase(multibase)
+    }
+
+    /// Returns the `did:key` representation of this key.
+    pub fn to_did_key(&self) -> String {
+        format!("{DID_KEY_PREFIX}{}", self.to_multibase())
+    }
+
+    /// Parses a multibase-encoded public key, as found in the `publicKeyMultibase` field of a DID
+    /// document verification method.
+    ///
+    /// The key must be base58btc-encoded, with a multicodec prefix identifying the curve, followed
+    /// by the compressed point.
+    pub fn from_multibase(s: &str) -> Result<PublicKey, CryptoError> {
+        let (base, bytes) = multibase::decode(s).map_err(|_| CryptoError::InvalidEncoding)?;
+
+        if base != multibase::Base::Base58Btc {
+            return Err(CryptoError::InvalidEncoding);
+        }
+
+        PublicKey::from_multicodec(&bytes)
+    }
+
+    /// Returns the multibase representation of this key, suitable for the `publicKeyMultibase`
+    /// field of a DID document verification method.
+    pub fn to_multibase(&self) -> String {
+        let mut bytes = Vec::with_capacity(P256_PREFIX.len() + COMPRESSED_LEN);
+
+        match self {
+            PublicKey::P256(key) => {
+                bytes.extend_from_slice(&P256_PREFIX);
+                bytes.extend_from_slice(key.to_encoded_point(true).as_bytes());
+            }
+            PublicKey::K256(key) => {
+                bytes.extend_from_slice(&K256_PREFIX);
+                bytes.extend_from_slice(key.to_encoded_point(true).as_bytes());
+            }
+        }
+
+        multibase::encode(multibase::Base::Base58Btc, bytes)
+    }
+
+    /// Parses a SEC1-encoded point on `curve`, which may be compressed or uncompressed.
+    ///
+    /// This is used for keys in legacy DID document formats; new keys should always use the
+    /// compressed multibase form.
+    pub fn from_sec1(curve: Curve, bytes: &[u8]) -> Result<PublicKey, CryptoError> {
+        match curve {
+            Curve::P256 => p256::ecdsa::VerifyingKey::from_sec1_bytes(bytes).map(PublicKey::P256),
+            Curve::K256 => k256::ecdsa::VerifyingKey::from_sec1_bytes(bytes).map(PublicKey::K256),
+        }
+        .map_err(|_| CryptoError::InvalidKey)
+    }
+
+    fn from_multicodec(bytes: &[u8]) -> Result<PublicKey, CryptoError> {
+        let compressed = |point: &[u8]| {
+            if point.len() == COMPRESSED_LEN {
+                Ok(())
+            } else {
+                Err(CryptoError::InvalidKey)
+            }
+        };
+
+        if let Some(point) = bytes.strip_prefix(&P256_PREFIX) {
+            compressed(point)?;
+            p256::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                .map(PublicKey::P256)
+                .map_err(|_| CryptoError::InvalidKey)
+        } else if let Some(point) = bytes.strip_prefix(&K256_PREFIX) {
+            compressed(point)?;
+            k256::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                .map(PublicKey::K256)
+                .map_err(|_| CryptoError::InvalidKey)
+        } else {
+            Err(CryptoError::UnsupportedKeyType)
+        }
+    }
+
+    /// Verifies an ECDSA signature of `msg` made by this key.
+    ///
+    /// `sig` must be a 64-byte compact signature in low-S form.
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> Result<(), CryptoError> {
+        use p256::ecdsa::signature::Verifier;
+
+        match self {
+            PublicKey::P256(key) => {
+                let sig = p256::ecdsa::Signature::from_slice(sig)
+                    .map_err(|_| CryptoError::InvalidSignature)?;
+
+                if sig.normalize_s().is_some() {
+                    return Err(CryptoError::InvalidSignature);
+                }
+
+                key.verify(msg, &sig)
+                    .map_err(|_| CryptoError::InvalidSignature)
+            }
+
+            PublicKey::K256(key) => {
+                let sig = k256::ecdsa::Signature::from_slice(sig)
+                    .map_err(|_| CryptoError::InvalidSignature)?;
+
+                if sig.normalize_s().is_some() {
+                    return Err(CryptoError::InvalidSignature);
+                }
+
+                key.verify(msg, &sig)
+                    .map_err(|_| CryptoError::InvalidSignature)
+            }
+        }
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = CryptoError;
+
+    /// Parses a `did:key` DID.
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PublicKey::from_did_key(s)
+    }
+}
+
+impl fmt::Display for PublicKey {
+    /// Formats this key as a `did:key` DID.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_did_key())
+    }
+}
+
+impl TryFrom<&Did> for PublicKey {
+    type Error = CryptoError;
+
+    #[inline]
+    fn try_from(did: &Did) -> Result<Self, Self::Error> {
+        PublicKey::from_did_key(did.as_str())
+    }
+}
+
+impl From<&PublicKey> for Did {
+    fn from(key: &PublicKey) -> Self {
+        Did::from_str(&key.to_did_key()).expect("did:key should be a valid DID")
+    }
+}
+
+/// A trait for types which can produce ATProto signatures.
+///
+/// Implementations must hash the message with SHA-256 and return a 64-byte compact signature in
+/// low-S form. This allows signing with keys held outside the process, such as in a hardware
+/// security module.
+pub trait Signer {
+    type Error;
+
+    /// Signs `msg`, returning the compact signature.
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// A private key used to sign ATProto data.
+///
+/// This is only available with the `signing` feature.
+#[cfg(feature = "signing")]
+#[derive(Clone, Debug)]
+pub enum Keypair {
+    /// A NIST P-256 private key.
+    P256(p256::ecdsa::SigningKey),
+    /// A secp256k1 private key.
+    K256(k256::ecdsa::SigningKey),
+}
+
+#[cfg(feature = "signing")]
+impl Keypair {
+    /// Generates a new random keypair on the given curve.
+    pub fn generate(curve: Curve) -> Keypair {
+        let mut rng = rand_core::OsRng;
+
+        match curve {
+            Curve::P256 => Keypair::P256(p256::ecdsa::SigningKey::random(&mut rng)),
+            Curve::K256 => Keypair::K256(k256::ecdsa::SigningKey::random(&mut rng)),
+        }
+    }
+
+    /// Creates a keypair from a 32-byte private key.
+    pub fn from_bytes(curve: Curve, bytes: &[u8]) -> Result<Keypair, CryptoError> {
+        match curve {
+            Curve::P256 => p256::ecdsa::SigningKey::from_slice(bytes).map(Keypair::P256),
+            Curve::K256 => k256::ecdsa::SigningKey::from_slice(bytes).map(Keypair::K256),
+        }
+        .map_err(|_| CryptoError::InvalidKey)
+    }
+
+    /// Returns the 32-byte private key.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Keypair::P256(key) => key.to_bytes().to_vec(),
+            Keypair::K256(key) => key.to_bytes().to_vec(),
+        }
+    }
+
+    /// Returns the curve of this keypair.
+    #[inline]
+    pub fn curve(&self) -> Curve {
+        match self {
+            Keypair::P256(_) => Curve::P256,
+            Keypair::K256(_) => Curve::K256,
+        }
+    }
+
+    /// Returns the public half of this keypair.
+    pub fn public_key(&self) -> PublicKey {
+        match self {
+            Keypair::P256(key) => PublicKey::P256(*key.verifying_key()),
+            Keypair::K256(key) => PublicKey::K256(*key.verifying_key()),
+        }
+    }
+}
+
+#[cfg(feature = "signing")]
+impl Signer for Keypair {
+    type Error = std::convert::Infallible;
+
+    fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        use p256::ecdsa::signature::Signer as _;
+
+        let sig = match self {
+            Keypair::P256(key) => {
+                let sig: p256::ecdsa::Signature = key.sign(msg);
+                sig.normalize_s().unwrap_or(sig).to_vec()
+            }
+            Keypair::K256(key) => {
+                let sig: k256::ecdsa::Signature = key.sign(msg);
+                sig.normalize_s().unwrap_or(sig).to_vec()
+            }
+        };
+
+        Ok(sig)
+    }
+}
+
+/// An error produced while parsing a key or verifying a signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CryptoError {
+    /// A key was not validly encoded.
+    InvalidEncoding,
+    /// A key was not a valid point on its curve.
+    InvalidKey,
+    /// A signature was malformed, not in low-S form, or did not match the message.
+    InvalidSignature,
+    /// A key used an unsupported curve.
+    UnsupportedKeyType,
+}
+
+impl fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CryptoError::InvalidEncoding => "invalid key encoding",
+            CryptoError::InvalidKey => "invalid public key",
+            CryptoError::InvalidSignature => "invalid signature",
+            CryptoError::UnsupportedKeyType => "unsupported key type",
+        })
+    }
+}
+
+impl std::error::Error for CryptoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_multibase() {
+        // From the ATProto cryptography specification.
+        let k256 =
+            PublicKey::from_multibase("zQ3shqwJEJyMBsBXCWyCBpUBMqxcon9oHB7mCvx4sSpMdLJwc").unwrap();
+        assert!(matches!(k256, PublicKey::K256(_)));
+
+        let p256 =
+            PublicKey::from_multibase("zDnaembgSGUhZULN2Caob4HLJPaxBh92N7rtH21TErzqf8HQo").unwrap();
+        assert!(matches!(p256, PublicKey::P256(_)));
+
+        // Wrong base.
+        assert_eq!(
+            PublicKey::from_multibase(
+                "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e"
+            ),
+            Err(CryptoError::InvalidEncoding)
+        );
+
+        // Ed25519 multicodec prefix.
+        let ed25519 = multibase::encode(multibase::Base::Base58Btc, [0xed, 0x01, 0, 0]);
+        assert_eq!(
+            PublicKey::from_multibase(&ed25519),
+            Err(CryptoError::UnsupportedKeyType)
+        );
+    }
+
+    #[test]
+    fn did_key_roundtrip() {
+        let cases = [
+            (
+                "did:key:zQ3shqwJEJyMBsBXCWyCBpUBMqxcon9oHB7mCvx4sSpMdLJwc",
+                Curve::K256,
+            ),
+            (
+                "did:key:zDnaembgSGUhZULN2Caob4HLJPaxBh92N7rtH21TErzqf8HQo",
+                Curve::P256,
+            ),
+        ];
+
+        for (s, curve) in cases {
+            let key = PublicKey::from_str(s).unwrap();
+            assert_eq!(key.curve(), curve);
+            assert_eq!(key.to_string(), s);
+
+            let did = Did::from(&key);
+            assert_eq!(did.as_str(), s);
+            assert_eq!(PublicKey::try_from(&did).unwrap(), key);
+        }
+
+        assert_eq!(
+            PublicKey::from_str("did:plc:z72i7hdynmk6r22z27h6tvur"),
+            Err(CryptoError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn reject_uncompressed() {
+        let key = PublicKey::from_str("did:key:zDnaembgSGUhZULN2Caob4HLJPaxBh92N7rtH21TErzqf8HQo")
+            .unwrap();
+        let PublicKey::P256(point) = key else {
+            unreachable!()
+        };
+
+        let mut bytes = P256_PREFIX.to_vec();
+        bytes.extend_from_slice(point.to_encoded_point(false).as_bytes());
+        let uncompressed = multibase::encode(multibase::Base::Base58Btc, bytes);
+
+        assert_eq!(
+            PublicKey::from_multibase(&uncompressed),
+            Err(CryptoError::InvalidKey)
+        );
+    }
+
+    #[cfg(feature = "signing")]
+    #[test]
+    fn keypair() {
+        let private = [7; 32];
+
+        for curve in [Curve::P256, Curve::K256] {
+            let keypair = Keypair::from_bytes(curve, &private).unwrap();
+            assert_eq!(keypair.to_bytes(), private);
+
+            let public = keypair.public_key();
+            assert_eq!(public.curve(), curve);
+            assert_eq!(PublicKey::from_str(&public.to_did_key()).unwrap(), public);
+        }
+
+        for curve in [Curve::P256, Curve::K256] {
+            let keypair = Keypair::generate(curve);
+            assert_eq!(keypair.curve(), curve);
+
+            let sig = keypair.sign(b"message").unwrap();
+            assert_eq!(keypair.public_key().verify(b"message", &sig), Ok(()));
+        }
+    }
+
+    #[test]
+    fn reject_high_s() {
+        use p256::ecdsa::signature::Signer as _;
+
+        let key = p256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap();
+        let public = PublicKey::P256(*key.verifying_key());
+
+        let sig: p256::ecdsa::Signature = key.sign(b"message");
+        let sig = sig.normalize_s().unwrap_or(sig);
+
+        assert_eq!(public.verify(b"message", &sig.to_bytes()), Ok(()));
+        assert_eq!(
+            public.verify(b"other message", &sig.to_bytes()),
+            Err(CryptoError::InvalidSignature)
+        );
+
+        // Negate `s` to produce the equivalent high-S signature.
+        let (r, s) = sig.split_scalars();
+        let high = p256::ecdsa::Signature::from_scalars(r.to_bytes(), (-s).to_bytes()).unwrap();
+
+        assert_eq!(
+            public.verify(b"message", &high.to_bytes()),
+            Err(CryptoError::InvalidSignature)
+        );
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use crate::{
+    crypto::{CryptoError, Curve, PublicKey},
     error::ParseError,
     impl_deserialize_via_from_str,
     parse::{is_uri_sub_delim, is_uri_unreserved, PCT_FRAGMENT_SET, PCT_QUERY_SET},
@@ -12,7 +13,7 @@ use crate::{
 };
 
 /// A Decentralized Identifier, or DID.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 pub struct Did(
     #[cfg_attr(
@@ -128,7 +129,7 @@ fn is_valid_did_ident(input: &[u8]) -> bool {
     true
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct DidUrl {
     did: Did,
     path: Option<String>,
@@ -136,6 +137,30 @@ pub struct DidUrl {
     fragment: Option<String>,
 }
 
+impl DidUrl {
+    #[inline]
+    pub fn did(&self) -> &Did {
+        &self.did
+    }
+
+    #[inline]
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns the query component, without the leading `?`.
+    #[inline]
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref().map(|q| &q[1..])
+    }
+
+    /// Returns the fragment component, without the leading `#`.
+    #[inline]
+    pub fn fragment(&self) -> Option<&str> {
+        self.fragment.as_deref().map(|f| &f[1..])
+    }
+}
+
 impl FromStr for DidUrl {
     type Err = ParseError;
 
@@ -303,49 +328,184 @@ fn match_query_or_fragment_char(input: &[u8]) -> Option<NonZeroUsize> {
 
 /// A DID document.
 ///
-/// This type represents the subset of DID documents recognized by ATProto.
-#[derive(Debug, Deserialize, Serialize)]
+/// This type models the parts of DID documents used by ATProto. Any other fields are preserved in
+/// `extra`, so a document can be round-tripped without loss.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DidDoc {
-    #[serde(rename = "@context")]
+    #[serde(rename = "@context", default)]
     pub context: Vec<String>,
     pub id: Did,
+    #[serde(default)]
     pub also_known_as: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
     pub verification_method: Option<Vec<VerificationMethod>>,
+    #[serde(default)]
     pub service: Vec<Service>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl DidDoc {
+    /// Returns the first handle listed in `alsoKnownAs`.
+    ///
+    /// The handle has not been verified; it must resolve back to this document's DID before it can
+    /// be trusted.
     pub fn handle(&self) -> Option<Handle> {
-        self.also_known_as
+        self.also_known_as.iter().find_map(|s| {
+            let handle = s.strip_prefix("at://")?;
+            Handle::from_str(handle).ok()
+        })
+    }
+
+    /// Returns the ATProto signing key's verification method, with the id `#atproto`.
+    pub fn signing_key(&self) -> Option<&VerificationMethod> {
+        self.verification_method
             .iter()
-            .find_map(|s| Handle::from_str(s).ok())
+            .flatten()
+            .find(|method| method.id.matches(&self.id, "atproto"))
     }
 
+    /// Returns the service with the id `#{fragment}`.
+    ///
+    /// Both relative ids and absolute ids referring to this document's DID are matched.
+    pub fn service(&self, fragment: &str) -> Option<&Service> {
+        self.service
+            .iter()
+            .find(|svc| svc.id.matches(&self.id, fragment))
+    }
+
+    /// Returns the endpoint of the account's PDS, with the id `#atproto_pds`.
     pub fn pds_service_url(&self) -> Option<&Url> {
-        self.service.iter().find_map(|svc| {
-            (svc.ty == "AtprotoPersonalDataServer" && svc.id.ends_with("#atproto_pds"))
-                .then_some(&svc.service_endpoint)
-        })
+        self.service("atproto_pds")
+            .filter(|svc| svc.ty == "AtprotoPersonalDataServer")
+            .map(|svc| &svc.service_endpoint)
+    }
+
+    /// Returns the endpoint of the account's labeler service, with the id `#atproto_labeler`.
+    pub fn labeler_service_url(&self) -> Option<&Url> {
+        self.service("atproto_labeler")
+            .filter(|svc| svc.ty == "AtprotoLabeler")
+            .map(|svc| &svc.service_endpoint)
+    }
+}
+
+/// The id of a verification method or service in a DID document.
+///
+/// Ids may be absolute DID URLs (`did:plc:...#atproto`) or relative to the document's DID
+/// (`#atproto`).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DidDocId {
+    Absolute(DidUrl),
+    Relative(String),
+}
+
+impl DidDocId {
+    /// Returns the fragment of this id, without the leading `#`.
+    pub fn fragment(&self) -> Option<&str> {
+        match self {
+            DidDocId::Absolute(url) => url.fragment(),
+            DidDocId::Relative(s) => s.strip_prefix('#'),
+        }
+    }
+
+    /// Returns `true` if this id refers to `did#fragment`.
+    pub fn matches(&self, did: &Did, fragment: &str) -> bool {
+        let did_matches = match self {
+            DidDocId::Absolute(url) => url.did() == did,
+            DidDocId::Relative(_) => true,
+        };
+
+        did_matches && self.fragment() == Some(fragment)
+    }
+}
+
+impl FromStr for DidDocId {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('#') {
+            validate_did_fragment(s.as_bytes())?;
+
+            Ok(DidDocId::Relative(s.into()))
+        } else {
+            DidUrl::from_str(s).map(DidDocId::Absolute)
+        }
+    }
+}
+
+impl fmt::Display for DidDocId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DidDocId::Absolute(url) => fmt::Display::fmt(url, f),
+            DidDocId::Relative(s) => f.write_str(s),
+        }
+    }
+}
+
+impl_deserialize_via_from_str!(DidDocId);
+
+impl Serialize for DidDocId {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        ser.collect_str(self)
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerificationMethod {
-    pub id: DidUrl,
+    pub id: DidDocId,
     pub controller: Did,
     #[serde(rename = "type")]
     pub ty: String,
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub public_key_multibase: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+impl VerificationMethod {
+    /// Parses the public key of this verification method.
+    ///
+    /// Both the current `Multikey` type and the legacy `EcdsaSecp256r1VerificationKey2019` and
+    /// `EcdsaSecp256k1VerificationKey2019` types are supported.
+    pub fn public_key(&self) -> Result<PublicKey, CryptoError> {
+        let multibase = self
+            .public_key_multibase
+            .as_deref()
+            .ok_or(CryptoError::InvalidEncoding)?;
+
+        let curve = match self.ty.as_str() {
+            "Multikey" => return PublicKey::from_multibase(multibase),
+            "EcdsaSecp256r1VerificationKey2019" => Curve::P256,
+            "EcdsaSecp256k1VerificationKey2019" => Curve::K256,
+            _ => return Err(CryptoError::UnsupportedKeyType),
+        };
+
+        // Legacy keys have no multicodec prefix, and may be uncompressed.
+        let (base, bytes) =
+            cid::multibase::decode(multibase).map_err(|_| CryptoError::InvalidEncoding)?;
+
+        if base != cid::multibase::Base::Base58Btc {
+            return Err(CryptoError::InvalidEncoding);
+        }
+
+        PublicKey::from_sec1(curve, &bytes)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Service {
-    pub id: String,
+    pub id: DidDocId,
     #[serde(rename = "type")]
     pub ty: String,
     pub service_endpoint: Url,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 #[cfg(test)]
@@ -384,6 +544,113 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn did_url_components() {
+        let url = DidUrl::from_str("did:plc:z72i7hdynmk6r22z27h6tvur/path?q=1#atproto").unwrap();
+
+        assert_eq!(url.did().as_str(), "did:plc:z72i7hdynmk6r22z27h6tvur");
+        assert_eq!(url.path(), Some("/path"));
+        assert_eq!(url.query(), Some("q=1"));
+        assert_eq!(url.fragment(), Some("atproto"));
+
+        let url = DidUrl::from_str("did:web:example.com").unwrap();
+
+        assert_eq!(url.path(), None);
+        assert_eq!(url.query(), None);
+        assert_eq!(url.fragment(), None);
+    }
+
+    fn example_doc() -> serde_json::Value {
+        serde_json::json!({
+            "@context": [
+                "https://www.w3.org/ns/did/v1",
+                "https://w3id.org/security/multikey/v1",
+            ],
+            "id": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+            "alsoKnownAs": ["at://atproto.com"],
+            "verificationMethod": [{
+                "id": "did:plc:ewvi7nxzyoun6zhxrhs64oiz#atproto",
+                "type": "Multikey",
+                "controller": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+                "publicKeyMultibase": "zQ3shunBKsXixLxKtC5qeSG9E4J5RkGN57im31pcTzbNQnm5w",
+            }],
+            "service": [
+                {
+                    "id": "#atproto_pds",
+                    "type": "AtprotoPersonalDataServer",
+                    "serviceEndpoint": "https://enoki.us-east.host.bsky.network/",
+                },
+                {
+                    "id": "did:plc:ewvi7nxzyoun6zhxrhs64oiz#atproto_labeler",
+                    "type": "AtprotoLabeler",
+                    "serviceEndpoint": "https://labeler.example.com/",
+                    "priority": 1,
+                },
+            ],
+            "controller": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+        })
+    }
+
+    #[test]
+    fn did_doc_roundtrip() {
+        // Endpoints have trailing slashes, since `Url` normalizes them.
+        let json = example_doc();
+        let doc: DidDoc = serde_json::from_value(json.clone()).unwrap();
+
+        assert!(doc.extra.contains_key("controller"));
+        assert!(doc.service[1].extra.contains_key("priority"));
+        assert_eq!(serde_json::to_value(&doc).unwrap(), json);
+    }
+
+    #[test]
+    fn did_doc_lookup() {
+        let doc: DidDoc = serde_json::from_value(example_doc()).unwrap();
+
+        assert_eq!(doc.handle().unwrap().as_str(), "atproto.com");
+        assert_eq!(
+            doc.pds_service_url().unwrap().as_str(),
+            "https://enoki.us-east.host.bsky.network/"
+        );
+        assert_eq!(
+            doc.labeler_service_url().unwrap().as_str(),
+            "https://labeler.example.com/"
+        );
+        assert!(doc.service("atproto_feed").is_none());
+
+        let key = doc.signing_key().unwrap().public_key().unwrap();
+        assert_eq!(key.curve(), Curve::K256);
+    }
+
+    #[test]
+    fn did_doc_id_matching() {
+        let did = Did::from_str("did:plc:ewvi7nxzyoun6zhxrhs64oiz").unwrap();
+        let other = Did::from_str("did:plc:z72i7hdynmk6r22z27h6tvur").unwrap();
+
+        let relative = DidDocId::from_str("#atproto").unwrap();
+        assert!(relative.matches(&did, "atproto"));
+        assert!(relative.matches(&other, "atproto"));
+        assert!(!relative.matches(&did, "atproto_pds"));
+
+        let absolute = DidDocId::from_str("did:plc:ewvi7nxzyoun6zhxrhs64oiz#atproto").unwrap();
+        assert!(absolute.matches(&did, "atproto"));
+        assert!(!absolute.matches(&other, "atproto"));
+
+        assert!(DidDocId::from_str("atproto").is_err());
+    }
+
+    #[test]
+    fn legacy_verification_method() {
+        let method: VerificationMethod = serde_json::from_value(serde_json::json!({
+            "id": "#atproto",
+            "type": "EcdsaSecp256k1VerificationKey2019",
+            "controller": "did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+            "publicKeyMultibase": "zQYEBzXeuTM9UR3rfvNag6L3RNAs5pQZyYPsomTsgQhsxLdEgCrPTLgFna8yqCnxPpNT7DBk6Ym3dgPKNu86vt9GR",
+        }))
+        .unwrap();
+
+        assert_eq!(method.public_key().unwrap().curve(), Curve::K256);
+    }
+
     proptest! {
         #[test]
         fn proptest_did_roundtrip(did: Did) {
@@ -0,0 +1,320 @@
+//! Support for XRPC event streams.
+//!
+//! Each WebSocket frame of an event stream consists of two concatenated DAG-CBOR objects: a
+//! [`Header`], followed by a body whose type is determined by the header. See the [Event Stream]
+//! section of the ATProto specification.
+//!
+//! [Event Stream]: https://atproto.com/specs/event-stream
+
+use std::{collections::TryReserveError, convert::Infallible, fmt};
+
+use bytes::Bytes;
+use ipld_core::ipld::Ipld;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::xrpc::{self, Subscription};
+
+/// The `op` value of a regular message frame.
+pub const OP_MESSAGE: i64 = 1;
+
+/// The `op` value of an error frame.
+pub const OP_ERROR: i64 = -1;
+
+/// The header of an event stream frame.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Header {
+    /// The frame type, either [`OP_MESSAGE`] or [`OP_ERROR`].
+    pub op: i64,
+    /// The Lexicon type of the message body (e.g. `#commit`).
+    ///
+    /// This is only present for message frames.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub t: Option<String>,
+}
+
+/// A decoded event stream frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame<M, E> {
+    /// A message frame, containing one variant of the subscription's message union.
+    Message(M),
+    /// An error frame. The server closes the stream after sending one of these.
+    Error(xrpc::Error<E>),
+}
+
+impl<M, E> Frame<M, E> {
+    /// Decodes a frame sent by the subscription `S`.
+    ///
+    /// The `t` field of a message frame is resolved relative to the NSID of `S` and used to select
+    /// the matching variant of `S::Message`.
+    pub fn decode<S>(bytes: &[u8]) -> Result<Self, FrameError>
+    where
+        S: Subscription<Message = M, RpcError = E>,
+        M: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        let mut des = serde_ipld_dagcbor::de::Deserializer::from_slice(bytes);
+
+        let header = Header::deserialize(&mut des).map_err(FrameError::Cbor)?;
+
+        match header.op {
+            OP_MESSAGE => {
+                let t = header.t.ok_or(FrameError::MissingType)?;
+
+                let body = Ipld::deserialize(&mut des).map_err(FrameError::Cbor)?;
+                des.end().map_err(FrameError::Cbor)?;
+
+                let Ipld::Map(mut map) = body else {
+                    return Err(FrameError::InvalidBody);
+                };
+
+                // Messages are tagged by the header rather than by a `$type` field, so add the
+                // field back and let the union's `Deserialize` impl do the dispatch.
+                let ty = match t.strip_prefix('#') {
+                    Some(name) => format!("{}#{name}", S::nsid()),
+                    None => t,
+                };
+                map.insert("$type".into(), Ipld::String(ty));
+
+                ipld_core::serde::from_ipld(Ipld::Map(map))
+                    .map(Frame::Message)
+                    .map_err(FrameError::Ipld)
+            }
+
+            OP_ERROR => {
+                let error = xrpc::Error::deserialize(&mut des).map_err(FrameError::Cbor)?;
+                des.end().map_err(FrameError::Cbor)?;
+
+                Ok(Frame::Error(error))
+            }
+
+            other => Err(FrameError::UnknownOp(other)),
+        }
+    }
+
+    /// Encodes a frame for the subscription `S`.
+    ///
+    /// This is the inverse of [`Frame::decode`].
+    pub fn encode<S>(&self) -> Result<Bytes, FrameError>
+    where
+        S: Subscription<Message = M, RpcError = E>,
+        M: Serialize,
+        E: Serialize,
+    {
+        let (header, body) = match self {
+            Frame::Message(msg) => {
+                let body = ipld_core::serde::to_ipld(msg).map_err(FrameError::Ipld)?;
+
+                let Ipld::Map(mut map) = body else {
+                    return Err(FrameError::InvalidBody);
+                };
+
+                let Some(Ipld::String(ty)) = map.remove("$type") else {
+                    return Err(FrameError::MissingType);
+                };
+
+                // Shorten references to the subscription's own definitions.
+                let t = match ty.strip_prefix(S::nsid()) {
+                    Some(frag) if frag.starts_with('#') => frag.to_owned(),
+                    _ => ty,
+                };
+
+                let header = Header {
+                    op: OP_MESSAGE,
+                    t: Some(t),
+                };
+
+                (header, Ipld::Map(map))
+            }
+
+            Frame::Error(error) => {
+                let header = Header {
+                    op: OP_ERROR,
+                    t: None,
+                };
+
+                let body = ipld_core::serde::to_ipld(error).map_err(FrameError::Ipld)?;
+
+                (header, body)
+            }
+        };
+
+        let mut buf = serde_ipld_dagcbor::to_vec(&header).map_err(FrameError::Encode)?;
+        buf.extend(serde_ipld_dagcbor::to_vec(&body).map_err(FrameError::Encode)?);
+
+        Ok(buf.into())
+    }
+}
+
+/// An error produced while decoding or encoding an event stream frame.
+#[derive(Debug)]
+pub enum FrameError {
+    /// The frame was not valid DAG-CBOR.
+    Cbor(serde_ipld_dagcbor::DecodeError<Infallible>),
+    /// The frame could not be encoded as DAG-CBOR.
+    Encode(serde_ipld_dagcbor::EncodeError<TryReserveError>),
+    /// The frame body did not match the expected type.
+    Ipld(ipld_core::serde::SerdeError),
+    /// The frame body was not a map.
+    InvalidBody,
+    /// A message frame did not specify the type of its body.
+    MissingType,
+    /// The frame header contained an unrecognized `op` value.
+    UnknownOp(i64),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::Cbor(e) => fmt::Display::fmt(e, f),
+            FrameError::Encode(e) => fmt::Display::fmt(e, f),
+            FrameError::Ipld(e) => fmt::Display::fmt(e, f),
+            FrameError::InvalidBody => f.write_str("frame body is not a map"),
+            FrameError::MissingType => f.write_str("message frame is missing a type"),
+            FrameError::UnknownOp(op) => write!(f, "unknown frame op: {op}"),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FrameError::Cbor(e) => Some(e),
+            FrameError::Encode(e) => Some(e),
+            FrameError::Ipld(e) => Some(e),
+            FrameError::InvalidBody | FrameError::MissingType | FrameError::UnknownOp(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Subscribe;
+
+    impl Subscription for Subscribe {
+        type Params = ();
+        type Message = Message;
+        type RpcError = String;
+
+        fn nsid() -> &'static str {
+            "com.example.subscribe"
+        }
+
+        fn serialize_params(_: &()) -> Result<String, serde_urlencoded_xrpc::ser::Error> {
+            Ok(String::new())
+        }
+
+        fn deserialize_params(_: &str) -> Result<(), serde_urlencoded_xrpc::de::Error> {
+            Ok(())
+        }
+    }
+
+    // Internally tagged enums buffer their contents, which doesn't support the integer types
+    // produced by `Ipld`, so the test messages only contain strings.
+    #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+    #[serde(tag = "$type")]
+    enum Message {
+        #[serde(rename = "com.example.subscribe#ping")]
+        Ping { id: String },
+        #[serde(rename = "com.example.other#pong")]
+        Pong { id: String },
+    }
+
+    type TestFrame = Frame<Message, String>;
+
+    fn raw_frame(header: &Header, body: &Ipld) -> Vec<u8> {
+        let mut buf = serde_ipld_dagcbor::to_vec(header).unwrap();
+        buf.extend(serde_ipld_dagcbor::to_vec(body).unwrap());
+        buf
+    }
+
+    #[test]
+    fn decode_message() {
+        let header = Header {
+            op: OP_MESSAGE,
+            t: Some("#ping".into()),
+        };
+        let body = Ipld::Map([("id".into(), Ipld::String("7".into()))].into());
+
+        let frame = TestFrame::decode::<Subscribe>(&raw_frame(&header, &body)).unwrap();
+        assert_eq!(frame, Frame::Message(Message::Ping { id: "7".into() }));
+    }
+
+    #[test]
+    fn decode_error() {
+        let header = Header {
+            op: OP_ERROR,
+            t: None,
+        };
+        let body = Ipld::Map(
+            [
+                ("error".into(), Ipld::String("FutureCursor".into())),
+                ("message".into(), Ipld::String("too far".into())),
+            ]
+            .into(),
+        );
+
+        let frame = TestFrame::decode::<Subscribe>(&raw_frame(&header, &body)).unwrap();
+        assert_eq!(
+            frame,
+            Frame::Error(xrpc::Error {
+                error: xrpc::ErrorCode::Method("FutureCursor".into()),
+                message: Some("too far".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn decode_unknown_op() {
+        let header = Header { op: 2, t: None };
+        let body = Ipld::Map(Default::default());
+
+        let res = TestFrame::decode::<Subscribe>(&raw_frame(&header, &body));
+        assert!(matches!(res, Err(FrameError::UnknownOp(2))));
+    }
+
+    #[test]
+    fn decode_trailing_data() {
+        let header = Header {
+            op: OP_MESSAGE,
+            t: Some("#ping".into()),
+        };
+        let body = Ipld::Map([("id".into(), Ipld::String("7".into()))].into());
+
+        let mut bytes = raw_frame(&header, &body);
+        bytes.push(0);
+
+        assert!(TestFrame::decode::<Subscribe>(&bytes).is_err());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let frames = [
+            Frame::Message(Message::Ping { id: "1".into() }),
+            Frame::Message(Message::Pong { id: "2".into() }),
+            Frame::Error(xrpc::Error {
+                error: xrpc::ErrorCode::Method("ConsumerTooSlow".into()),
+                message: None,
+            }),
+        ];
+
+        for frame in frames {
+            let bytes = frame.encode::<Subscribe>().unwrap();
+            let decoded = TestFrame::decode::<Subscribe>(&bytes).unwrap();
+            assert_eq!(frame, decoded);
+        }
+    }
+
+    #[test]
+    fn encode_short_type() {
+        let bytes = TestFrame::Message(Message::Ping { id: "1".into() })
+            .encode::<Subscribe>()
+            .unwrap();
+
+        let mut des = serde_ipld_dagcbor::de::Deserializer::from_slice(&bytes);
+        let header = Header::deserialize(&mut des).unwrap();
+
+        assert_eq!(header.t.as_deref(), Some("#ping"));
+    }
+}
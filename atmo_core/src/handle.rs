@@ -9,7 +9,7 @@ use crate::{
 const LEN_RANGE: RangeInclusive<usize> = 1..=253;
 
 /// A human-friendly, less-permanent unique account identifier.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Handle(String);
 
 impl Handle {
@@ -30,14 +30,21 @@ mod at_uri;
 mod blob;
 #[doc(hidden)]
 pub mod bytes;
-mod cid;
+pub mod car;
+pub mod cid;
+pub mod commit;
+pub mod crypto;
 mod datetime;
 pub mod did;
 pub mod error;
+pub mod event_stream;
 mod handle;
+pub mod mst;
 pub mod nsid;
 mod nullable;
 mod parse;
+pub mod plc;
+pub mod record;
 mod rkey;
 mod tid;
 #[doc(hidden)]
@@ -0,0 +1,984 @@
+//! Merkle Search Trees.
+//!
+//! ATProto repositories store their records in a [Merkle Search Tree] (MST), keyed by
+//! `collection/rkey` paths. Each node of the tree is a DAG-CBOR block containing a sorted list of
+//! entries, with keys compressed against the preceding key in the node, and links to the subtrees
+//! between them. The depth of a key in the tree is derived from its SHA-256 hash, so a given set of
+//! keys always produces the same tree.
+//!
+//! [Merkle Search Tree]: https://atproto.com/specs/repository#mst-structure
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    convert::Infallible,
+    fmt,
+    hash::BuildHasher,
+};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{cid::Codec, CidLink};
+
+/// A source of blocks, addressed by CID.
+pub trait BlockStore {
+    /// Returns the block addressed by `cid`, if it is present.
+    fn get(&self, cid: &CidLink) -> Option<Bytes>;
+}
+
+impl<S> BlockStore for HashMap<CidLink, Bytes, S>
+where
+    S: BuildHasher,
+{
+    #[inline]
+    fn get(&self, cid: &CidLink) -> Option<Bytes> {
+        HashMap::get(self, cid).cloned()
+    }
+}
+
+impl BlockStore for BTreeMap<CidLink, Bytes> {
+    #[inline]
+    fn get(&self, cid: &CidLink) -> Option<Bytes> {
+        BTreeMap::get(self, cid).cloned()
+    }
+}
+
+impl<T> BlockStore for &T
+where
+    T: BlockStore + ?Sized,
+{
+    #[inline]
+    fn get(&self, cid: &CidLink) -> Option<Bytes> {
+        T::get(self, cid)
+    }
+}
+
+/// Returns the depth of `key` in an MST.
+///
+/// This is the number of leading zero bits in the SHA-256 hash of the key, divided by two (rounding
+/// down), giving the tree a fanout of 4.
+pub fn key_depth(key: &str) -> u32 {
+    let hash = Sha256::digest(key.as_bytes());
+
+    let mut zeros = 0;
+    for byte in hash {
+        zeros += byte.leading_zeros();
+
+        if byte != 0 {
+            break;
+        }
+    }
+
+    zeros / 2
+}
+
+/// The DAG-CBOR representation of an MST node.
+///
+/// Fields are declared in DAG-CBOR canonical order.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct NodeData {
+    /// The entries of the node, in key order.
+    pub e: Vec<TreeEntry>,
+    /// A link to the subtree containing keys less than the first entry.
+    pub l: Option<CidLink>,
+}
+
+/// The DAG-CBOR representation of an entry in an MST node.
+///
+/// Fields are declared in DAG-CBOR canonical order.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TreeEntry {
+    /// The key suffix, after removing the first `p` bytes.
+    pub k: Bytes,
+    /// The number of bytes shared with the previous key in the node.
+    pub p: u64,
+    /// A link to the subtree containing keys between this entry and the next.
+    pub t: Option<CidLink>,
+    /// The value of the entry.
+    pub v: CidLink,
+}
+
+/// A decoded MST node.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Node {
+    /// A link to the subtree containing keys less than the first entry.
+    pub left: Option<CidLink>,
+    /// The entries of the node, in key order.
+    pub entries: Vec<Entry>,
+}
+
+/// An entry in an MST node, with its key decompressed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    /// The full key of the entry.
+    pub key: String,
+    /// The value of the entry, usually a link to a record.
+    pub value: CidLink,
+    /// A link to the subtree containing keys between this entry and the next.
+    pub right: Option<CidLink>,
+}
+
+impl Node {
+    /// Decodes a node from a DAG-CBOR block.
+    pub fn decode(bytes: &[u8]) -> Result<Node, MstError> {
+        let data: NodeData = serde_ipld_dagcbor::from_slice(bytes).map_err(MstError::Decode)?;
+
+        Node::try_from(data)
+    }
+
+    /// Encodes this node as a DAG-CBOR block.
+    pub fn encode(&self) -> Vec<u8> {
+        serde_ipld_dagcbor::to_vec(&NodeData::from(self))
+            .expect("MST node serialization should never fail")
+    }
+
+    /// Encodes this node, returning the resulting block and its CID.
+    pub fn to_block(&self) -> (CidLink, Bytes) {
+        let bytes = self.encode();
+
+        (CidLink::compute(Codec::DagCbor, &bytes), bytes.into())
+    }
+}
+
+impl TryFrom<NodeData> for Node {
+    type Error = MstError;
+
+    fn try_from(data: NodeData) -> Result<Self, Self::Error> {
+        let mut entries: Vec<Entry> = Vec::with_capacity(data.e.len());
+        let mut key = Vec::new();
+
+        for entry in data.e {
+            let prefix = usize::try_from(entry.p)
+                .ok()
+                .filter(|&p| p <= key.len())
+                .ok_or(MstError::InvalidNode(
+                    "key prefix is longer than previous key",
+                ))?;
+
+            key.truncate(prefix);
+            key.extend_from_slice(&entry.k);
+
+            let full = String::from_utf8(key.clone())
+                .map_err(|_| MstError::InvalidNode("key is not valid UTF-8"))?;
+
+            if entries.last().is_some_and(|prev| prev.key >= full) {
+                return Err(MstError::InvalidNode("keys are not in ascending order"));
+            }
+
+            entries.push(Entry {
+                key: full,
+                value: entry.v,
+                right: entry.t,
+            });
+        }
+
+        Ok(Node {
+            left: data.l,
+            entries,
+        })
+    }
+}
+
+impl From<&Node> for NodeData {
+    fn from(node: &Node) -> Self {
+        let mut prev: &[u8] = &[];
+
+        let e = node
+            .entries
+            .iter()
+            .map(|entry| {
+                let key = entry.key.as_bytes();
+                let prefix = prev.iter().zip(key).take_while(|(a, b)| a == b).count();
+                prev = key;
+
+                TreeEntry {
+                    k: Bytes::copy_from_slice(&key[prefix..]),
+                    p: prefix as u64,
+                    t: entry.right.clone(),
+                    v: entry.value.clone(),
+                }
+            })
+            .collect();
+
+        NodeData {
+            e,
+            l: node.left.clone(),
+        }
+    }
+}
+
+/// A view of an MST stored in a [`BlockStore`].
+pub struct Mst<S> {
+    store: S,
+    root: CidLink,
+}
+
+impl<S> Mst<S>
+where
+    S: BlockStore,
+{
+    /// Creates a view of the MST with the given root node.
+    ///
+    /// Nodes are loaded from `store` lazily, as they are needed.
+    #[inline]
+    pub fn load(store: S, root: CidLink) -> Self {
+        Mst { store, root }
+    }
+
+    /// Returns the CID of the root node.
+    #[inline]
+    pub fn root(&self) -> &CidLink {
+        &self.root
+    }
+
+    /// Returns a reference to the underlying block store.
+    #[inline]
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Loads and decodes the node with the given CID.
+    pub fn node(&self, cid: &CidLink) -> Result<Node, MstError> {
+        let bytes = self
+            .store
+            .get(cid)
+            .ok_or_else(|| MstError::MissingBlock(cid.clone()))?;
+
+        Node::decode(&bytes)
+    }
+
+    /// Looks up the value of `key`.
+    pub fn get(&self, key: &str) -> Result<Option<CidLink>, MstError> {
+        let mut cid = self.root.clone();
+
+        loop {
+            let node = self.node(&cid)?;
+
+            // Index of the first entry with a key not less than `key`.
+            let idx = node.entries.partition_point(|e| e.key.as_str() < key);
+
+            if let Some(entry) = node.entries.get(idx) {
+                if entry.key == key {
+                    return Ok(Some(entry.value.clone()));
+                }
+            }
+
+            let subtree = match idx {
+                0 => node.left,
+                _ => node.entries[idx - 1].right.clone(),
+            };
+
+            match subtree {
+                Some(next) => cid = next,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Returns an iterator over the entries of the tree, in key order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, S> {
+        Iter {
+            mst: self,
+            stack: Vec::new(),
+            pending: Some(self.root.clone()),
+        }
+    }
+
+    /// Applies `mutations` to the tree, in order, returning the new root and the changed blocks.
+    ///
+    /// The tree is not modified; to continue working with the result, add the new blocks to the
+    /// store and [load](Mst::load) the new root. The resulting tree depends only on the final set of
+    /// keys, not on the order in which they were inserted.
+    ///
+    /// This rebuilds the whole tree, so its cost is proportional to the size of the tree rather
+    /// than the number of mutations. Batch mutations together where possible.
+    pub fn apply<I>(&self, mutations: I) -> Result<MstDiff, MstError>
+    where
+        I: IntoIterator<Item = Mutation>,
+    {
+        let mut entries = self.iter().collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        for mutation in mutations {
+            match mutation {
+                Mutation::Insert { key, value } => {
+                    validate_key(&key)?;
+
+                    if entries.contains_key(&key) {
+                        return Err(MstError::KeyExists(key));
+                    }
+
+                    entries.insert(key, value);
+                }
+
+                Mutation::Update { key, value } => match entries.get_mut(&key) {
+                    Some(v) => *v = value,
+                    None => return Err(MstError::KeyNotFound(key)),
+                },
+
+                Mutation::Delete { key } => {
+                    if entries.remove(&key).is_none() {
+                        return Err(MstError::KeyNotFound(key));
+                    }
+                }
+            }
+        }
+
+        let old_nodes = self.node_cids()?;
+        let (root, mut new_blocks) = build(&entries)?;
+
+        let removed_blocks = old_nodes
+            .into_iter()
+            .filter(|cid| new_blocks.remove(cid).is_none())
+            .collect();
+
+        Ok(MstDiff {
+            root,
+            new_blocks,
+            removed_blocks,
+        })
+    }
+
+    // Returns the CIDs of every node in the tree.
+    fn node_cids(&self) -> Result<BTreeSet<CidLink>, MstError> {
+        let mut cids = BTreeSet::new();
+        let mut stack = vec![self.root.clone()];
+
+        while let Some(cid) = stack.pop() {
+            let node = self.node(&cid)?;
+
+            stack.extend(node.left);
+            stack.extend(node.entries.into_iter().filter_map(|e| e.right));
+
+            cids.insert(cid);
+        }
+
+        Ok(cids)
+    }
+}
+
+/// A change to a single key of an MST.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mutation {
+    /// Adds a key which is not already present.
+    Insert { key: String, value: CidLink },
+    /// Changes the value of an existing key.
+    Update { key: String, value: CidLink },
+    /// Removes an existing key.
+    Delete { key: String },
+}
+
+/// The result of applying [`Mutation`]s to an MST.
+///
+/// This is returned by [`Mst::apply`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MstDiff {
+    /// The CID of the new root node.
+    pub root: CidLink,
+    /// Nodes which are part of the new tree, but not the old one.
+    pub new_blocks: BTreeMap<CidLink, Bytes>,
+    /// Nodes which are part of the old tree, but not the new one.
+    pub removed_blocks: BTreeSet<CidLink>,
+}
+
+/// Builds an MST containing `entries`, returning the CID of the root node and every node block.
+pub fn build(
+    entries: &BTreeMap<String, CidLink>,
+) -> Result<(CidLink, BTreeMap<CidLink, Bytes>), MstError> {
+    let entries = entries
+        .iter()
+        .map(|(key, value)| {
+            validate_key(key)?;
+            Ok((key.as_str(), value, key_depth(key)))
+        })
+        .collect::<Result<Vec<_>, MstError>>()?;
+
+    // The root is at the layer of the deepest key.
+    let layer = entries
+        .iter()
+        .map(|(_, _, depth)| *depth)
+        .max()
+        .unwrap_or(0);
+
+    let mut blocks = BTreeMap::new();
+    let root = build_node(&entries, layer, &mut blocks);
+
+    Ok((root, blocks))
+}
+
+// Builds the node at `layer` containing `entries`, all of which have a depth of at most `layer`.
+fn build_node(
+    entries: &[(&str, &CidLink, u32)],
+    layer: u32,
+    blocks: &mut BTreeMap<CidLink, Bytes>,
+) -> CidLink {
+    let mut node = Node::default();
+
+    // Entries deeper than this layer belong to subtrees in the gaps between this layer's entries.
+    // Gaps are built at the next layer down even if it has no entries of its own, so every path
+    // from the root passes through every layer.
+    let mut gaps = entries.split(|(_, _, depth)| *depth == layer);
+    let here = entries.iter().filter(|(_, _, depth)| *depth == layer);
+
+    node.left = build_subtree(gaps.next().unwrap_or_default(), layer, blocks);
+
+    for ((key, value, _), gap) in here.zip(gaps) {
+        node.entries.push(Entry {
+            key: (*key).to_owned(),
+            value: (*value).clone(),
+            right: build_subtree(gap, layer, blocks),
+        });
+    }
+
+    let (cid, bytes) = node.to_block();
+    blocks.insert(cid.clone(), bytes);
+
+    cid
+}
+
+fn build_subtree(
+    entries: &[(&str, &CidLink, u32)],
+    layer: u32,
+    blocks: &mut BTreeMap<CidLink, Bytes>,
+) -> Option<CidLink> {
+    if entries.is_empty() {
+        return None;
+    }
+
+    Some(build_node(entries, layer - 1, blocks))
+}
+
+/// Checks that `key` is a valid MST key.
+///
+/// Keys have the form `collection/rkey`, are at most 1024 bytes long, and contain only ASCII
+/// alphanumerics and the characters `.-_:~`.
+fn validate_key(key: &str) -> Result<(), MstError> {
+    let valid = key.len() <= 1024
+        && key.split_once('/').is_some_and(|(collection, rkey)| {
+            !collection.is_empty() && !rkey.is_empty() && !rkey.contains('/')
+        })
+        && key
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"/.-_:~".contains(&b));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(MstError::InvalidKey(key.to_owned()))
+    }
+}
+
+/// An iterator over the entries of an [`Mst`], in key order.
+///
+/// This is returned by [`Mst::iter`].
+pub struct Iter<'a, S> {
+    mst: &'a Mst<S>,
+    stack: Vec<std::vec::IntoIter<Entry>>,
+    // A subtree which must be visited before the entries on the stack.
+    pending: Option<CidLink>,
+}
+
+impl<S> Iter<'_, S>
+where
+    S: BlockStore,
+{
+    // Pushes the nodes along the leftmost path of the pending subtree onto the stack.
+    fn descend(&mut self) -> Result<(), MstError> {
+        while let Some(cid) = self.pending.take() {
+            let node = self.mst.node(&cid)?;
+            self.stack.push(node.entries.into_iter());
+            self.pending = node.left;
+        }
+
+        Ok(())
+    }
+}
+
+impl<S> Iterator for Iter<'_, S>
+where
+    S: BlockStore,
+{
+    type Item = Result<(String, CidLink), MstError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.descend() {
+            self.stack.clear();
+            return Some(Err(e));
+        }
+
+        loop {
+            let entries = self.stack.last_mut()?;
+
+            match entries.next() {
+                Some(entry) => {
+                    self.pending = entry.right;
+                    return Some(Ok((entry.key, entry.value)));
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+    }
+}
+
+/// An error produced while reading or modifying an MST.
+#[derive(Debug)]
+pub enum MstError {
+    /// A node could not be decoded.
+    Decode(serde_ipld_dagcbor::DecodeError<Infallible>),
+    /// A key was not of the form `collection/rkey`.
+    InvalidKey(String),
+    /// A node was well-formed DAG-CBOR, but not a valid MST node.
+    InvalidNode(&'static str),
+    /// An inserted key was already present.
+    KeyExists(String),
+    /// An updated or deleted key was not present.
+    KeyNotFound(String),
+    /// A node was not present in the block store.
+    MissingBlock(CidLink),
+}
+
+impl fmt::Display for MstError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MstError::Decode(e) => write!(f, "invalid MST node: {e}"),
+            MstError::InvalidKey(key) => write!(f, "invalid MST key: {key:?}"),
+            MstError::InvalidNode(msg) => write!(f, "invalid MST node: {msg}"),
+            MstError::KeyExists(key) => write!(f, "MST key already exists: {key:?}"),
+            MstError::KeyNotFound(key) => write!(f, "MST key not found: {key:?}"),
+            MstError::MissingBlock(cid) => write!(f, "missing MST node: {cid}"),
+        }
+    }
+}
+
+impl std::error::Error for MstError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MstError::Decode(e) => Some(e),
+            MstError::InvalidKey(_)
+            | MstError::InvalidNode(_)
+            | MstError::KeyExists(_)
+            | MstError::KeyNotFound(_)
+            | MstError::MissingBlock(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn leaf() -> CidLink {
+        CidLink::compute(Codec::Raw, b"record")
+    }
+
+    fn entry(key: &str, right: Option<CidLink>) -> Entry {
+        Entry {
+            key: key.into(),
+            value: leaf(),
+            right,
+        }
+    }
+
+    fn insert(store: &mut HashMap<CidLink, Bytes>, node: Node) -> CidLink {
+        let (cid, bytes) = node.to_block();
+        store.insert(cid.clone(), bytes);
+        cid
+    }
+
+    // A three-level tree, built by hand:
+    //
+    //            [        b        d ]
+    //            /         \
+    //   [  a  ]             [  c  ]
+    //  /
+    // [ 0 ]
+    fn example(store: &mut HashMap<CidLink, Bytes>) -> CidLink {
+        let bottom = insert(
+            store,
+            Node {
+                left: None,
+                entries: vec![entry("com.example/0", None)],
+            },
+        );
+
+        let left = insert(
+            store,
+            Node {
+                left: Some(bottom),
+                entries: vec![entry("com.example/a", None)],
+            },
+        );
+
+        let right = insert(
+            store,
+            Node {
+                left: None,
+                entries: vec![entry("com.example/c", None)],
+            },
+        );
+
+        insert(
+            store,
+            Node {
+                left: Some(left),
+                entries: vec![
+                    entry("com.example/b", Some(right)),
+                    entry("com.example/d", None),
+                ],
+            },
+        )
+    }
+
+    #[test]
+    fn key_depths() {
+        // From the ATProto interop test vectors.
+        let cases = [
+            ("", 0),
+            ("asdf", 0),
+            ("blue", 1),
+            ("2653ae71", 0),
+            ("88bfafc7", 2),
+            ("2a92d355", 4),
+            ("884976f5", 6),
+            ("app.bsky.feed.post/454397e440ec", 4),
+            ("app.bsky.feed.post/9adeb165882c", 8),
+        ];
+
+        for (key, depth) in cases {
+            assert_eq!(key_depth(key), depth, "key: {key:?}");
+        }
+    }
+
+    #[test]
+    fn empty_node_cid() {
+        let (cid, _) = Node::default().to_block();
+
+        assert_eq!(
+            cid.to_string(),
+            "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm"
+        );
+    }
+
+    #[test]
+    fn prefix_compression() {
+        let node = Node {
+            left: None,
+            entries: vec![
+                entry("com.example.record/3jqfcqzm3fo2j", None),
+                entry("com.example.record/3jqfcqzm3fp2j", None),
+                entry("com.example.thing/abc", None),
+            ],
+        };
+
+        let data = NodeData::from(&node);
+        let compressed: Vec<_> = data.e.iter().map(|e| (e.p, &e.k[..])).collect();
+
+        assert_eq!(
+            compressed,
+            [
+                (0, &b"com.example.record/3jqfcqzm3fo2j"[..]),
+                (29, b"p2j"),
+                (12, b"thing/abc"),
+            ]
+        );
+
+        assert_eq!(Node::decode(&node.encode()).unwrap(), node);
+    }
+
+    #[test]
+    fn invalid_nodes() {
+        let bad_prefix = NodeData {
+            e: vec![TreeEntry {
+                k: Bytes::from_static(b"a"),
+                p: 1,
+                t: None,
+                v: leaf(),
+            }],
+            l: None,
+        };
+
+        let unordered = NodeData {
+            e: vec![
+                TreeEntry {
+                    k: Bytes::from_static(b"b"),
+                    p: 0,
+                    t: None,
+                    v: leaf(),
+                },
+                TreeEntry {
+                    k: Bytes::from_static(b"a"),
+                    p: 0,
+                    t: None,
+                    v: leaf(),
+                },
+            ],
+            l: None,
+        };
+
+        for data in [bad_prefix, unordered] {
+            let bytes = serde_ipld_dagcbor::to_vec(&data).unwrap();
+            assert!(matches!(
+                Node::decode(&bytes),
+                Err(MstError::InvalidNode(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn iterate() {
+        let mut store = HashMap::new();
+        let root = example(&mut store);
+
+        let mst = Mst::load(&store, root);
+        let keys = mst
+            .iter()
+            .map(|res| res.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            keys,
+            [
+                "com.example/0",
+                "com.example/a",
+                "com.example/b",
+                "com.example/c",
+                "com.example/d",
+            ]
+        );
+    }
+
+    #[test]
+    fn lookup() {
+        let mut store = HashMap::new();
+        let root = example(&mut store);
+
+        let mst = Mst::load(&store, root);
+
+        for key in ["0", "a", "b", "c", "d"] {
+            let key = format!("com.example/{key}");
+            assert_eq!(mst.get(&key).unwrap(), Some(leaf()), "key: {key}");
+        }
+
+        for key in ["", "1", "bb", "e"] {
+            let key = format!("com.example/{key}");
+            assert_eq!(mst.get(&key).unwrap(), None, "key: {key}");
+        }
+    }
+
+    #[test]
+    fn missing_block() {
+        let mut store = HashMap::new();
+        let root = example(&mut store);
+        let root_cid = root.clone();
+
+        // Remove everything but the root node.
+        store.retain(|cid, _| *cid == root_cid);
+
+        let mst = Mst::load(&store, root);
+
+        let mut iter = mst.iter();
+        assert!(matches!(iter.next(), Some(Err(MstError::MissingBlock(_)))));
+        assert!(iter.next().is_none());
+
+        assert!(mst.get("com.example/d").unwrap().is_some());
+        assert!(matches!(
+            mst.get("com.example/a"),
+            Err(MstError::MissingBlock(_))
+        ));
+    }
+
+    fn cid(s: &str) -> CidLink {
+        CidLink::from(cid::Cid::from_str(s).unwrap())
+    }
+
+    fn record_key(rkey: &str) -> String {
+        format!("com.example.record/{rkey}")
+    }
+
+    // Applies `mutations` to the tree in `store`, updating the store and returning the new root.
+    fn apply(
+        store: &mut HashMap<CidLink, Bytes>,
+        root: CidLink,
+        mutations: Vec<Mutation>,
+    ) -> CidLink {
+        let diff = Mst::load(&*store, root).apply(mutations).unwrap();
+
+        for cid in &diff.removed_blocks {
+            assert!(store.remove(cid).is_some());
+        }
+
+        for (cid, bytes) in diff.new_blocks {
+            assert!(store.insert(cid, bytes).is_none());
+        }
+
+        diff.root
+    }
+
+    fn empty_tree(store: &mut HashMap<CidLink, Bytes>) -> CidLink {
+        insert(store, Node::default())
+    }
+
+    #[test]
+    fn root_cids() {
+        // From the reference implementation's test suite.
+        let value = cid("bafyreie5cvv4h45feadgeuwhbcutmh6t2ceseocckahdoe6uat64zmz454");
+
+        let cases: [(&[&str], &str); 4] = [
+            (
+                &[],
+                "bafyreie5737gdxlw5i64vzichcalba3z2v5n6icifvx5xytvske7mr3hpm",
+            ),
+            (
+                &["3jqfcqzm3fo2j"],
+                "bafyreibj4lsc3aqnrvphp5xmrnfoorvru4wynt6lwidqbm2623a6tatzdu",
+            ),
+            (
+                &["3jqfcqzm3fx2j"],
+                "bafyreih7wfei65pxzhauoibu3ls7jgmkju4bspy4t2ha2qdjnzqvoy33ai",
+            ),
+            (
+                &[
+                    "3jqfcqzm3fp2j",
+                    "3jqfcqzm3fr2j",
+                    "3jqfcqzm3fs2j",
+                    "3jqfcqzm3ft2j",
+                    "3jqfcqzm4fc2j",
+                ],
+                "bafyreicmahysq4n6wfuxo522m6dpiy7z7qzym3dzs756t5n7nfdgccwq7m",
+            ),
+        ];
+
+        for (rkeys, expected) in cases {
+            let entries = rkeys
+                .iter()
+                .map(|rkey| (record_key(rkey), value.clone()))
+                .collect();
+
+            let (root, _) = build(&entries).unwrap();
+            assert_eq!(root.to_string(), expected, "keys: {rkeys:?}");
+
+            // Inserting the keys one at a time gives the same tree.
+            let mut store = HashMap::new();
+            let mut root = empty_tree(&mut store);
+
+            for rkey in rkeys {
+                let mutation = Mutation::Insert {
+                    key: record_key(rkey),
+                    value: value.clone(),
+                };
+                root = apply(&mut store, root, vec![mutation]);
+            }
+
+            assert_eq!(root.to_string(), expected, "keys: {rkeys:?}");
+        }
+    }
+
+    #[test]
+    fn insertion_order() {
+        let keys: Vec<_> = (0..200).map(|i| record_key(&format!("{i:04}"))).collect();
+        let expected = build(&keys.iter().map(|k| (k.clone(), leaf())).collect())
+            .unwrap()
+            .0;
+
+        // Insert in a scrambled order, in batches of varying size.
+        let mut scrambled = keys.clone();
+        for i in 0..scrambled.len() {
+            scrambled.swap(i, (i * 7919) % keys.len());
+        }
+
+        let mut store = HashMap::new();
+        let mut root = empty_tree(&mut store);
+
+        for (n, batch) in scrambled.chunks(37).enumerate() {
+            let mutations = batch
+                .iter()
+                .map(|key| Mutation::Insert {
+                    key: key.clone(),
+                    value: leaf(),
+                })
+                .collect();
+
+            root = apply(&mut store, root, mutations);
+
+            // The tree should be readable after every batch.
+            let count = Mst::load(&store, root.clone()).iter().count();
+            assert_eq!(count, (37 * (n + 1)).min(keys.len()));
+        }
+
+        assert_eq!(root, expected);
+
+        // Every block in the store should be part of the tree.
+        assert_eq!(
+            Mst::load(&store, root.clone()).node_cids().unwrap().len(),
+            store.len()
+        );
+
+        // Deleting everything gives the empty tree, and leaves only its root in the store.
+        let mutations = keys
+            .into_iter()
+            .map(|key| Mutation::Delete { key })
+            .collect();
+        let root = apply(&mut store, root, mutations);
+
+        assert_eq!(root, Node::default().to_block().0);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn update() {
+        let mut store = HashMap::new();
+        let root = example(&mut store);
+
+        let value = CidLink::compute(Codec::Raw, b"updated");
+        let root = apply(
+            &mut store,
+            root,
+            vec![Mutation::Update {
+                key: "com.example/c".into(),
+                value: value.clone(),
+            }],
+        );
+
+        let mst = Mst::load(&store, root);
+        assert_eq!(mst.get("com.example/c").unwrap(), Some(value));
+        assert_eq!(mst.get("com.example/d").unwrap(), Some(leaf()));
+    }
+
+    #[test]
+    fn mutation_errors() {
+        let mut store = HashMap::new();
+        let root = example(&mut store);
+        let mst = Mst::load(&store, root);
+
+        let res = mst.apply([Mutation::Insert {
+            key: "com.example/a".into(),
+            value: leaf(),
+        }]);
+        assert!(matches!(res, Err(MstError::KeyExists(_))));
+
+        let res = mst.apply([Mutation::Update {
+            key: "com.example/e".into(),
+            value: leaf(),
+        }]);
+        assert!(matches!(res, Err(MstError::KeyNotFound(_))));
+
+        let res = mst.apply([Mutation::Delete {
+            key: "com.example/e".into(),
+        }]);
+        assert!(matches!(res, Err(MstError::KeyNotFound(_))));
+
+        for key in ["", "com.example", "/a", "com.example/", "a/b/c", "a/b c"] {
+            let res = mst.apply([Mutation::Insert {
+                key: key.into(),
+                value: leaf(),
+            }]);
+            assert!(matches!(res, Err(MstError::InvalidKey(_))), "key: {key:?}");
+        }
+    }
+}
@@ -0,0 +1,584 @@
+//! `did:plc` operations.
+//!
+//! A `did:plc` identity is defined by a chain of signed operations, each linking to the previous
+//! one by CID. The DID itself is derived from the hash of the first, or genesis, operation. See
+//! the [DID PLC] specification.
+//!
+//! [DID PLC]: https://web.plc.directory/spec/v0.1/did-plc
+
+use std::{collections::BTreeMap, collections::HashSet, fmt, str::FromStr};
+
+use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
+use jiff::SignedDuration;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    cid::{CidString, Codec},
+    crypto::{PublicKey, Signer},
+    CidLink, DateTime, Did,
+};
+
+/// The time after an operation during which it may be nullified by a higher-priority rotation key.
+pub const RECOVERY_WINDOW: SignedDuration = SignedDuration::from_hours(72);
+
+/// A `did:plc` operation, without its signature.
+///
+/// The signature of a [`SignedOperation`] is computed over the DAG-CBOR encoding of this object.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum UnsignedOperation {
+    /// A regular operation, which sets the full state of the identity.
+    #[serde(rename = "plc_operation")]
+    Operation(Operation),
+    /// An operation which permanently deactivates the identity.
+    #[serde(rename = "plc_tombstone")]
+    Tombstone(Tombstone),
+    /// A legacy genesis operation.
+    ///
+    /// These are no longer created, but may appear at the start of existing logs.
+    #[serde(rename = "create")]
+    Create(LegacyCreate),
+}
+
+/// The state of a `did:plc` identity, set by a `plc_operation`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    /// The `did:key` rotation keys which may sign the next operation, in order of priority.
+    pub rotation_keys: Vec<Did>,
+    /// The `did:key` verification methods of the DID document, by fragment.
+    pub verification_methods: BTreeMap<String, Did>,
+    /// The `alsoKnownAs` URIs of the DID document, such as `at://` handles.
+    pub also_known_as: Vec<String>,
+    /// The services of the DID document, by fragment.
+    pub services: BTreeMap<String, Service>,
+    /// The CID of the previous operation, or `None` for the genesis operation.
+    ///
+    /// This is always encoded.
+    pub prev: Option<CidString>,
+}
+
+/// A service in a `plc_operation`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Service {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub endpoint: String,
+}
+
+/// A `plc_tombstone` operation.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Tombstone {
+    /// The CID of the previous operation.
+    pub prev: CidString,
+}
+
+/// A legacy `create` operation.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyCreate {
+    /// The `did:key` ATProto signing key.
+    pub signing_key: Did,
+    /// The `did:key` recovery key, which takes priority over the signing key for rotation.
+    pub recovery_key: Did,
+    /// The account's handle, without the `at://` prefix.
+    pub handle: String,
+    /// The URL of the account's PDS.
+    pub service: String,
+    /// Always `None`.
+    pub prev: Option<CidString>,
+}
+
+impl UnsignedOperation {
+    /// Returns the CID of the previous operation, or `None` for a genesis operation.
+    pub fn prev(&self) -> Option<&CidString> {
+        match self {
+            UnsignedOperation::Operation(op) => op.prev.as_ref(),
+            UnsignedOperation::Tombstone(op) => Some(&op.prev),
+            UnsignedOperation::Create(op) => op.prev.as_ref(),
+        }
+    }
+
+    /// Returns the rotation keys which may sign the next operation, in order of priority.
+    ///
+    /// A tombstone has no rotation keys.
+    pub fn rotation_keys(&self) -> Vec<&Did> {
+        match self {
+            UnsignedOperation::Operation(op) => op.rotation_keys.iter().collect(),
+            UnsignedOperation::Tombstone(_) => Vec::new(),
+            UnsignedOperation::Create(op) => vec![&op.recovery_key, &op.signing_key],
+        }
+    }
+
+    /// Encodes this operation as DAG-CBOR, producing the bytes to be signed.
+    pub fn encode(&self) -> Vec<u8> {
+        serde_ipld_dagcbor::to_vec(self).expect("operation serialization should never fail")
+    }
+
+    /// Signs this operation with `signer`, which should hold one of the previous operation's
+    /// rotation keys.
+    pub fn sign<S>(self, signer: &S) -> Result<SignedOperation, S::Error>
+    where
+        S: Signer + ?Sized,
+    {
+        let sig = signer.sign(&self.encode())?;
+
+        Ok(SignedOperation {
+            op: self,
+            sig: BASE64URL_NOPAD.encode(&sig),
+        })
+    }
+}
+
+/// A signed `did:plc` operation.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SignedOperation {
+    #[serde(flatten)]
+    pub op: UnsignedOperation,
+    /// The base64url-encoded signature of the DAG-CBOR encoded [`UnsignedOperation`].
+    pub sig: String,
+}
+
+impl SignedOperation {
+    /// Encodes this operation as DAG-CBOR.
+    pub fn encode(&self) -> Vec<u8> {
+        serde_ipld_dagcbor::to_vec(self).expect("operation serialization should never fail")
+    }
+
+    /// Returns the CID of this operation, which is referenced by the next operation's `prev`.
+    pub fn cid(&self) -> CidLink {
+        CidLink::compute(Codec::DagCbor, &self.encode())
+    }
+
+    /// Derives a DID from this operation.
+    ///
+    /// This is only meaningful for genesis operations, whose hash defines the DID.
+    pub fn did(&self) -> Did {
+        let digest = Sha256::digest(self.encode());
+        let encoded = BASE32_NOPAD.encode(&digest).to_ascii_lowercase();
+
+        Did::from_str(&format!("did:plc:{}", &encoded[..24]))
+            .expect("did:plc should be a valid DID")
+    }
+
+    /// Verifies the signature of this operation against `keys`.
+    ///
+    /// Returns the index of the key which produced the signature.
+    pub fn verify(&self, keys: &[&Did]) -> Result<usize, PlcError> {
+        let sig = BASE64URL_NOPAD
+            .decode(self.sig.as_bytes())
+            .map_err(|_| PlcError::InvalidSignature)?;
+        let msg = self.op.encode();
+
+        keys.iter()
+            .position(|key| {
+                // Keys which can't be parsed can't have produced the signature.
+                PublicKey::try_from(*key).is_ok_and(|key| key.verify(&msg, &sig).is_ok())
+            })
+            .ok_or(PlcError::InvalidSignature)
+    }
+}
+
+/// An entry in the audit log of a `did:plc` identity, as returned by a PLC directory's
+/// `/<did>/log/audit` endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub did: Did,
+    pub operation: SignedOperation,
+    pub cid: CidString,
+    /// Whether the operation was nullified by a later recovery operation.
+    pub nullified: bool,
+    pub created_at: DateTime,
+}
+
+/// Verifies the audit log of `did`, returning the operation which defines its current state.
+///
+/// This checks that the genesis operation derives `did`, that each operation is signed by a
+/// rotation key of the operation it follows, and that exactly those operations marked as
+/// nullified were replaced by a higher-priority rotation key within the [`RECOVERY_WINDOW`].
+pub fn verify_audit_log<'a>(
+    did: &Did,
+    log: &'a [AuditEntry],
+) -> Result<&'a SignedOperation, PlcError> {
+    // The indices of the currently valid operations, and the index of the key which signed each.
+    let mut chain: Vec<(usize, usize)> = Vec::new();
+    let mut nullified = HashSet::new();
+
+    for (i, entry) in log.iter().enumerate() {
+        if entry.did != *did {
+            return Err(PlcError::DidMismatch);
+        }
+
+        if CidString::from(entry.operation.cid()) != entry.cid {
+            return Err(PlcError::CidMismatch);
+        }
+
+        let Some(prev) = entry.operation.op.prev() else {
+            if i != 0 {
+                return Err(PlcError::InvalidPrev);
+            }
+
+            if entry.operation.did() != *did {
+                return Err(PlcError::DidMismatch);
+            }
+
+            let signer = entry
+                .operation
+                .verify(&entry.operation.op.rotation_keys())?;
+            chain.push((i, signer));
+            continue;
+        };
+
+        let pos = chain
+            .iter()
+            .position(|&(j, _)| log[j].cid == *prev)
+            .ok_or(PlcError::InvalidPrev)?;
+        let keys = log[chain[pos].0].operation.op.rotation_keys();
+        let signer = entry.operation.verify(&keys)?;
+
+        // An operation which doesn't follow the latest one nullifies those after its `prev`.
+        if let Some(&(first, first_signer)) = chain.get(pos + 1) {
+            if signer >= first_signer {
+                return Err(PlcError::UnauthorizedRecovery);
+            }
+
+            let elapsed = entry
+                .created_at
+                .timestamp()
+                .duration_since(log[first].created_at.timestamp());
+
+            if elapsed > RECOVERY_WINDOW {
+                return Err(PlcError::RecoveryWindowExpired);
+            }
+
+            nullified.extend(chain.drain(pos + 1..).map(|(j, _)| j));
+        }
+
+        chain.push((i, signer));
+    }
+
+    if log
+        .iter()
+        .enumerate()
+        .any(|(i, entry)| entry.nullified != nullified.contains(&i))
+    {
+        return Err(PlcError::NullifiedMismatch);
+    }
+
+    let &(head, _) = chain.last().ok_or(PlcError::EmptyLog)?;
+
+    Ok(&log[head].operation)
+}
+
+/// An error produced while verifying `did:plc` operations.
+#[derive(Debug)]
+pub enum PlcError {
+    /// An operation's CID does not match its contents.
+    CidMismatch,
+    /// An operation belongs to a different DID.
+    DidMismatch,
+    /// The audit log is empty.
+    EmptyLog,
+    /// An operation's `prev` does not refer to a valid earlier operation.
+    InvalidPrev,
+    /// An operation is not signed by any of the rotation keys of the operation it follows.
+    InvalidSignature,
+    /// The operations marked as nullified are not those replaced by recovery operations.
+    NullifiedMismatch,
+    /// A recovery operation was submitted too late.
+    RecoveryWindowExpired,
+    /// A recovery operation was not signed by a higher-priority key than the operation it
+    /// replaces.
+    UnauthorizedRecovery,
+}
+
+impl fmt::Display for PlcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PlcError::CidMismatch => "operation CID does not match contents",
+            PlcError::DidMismatch => "operation does not belong to DID",
+            PlcError::EmptyLog => "audit log is empty",
+            PlcError::InvalidPrev => "operation does not follow a valid operation",
+            PlcError::InvalidSignature => "operation not signed by a rotation key",
+            PlcError::NullifiedMismatch => "nullified operations do not match recovery operations",
+            PlcError::RecoveryWindowExpired => "recovery operation outside recovery window",
+            PlcError::UnauthorizedRecovery => "recovery operation signed by lower-priority key",
+        })
+    }
+}
+
+impl std::error::Error for PlcError {}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use p256::ecdsa::{signature::Signer as _, Signature, SigningKey};
+    use serde_json::json;
+
+    use super::*;
+
+    struct TestKey(SigningKey);
+
+    impl Signer for TestKey {
+        type Error = Infallible;
+
+        fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, Infallible> {
+            let sig: Signature = self.0.sign(msg);
+            Ok(sig.normalize_s().unwrap_or(sig).to_vec())
+        }
+    }
+
+    impl TestKey {
+        fn new(seed: u8) -> Self {
+            TestKey(SigningKey::from_slice(&[seed; 32]).unwrap())
+        }
+
+        fn did(&self) -> Did {
+            Did::from(&PublicKey::P256(*self.0.verifying_key()))
+        }
+    }
+
+    fn operation(keys: &[&TestKey], prev: Option<CidLink>) -> UnsignedOperation {
+        UnsignedOperation::Operation(Operation {
+            rotation_keys: keys.iter().map(|k| k.did()).collect(),
+            verification_methods: BTreeMap::from([("atproto".into(), keys[0].did())]),
+            also_known_as: vec!["at://alice.test".into()],
+            services: BTreeMap::from([(
+                "atproto_pds".into(),
+                Service {
+                    ty: "AtprotoPersonalDataServer".into(),
+                    endpoint: "https://pds.example.com".into(),
+                },
+            )]),
+            prev: prev.map(Into::into),
+        })
+    }
+
+    fn entry(did: &Did, operation: SignedOperation, hours: i64) -> AuditEntry {
+        AuditEntry {
+            did: did.clone(),
+            cid: operation.cid().into(),
+            operation,
+            nullified: false,
+            created_at: DateTime::from_unix_seconds(1_700_000_000 + hours * 60 * 60).unwrap(),
+        }
+    }
+
+    /// Returns a log with a genesis operation signed by `keys[0]`, followed by an update signed by
+    /// `keys[1]`.
+    fn log(keys: &[&TestKey]) -> (Did, Vec<AuditEntry>) {
+        let genesis = operation(keys, None).sign(keys[0]).unwrap();
+        let did = genesis.did();
+        let update = operation(keys, Some(genesis.cid())).sign(keys[1]).unwrap();
+
+        let log = vec![entry(&did, genesis, 0), entry(&did, update, 1)];
+        (did, log)
+    }
+
+    #[test]
+    fn encoding() {
+        let key = TestKey::new(1);
+        let op = operation(&[&key], None).sign(&key).unwrap();
+
+        let value = serde_json::to_value(&op).unwrap();
+        assert_eq!(value["type"], "plc_operation");
+        assert_eq!(value["prev"], serde_json::Value::Null);
+        assert_eq!(value["rotationKeys"], json!([key.did()]));
+        assert_eq!(
+            value["services"]["atproto_pds"]["type"],
+            "AtprotoPersonalDataServer"
+        );
+        assert_eq!(
+            serde_json::from_value::<SignedOperation>(value).unwrap(),
+            op
+        );
+
+        // The signature covers everything but itself.
+        let unsigned: ipld_core::ipld::Ipld =
+            serde_ipld_dagcbor::from_slice(&op.op.encode()).unwrap();
+        assert!(unsigned.get("sig").unwrap().is_none());
+        assert!(unsigned.get("prev").unwrap().is_some());
+
+        let tombstone = UnsignedOperation::Tombstone(Tombstone {
+            prev: op.cid().into(),
+        });
+        let value = serde_json::to_value(&tombstone).unwrap();
+        assert_eq!(
+            value,
+            json!({"type": "plc_tombstone", "prev": op.cid().to_string()})
+        );
+    }
+
+    #[test]
+    fn legacy_create() {
+        let recovery = TestKey::new(1);
+        let signing = TestKey::new(2);
+
+        let op: SignedOperation = serde_json::from_value(json!({
+            "type": "create",
+            "signingKey": signing.did(),
+            "recoveryKey": recovery.did(),
+            "handle": "alice.test",
+            "service": "https://pds.example.com",
+            "prev": null,
+            "sig": "",
+        }))
+        .unwrap();
+
+        assert_eq!(op.op.prev(), None);
+        assert_eq!(op.op.rotation_keys(), [&recovery.did(), &signing.did()]);
+
+        let op = op.op.sign(&signing).unwrap();
+        assert_eq!(op.verify(&op.op.rotation_keys()).unwrap(), 1);
+    }
+
+    #[test]
+    fn did_derivation() {
+        let key = TestKey::new(1);
+        let op = operation(&[&key], None).sign(&key).unwrap();
+
+        let did = op.did();
+        let suffix = did.as_str().strip_prefix("did:plc:").unwrap();
+        assert_eq!(suffix.len(), 24);
+        assert!(suffix
+            .bytes()
+            .all(|b| matches!(b, b'a'..=b'z' | b'2'..=b'7')));
+
+        let digest = Sha256::digest(op.encode());
+        assert_eq!(
+            BASE32_NOPAD
+                .decode(suffix[..16].to_ascii_uppercase().as_bytes())
+                .unwrap(),
+            &digest[..10]
+        );
+    }
+
+    #[test]
+    fn verify_signature() {
+        let (k0, k1) = (TestKey::new(1), TestKey::new(2));
+        let op = operation(&[&k0], None).sign(&k1).unwrap();
+
+        assert_eq!(op.verify(&[&k0.did(), &k1.did()]).unwrap(), 1);
+        assert!(matches!(
+            op.verify(&[&k0.did()]),
+            Err(PlcError::InvalidSignature)
+        ));
+
+        let mut tampered = op.clone();
+        tampered.sig.replace_range(..4, "AAAA");
+        assert!(matches!(
+            tampered.verify(&[&k1.did()]),
+            Err(PlcError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn audit_log() {
+        let (k0, k1) = (TestKey::new(1), TestKey::new(2));
+        let (did, log) = log(&[&k0, &k1]);
+
+        assert_eq!(verify_audit_log(&did, &log).unwrap(), &log[1].operation);
+        assert_eq!(
+            verify_audit_log(&did, &log[..1]).unwrap(),
+            &log[0].operation
+        );
+        assert!(matches!(
+            verify_audit_log(&did, &[]),
+            Err(PlcError::EmptyLog)
+        ));
+
+        let other = Did::from_str("did:plc:z72i7hdynmk6r22z27h6tvur").unwrap();
+        assert!(matches!(
+            verify_audit_log(&other, &log),
+            Err(PlcError::DidMismatch)
+        ));
+
+        let mut bad_cid = log.clone();
+        bad_cid[1].cid = bad_cid[0].cid.clone();
+        assert!(matches!(
+            verify_audit_log(&did, &bad_cid),
+            Err(PlcError::CidMismatch)
+        ));
+
+        // Not signed by a rotation key of the previous operation.
+        let k2 = TestKey::new(3);
+        let mut bad_sig = log.clone();
+        let op = operation(&[&k0], Some(log[0].operation.cid()))
+            .sign(&k2)
+            .unwrap();
+        bad_sig[1] = entry(&did, op, 1);
+        assert!(matches!(
+            verify_audit_log(&did, &bad_sig),
+            Err(PlcError::InvalidSignature)
+        ));
+
+        // Nothing may follow a tombstone.
+        let mut tombstoned = log.clone();
+        let tombstone = UnsignedOperation::Tombstone(Tombstone {
+            prev: log[1].cid.clone(),
+        })
+        .sign(&k0)
+        .unwrap();
+        let cid = tombstone.cid();
+        tombstoned.push(entry(&did, tombstone, 2));
+        assert_eq!(
+            verify_audit_log(&did, &tombstoned).unwrap(),
+            &tombstoned[2].operation
+        );
+
+        let op = operation(&[&k0], Some(cid)).sign(&k0).unwrap();
+        tombstoned.push(entry(&did, op, 3));
+        assert!(matches!(
+            verify_audit_log(&did, &tombstoned),
+            Err(PlcError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn audit_log_recovery() {
+        let (k0, k1) = (TestKey::new(1), TestKey::new(2));
+        let (did, mut log) = log(&[&k0, &k1]);
+
+        let recover = |signer: &TestKey, hours| {
+            let op = operation(&[&k0], Some(log[0].operation.cid()))
+                .sign(signer)
+                .unwrap();
+            entry(&did, op, hours)
+        };
+
+        // The update signed by k1 is replaced by k0 within the recovery window.
+        let mut recovered = log.clone();
+        recovered.push(recover(&k0, 72));
+        recovered[1].nullified = true;
+        assert_eq!(
+            verify_audit_log(&did, &recovered).unwrap(),
+            &recovered[2].operation
+        );
+
+        // The nullified flags must match.
+        recovered[1].nullified = false;
+        assert!(matches!(
+            verify_audit_log(&did, &recovered),
+            Err(PlcError::NullifiedMismatch)
+        ));
+
+        let mut late = log.clone();
+        late.push(recover(&k0, 74));
+        late[1].nullified = true;
+        assert!(matches!(
+            verify_audit_log(&did, &late),
+            Err(PlcError::RecoveryWindowExpired)
+        ));
+
+        let unauthorized = recover(&k1, 2);
+        log.push(unauthorized);
+        log[1].nullified = true;
+        assert!(matches!(
+            verify_audit_log(&did, &log),
+            Err(PlcError::UnauthorizedRecovery)
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+//! Typed records.
+//!
+//! Each Lexicon `record` definition is represented by a type implementing [`Record`], which
+//! identifies the record's collection and the format of its record keys. See the [Record Keys]
+//! section of the ATProto specification.
+//!
+//! [Record Keys]: https://atproto.com/specs/record-key
+
+use std::str::FromStr;
+
+use serde::{de::DeserializeOwned, ser::Error as _, Deserialize, Serialize};
+
+use crate::{Nsid, RecordKey, Tid, Unknown};
+
+/// The format of the record keys of a collection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyType {
+    /// The record key is a TID, usually generated by the PDS.
+    Tid,
+    /// The record key is an NSID.
+    Nsid,
+    /// The record key is always the given value, such as `self`.
+    Literal(&'static str),
+    /// The record key may be any valid record key.
+    Any,
+}
+
+impl KeyType {
+    /// Returns whether `rkey` is a valid record key of this type.
+    pub fn accepts(&self, rkey: &str) -> bool {
+        match self {
+            KeyType::Tid => Tid::from_str(rkey).is_ok(),
+            KeyType::Nsid => Nsid::from_str(rkey).is_ok(),
+            KeyType::Literal(value) => rkey == *value,
+            KeyType::Any => RecordKey::from_str(rkey).is_ok(),
+        }
+    }
+}
+
+/// A trait for the types of records stored in a repository.
+pub trait Record: Serialize + DeserializeOwned {
+    /// The NSID of the record type, which is also the name of its collection.
+    const NSID: &'static str;
+
+    /// The format of the record keys of the collection.
+    const KEY: KeyType;
+
+    /// Converts this record to an untyped value, with its `$type` set to [`NSID`](Self::NSID).
+    fn to_unknown(&self) -> Result<Unknown, serde_json::Error> {
+        let serde_json::Value::Object(mut map) = serde_json::to_value(self)? else {
+            return Err(serde_json::Error::custom("record is not an object"));
+        };
+        map.insert("$type".into(), Self::NSID.into());
+
+        Unknown::deserialize(serde_json::Value::Object(map))
+    }
+
+    /// Converts an untyped value to a record.
+    ///
+    /// The `$type` of `value` is not checked.
+    fn from_unknown(value: &Unknown) -> Result<Self, serde_json::Error> {
+        Self::deserialize(serde::de::IntoDeserializer::into_deserializer(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Status {
+        text: String,
+    }
+
+    impl Record for Status {
+        const NSID: &'static str = "com.example.status";
+        const KEY: KeyType = KeyType::Literal("self");
+    }
+
+    #[test]
+    fn key_types() {
+        assert!(KeyType::Tid.accepts("3jzfcijpj2z2a"));
+        assert!(!KeyType::Tid.accepts("self"));
+        assert!(KeyType::Nsid.accepts("com.example.status"));
+        assert!(!KeyType::Nsid.accepts("3jzfcijpj2z2a"));
+        assert!(KeyType::Literal("self").accepts("self"));
+        assert!(!KeyType::Literal("self").accepts("other"));
+        assert!(KeyType::Any.accepts("self"));
+        assert!(!KeyType::Any.accepts(".."));
+    }
+
+    #[test]
+    fn unknown_roundtrip() {
+        let status = Status {
+            text: "hello".into(),
+        };
+
+        let unknown = status.to_unknown().unwrap();
+        assert_eq!(unknown.ty().unwrap().as_str(), "com.example.status");
+        assert_eq!(
+            serde_json::to_value(&unknown).unwrap(),
+            json!({ "$type": "com.example.status", "text": "hello" })
+        );
+        assert_eq!(Status::from_unknown(&unknown).unwrap(), status);
+    }
+}
@@ -32,8 +32,17 @@ pub trait Request {
     fn deserialize_params(query: &str) -> Result<Self::Params, serde_urlencoded_xrpc::de::Error>;
 
     /// Returns the media (MIME) type of the request body.
+    ///
+    /// This is the `encoding` of the method's input in its lexicon, which may be a wildcard such
+    /// as `*/*`. See [`encoding_matches`].
     fn input_content_type() -> Option<&'static str>;
 
+    /// Returns the media (MIME) type of the response body.
+    ///
+    /// This is the `encoding` of the method's output in its lexicon, which may be a wildcard such
+    /// as `*/*`. See [`encoding_matches`].
+    fn output_content_type() -> Option<&'static str>;
+
     /// Serializes this RPC's input to a byte buffer.
     fn serialize_input(input: &Self::Input) -> Result<Bytes, Self::InputError>;
 
@@ -47,14 +56,181 @@ pub trait Request {
     fn deserialize_output(bytes: &Bytes) -> Result<Self::Output, Self::OutputError>;
 }
 
+/// A trait for XRPC methods whose results are split into pages.
+///
+/// The output of each page has a cursor, which is passed back in the parameters to fetch the next
+/// page. The last page has no cursor.
+pub trait Paginated: Request {
+    /// The type of the items listed in each page.
+    type Item;
+
+    /// Returns the cursor of the next page, if there is one.
+    fn cursor(output: &Self::Output) -> Option<&str>;
+
+    /// Sets the cursor of the page to fetch.
+    fn set_cursor(params: &mut Self::Params, cursor: Option<String>);
+
+    /// Consumes a page, returning its items.
+    fn into_items(output: Self::Output) -> Vec<Self::Item>;
+}
+
+/// Returns whether the media type `content_type` is acceptable for a body whose lexicon
+/// `encoding` is `encoding`.
+///
+/// `encoding` may be `*/*` or a wildcard subtype such as `image/*`. Parameters of `content_type`,
+/// such as `charset`, are ignored.
+pub fn encoding_matches(encoding: &str, content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or_default().trim();
+
+    match encoding.split_once('/') {
+        Some(("*", "*")) => true,
+        Some((ty, "*")) => media_type
+            .split_once('/')
+            .is_some_and(|(actual, _)| actual.eq_ignore_ascii_case(ty)),
+        _ => media_type.eq_ignore_ascii_case(encoding),
+    }
+}
+
+/// The successful response to an XRPC request, as sent by a server.
+///
+/// This pairs the method's output with headers for the HTTP response. In particular, a method
+/// whose output encoding is a wildcard such as `*/*` should set the actual media type of its
+/// output with [`content_type`](Self::content_type).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Response<T> {
+    /// The output of the method.
+    pub output: T,
+    /// The headers of the response.
+    pub headers: http::HeaderMap,
+}
+
+impl<T> Response<T> {
+    /// Creates a response with `output` and no headers.
+    #[inline]
+    pub fn new(output: T) -> Self {
+        Response {
+            output,
+            headers: http::HeaderMap::new(),
+        }
+    }
+
+    /// Sets the media type of the output, overriding the method's output encoding.
+    #[inline]
+    pub fn content_type(mut self, content_type: http::HeaderValue) -> Self {
+        self.headers
+            .insert(http::header::CONTENT_TYPE, content_type);
+        self
+    }
+
+    /// Encodes this response as the output of the XRPC method `R`.
+    ///
+    /// Unless a `Content-Type` header has been set, the media type is the method's output
+    /// encoding, or `application/octet-stream` if the encoding is a wildcard. Methods without
+    /// output respond with an empty body.
+    pub fn encode<R>(self) -> Result<http::Response<Bytes>, R::OutputError>
+    where
+        R: Request<Output = T>,
+    {
+        let Some(encoding) = R::output_content_type() else {
+            let mut response = http::Response::new(Bytes::new());
+            *response.headers_mut() = self.headers;
+            return Ok(response);
+        };
+
+        let mut response = http::Response::new(R::serialize_output(&self.output)?);
+        *response.headers_mut() = self.headers;
+        if !response.headers().contains_key(http::header::CONTENT_TYPE) {
+            // The actual type of a wildcard output is unknown.
+            let content_type = if encoding.contains('*') {
+                "application/octet-stream"
+            } else {
+                encoding
+            };
+            response.headers_mut().insert(
+                http::header::CONTENT_TYPE,
+                http::HeaderValue::from_static(content_type),
+            );
+        }
+
+        Ok(response)
+    }
+}
+
+impl<T> From<T> for Response<T> {
+    #[inline]
+    fn from(output: T) -> Self {
+        Response::new(output)
+    }
+}
+
+/// A trait for types which represent an XRPC subscription.
+///
+/// Subscriptions are event streams delivered over a WebSocket. See the [Event Stream] section of
+/// the ATProto specification.
+///
+/// [Event Stream]: https://atproto.com/specs/event-stream
+pub trait Subscription {
+    type Params;
+
+    /// The type of the messages sent by the server.
+    ///
+    /// This is a union of all message types defined by the subscription.
+    type Message;
+
+    /// The type of the error code sent in an error frame.
+    type RpcError: DeserializeOwned;
+
+    /// The unique NSID of the XRPC subscription.
+    fn nsid() -> &'static str;
+
+    /// Serializes this subscription's query parameters to a query string.
+    fn serialize_params(params: &Self::Params)
+        -> Result<String, serde_urlencoded_xrpc::ser::Error>;
+
+    /// Deserializes this subscription's query parameters from a query string.
+    fn deserialize_params(query: &str) -> Result<Self::Params, serde_urlencoded_xrpc::de::Error>;
+}
+
 /// A generic XRPC error.
-#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(bound(serialize = "E: Serialize", deserialize = "E: DeserializeOwned"))]
 pub struct Error<E> {
-    pub error: E,
+    pub error: ErrorCode<E>,
     #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
     pub message: Option<String>,
 }
 
+impl<E> Error<E> {
+    /// Creates an error with no message.
+    #[inline]
+    pub fn new(error: ErrorCode<E>) -> Self {
+        Error {
+            error,
+            message: None,
+        }
+    }
+
+    /// Sets the human-readable message of this error.
+    #[inline]
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Returns the HTTP status code of a response with this error.
+    #[inline]
+    pub fn status(&self) -> http::StatusCode {
+        self.error.status()
+    }
+}
+
+impl<E> From<ErrorCode<E>> for Error<E> {
+    #[inline]
+    fn from(error: ErrorCode<E>) -> Self {
+        Error::new(error)
+    }
+}
+
 impl<E> fmt::Display for Error<E>
 where
     E: fmt::Display,
@@ -67,6 +243,185 @@ where
     }
 }
 
+impl<E> StdError for Error<E> where E: fmt::Debug + fmt::Display {}
+
+/// The error code of an XRPC error.
+///
+/// This is either one of the codes shared by all methods, or one declared by the method. See the
+/// [Error Responses] section of the XRPC specification.
+///
+/// Shared codes take precedence, so a method error with the same name as a shared code is
+/// deserialized as the shared code.
+///
+/// [Error Responses]: https://atproto.com/specs/xrpc#error-responses
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorCode<E> {
+    /// The request was invalid (400).
+    InvalidRequest,
+    /// The access token has expired (400).
+    ExpiredToken,
+    /// The access token is invalid (400).
+    InvalidToken,
+    /// The method requires authentication (401).
+    AuthRequired,
+    /// The caller is not allowed to call the method (403).
+    Forbidden,
+    /// The request body is too large (413).
+    PayloadTooLarge,
+    /// The caller has sent too many requests (429).
+    RateLimitExceeded,
+    /// The server failed to handle the request (500).
+    InternalServerError,
+    /// The server does not implement the method (501).
+    MethodNotImplemented,
+    /// An upstream service failed (502).
+    UpstreamFailure,
+    /// The server is overloaded (503).
+    NotEnoughResources,
+    /// An upstream service timed out (504).
+    UpstreamTimeout,
+    /// An error declared by the method (400).
+    Method(E),
+}
+
+impl<E> ErrorCode<E> {
+    /// Parses one of the shared error codes.
+    pub fn shared(code: &str) -> Option<Self> {
+        let code = match code {
+            "InvalidRequest" => ErrorCode::InvalidRequest,
+            "ExpiredToken" => ErrorCode::ExpiredToken,
+            "InvalidToken" => ErrorCode::InvalidToken,
+            "AuthenticationRequired" => ErrorCode::AuthRequired,
+            "Forbidden" => ErrorCode::Forbidden,
+            "PayloadTooLarge" => ErrorCode::PayloadTooLarge,
+            "RateLimitExceeded" => ErrorCode::RateLimitExceeded,
+            "InternalServerError" => ErrorCode::InternalServerError,
+            "MethodNotImplemented" => ErrorCode::MethodNotImplemented,
+            "UpstreamFailure" => ErrorCode::UpstreamFailure,
+            "NotEnoughResources" => ErrorCode::NotEnoughResources,
+            "UpstreamTimeout" => ErrorCode::UpstreamTimeout,
+            _ => return None,
+        };
+
+        Some(code)
+    }
+
+    /// Returns the shared error code for responses with `status` and no XRPC error body.
+    ///
+    /// Proxies and load balancers may respond with a bare status, such as 429 or 502.
+    pub fn from_status(status: http::StatusCode) -> Option<Self> {
+        let code = match status.as_u16() {
+            400 => ErrorCode::InvalidRequest,
+            401 => ErrorCode::AuthRequired,
+            403 => ErrorCode::Forbidden,
+            413 => ErrorCode::PayloadTooLarge,
+            429 => ErrorCode::RateLimitExceeded,
+            500 => ErrorCode::InternalServerError,
+            501 => ErrorCode::MethodNotImplemented,
+            502 => ErrorCode::UpstreamFailure,
+            503 => ErrorCode::NotEnoughResources,
+            504 => ErrorCode::UpstreamTimeout,
+            _ => return None,
+        };
+
+        Some(code)
+    }
+
+    /// Returns the HTTP status code of a response with this error.
+    pub fn status(&self) -> http::StatusCode {
+        use http::StatusCode;
+
+        match self {
+            ErrorCode::InvalidRequest
+            | ErrorCode::ExpiredToken
+            | ErrorCode::InvalidToken
+            | ErrorCode::Method(_) => StatusCode::BAD_REQUEST,
+            ErrorCode::AuthRequired => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::MethodNotImplemented => StatusCode::NOT_IMPLEMENTED,
+            ErrorCode::UpstreamFailure => StatusCode::BAD_GATEWAY,
+            ErrorCode::NotEnoughResources => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::UpstreamTimeout => StatusCode::GATEWAY_TIMEOUT,
+        }
+    }
+
+    /// Returns the name of a shared error code, or `None` for a method error.
+    #[inline]
+    pub fn shared_name(&self) -> Option<&'static str> {
+        self.split().ok()
+    }
+
+    /// Returns the name of a shared error code, or the method error.
+    fn split(&self) -> Result<&'static str, &E> {
+        let name = match self {
+            ErrorCode::InvalidRequest => "InvalidRequest",
+            ErrorCode::ExpiredToken => "ExpiredToken",
+            ErrorCode::InvalidToken => "InvalidToken",
+            ErrorCode::AuthRequired => "AuthenticationRequired",
+            ErrorCode::Forbidden => "Forbidden",
+            ErrorCode::PayloadTooLarge => "PayloadTooLarge",
+            ErrorCode::RateLimitExceeded => "RateLimitExceeded",
+            ErrorCode::InternalServerError => "InternalServerError",
+            ErrorCode::MethodNotImplemented => "MethodNotImplemented",
+            ErrorCode::UpstreamFailure => "UpstreamFailure",
+            ErrorCode::NotEnoughResources => "NotEnoughResources",
+            ErrorCode::UpstreamTimeout => "UpstreamTimeout",
+            ErrorCode::Method(e) => return Err(e),
+        };
+
+        Ok(name)
+    }
+}
+
+impl<E> fmt::Display for ErrorCode<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.split() {
+            Ok(name) => f.write_str(name),
+            Err(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl<E> Serialize for ErrorCode<E>
+where
+    E: Serialize,
+{
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.split() {
+            Ok(name) => ser.serialize_str(name),
+            Err(e) => e.serialize(ser),
+        }
+    }
+}
+
+impl<'de, E> Deserialize<'de> for ErrorCode<E>
+where
+    E: DeserializeOwned,
+{
+    fn deserialize<D>(des: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::IntoDeserializer as _;
+
+        let code = String::deserialize(des)?;
+
+        match ErrorCode::shared(&code) {
+            Some(code) => Ok(code),
+            None => E::deserialize(code.into_deserializer()).map(ErrorCode::Method),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
@@ -77,7 +432,7 @@ mod tests {
     fn error_roundtrip() {
         // With message
         let i1 = Error {
-            error: String::from("Error"),
+            error: ErrorCode::Method(String::from("Error")),
             message: Some("Message!".into()),
         };
 
@@ -89,7 +444,7 @@ mod tests {
 
         // Without message
         let i2 = Error {
-            error: String::from("Error"),
+            error: ErrorCode::Method(String::from("Error")),
             message: None,
         };
 
@@ -99,4 +454,43 @@ mod tests {
         let d2 = serde_json::from_value(s2).unwrap();
         assert_eq!(i2, d2);
     }
+
+    #[test]
+    fn encodings() {
+        assert!(encoding_matches("application/json", "application/json"));
+        assert!(encoding_matches(
+            "application/json",
+            "Application/JSON; charset=utf-8"
+        ));
+        assert!(!encoding_matches("application/json", "text/plain"));
+        assert!(encoding_matches("*/*", "image/png"));
+        assert!(encoding_matches("image/*", "image/png"));
+        assert!(!encoding_matches("image/*", "video/mp4"));
+        assert!(!encoding_matches("application/vnd.ipld.car", ""));
+    }
+
+    #[test]
+    fn shared_codes() {
+        let e: Error<String> =
+            serde_json::from_value(json!({ "error": "AuthenticationRequired" })).unwrap();
+        assert_eq!(e.error, ErrorCode::AuthRequired);
+        assert_eq!(e.status(), http::StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            serde_json::to_value(&e).unwrap(),
+            json!({ "error": "AuthenticationRequired" })
+        );
+
+        let e: Error<String> = serde_json::from_value(json!({ "error": "Custom" })).unwrap();
+        assert_eq!(e.error, ErrorCode::Method("Custom".into()));
+        assert_eq!(e.status(), http::StatusCode::BAD_REQUEST);
+
+        assert_eq!(
+            ErrorCode::<String>::from_status(http::StatusCode::TOO_MANY_REQUESTS),
+            Some(ErrorCode::RateLimitExceeded)
+        );
+        assert_eq!(
+            ErrorCode::<String>::from_status(http::StatusCode::NOT_FOUND),
+            None
+        );
+    }
 }
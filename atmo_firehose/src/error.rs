@@ -0,0 +1,80 @@
+use std::fmt;
+
+use atmo_api::com::atproto::sync::subscribe_repos;
+use atmo_core::{event_stream::FrameError, xrpc};
+use tokio_tungstenite::tungstenite::{self, protocol::CloseFrame};
+
+/// Error type for firehose operations.
+#[derive(Debug)]
+pub enum Error {
+    /// The underlying WebSocket was closed by the remote peer.
+    Closed(Option<CloseFrame<'static>>),
+    /// A received frame could not be decoded.
+    Frame(FrameError),
+    /// An HTTP protocol error occurred.
+    Http(http::Error),
+    /// The server sent an error frame.
+    ///
+    /// The server closes the connection after sending an error frame.
+    Rpc(xrpc::Error<subscribe_repos::Error>),
+    /// A WebSocket protocol error occurred.
+    WebSocket(tungstenite::Error),
+}
+
+impl From<FrameError> for Error {
+    #[inline]
+    fn from(e: FrameError) -> Self {
+        Error::Frame(e)
+    }
+}
+
+impl From<http::Error> for Error {
+    #[inline]
+    fn from(e: http::Error) -> Self {
+        Error::Http(e)
+    }
+}
+
+impl From<xrpc::Error<subscribe_repos::Error>> for Error {
+    #[inline]
+    fn from(e: xrpc::Error<subscribe_repos::Error>) -> Self {
+        Error::Rpc(e)
+    }
+}
+
+impl From<tungstenite::Error> for Error {
+    #[inline]
+    fn from(e: tungstenite::Error) -> Self {
+        Error::WebSocket(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Closed(opt) => match opt {
+                Some(c) => write!(f, "WebSocket closed by remote peer: {}", &c.reason),
+                None => f.write_str("WebSocket closed by remote peer"),
+            },
+            Error::Frame(e) => fmt::Display::fmt(e, f),
+            Error::Http(e) => fmt::Display::fmt(e, f),
+            Error::Rpc(e) => match &e.message {
+                Some(msg) => write!(f, "{}: {msg}", e.error),
+                None => fmt::Display::fmt(&e.error, f),
+            },
+            Error::WebSocket(e) => fmt::Display::fmt(e, f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Closed(_) => None,
+            Error::Frame(e) => Some(e),
+            Error::Http(e) => Some(e),
+            Error::Rpc(_) => None,
+            Error::WebSocket(e) => Some(e),
+        }
+    }
+}
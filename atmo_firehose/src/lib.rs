@@ -0,0 +1,357 @@
+//! ATProto repository event stream (firehose) subscriber.
+//!
+//! This crate consumes the `com.atproto.sync.subscribeRepos` [event stream] served by PDSes and
+//! relays.
+//!
+//! [event stream]: https://atproto.com/specs/event-stream
+use std::{
+    pin::Pin,
+    task::{ready, Poll},
+};
+
+use atmo_api::com::atproto::sync::{
+    subscribe_repos::{self, Message},
+    SubscribeRepos,
+};
+use atmo_core::{event_stream::Frame, xrpc::Subscription};
+use futures::{Stream, StreamExt};
+use http::{uri::InvalidUri, Uri};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{tungstenite, MaybeTlsStream};
+
+mod error;
+
+pub use error::Error;
+
+/// A frame of the `com.atproto.sync.subscribeRepos` event stream.
+pub type SubscribeReposFrame = Frame<Message, subscribe_repos::Error>;
+
+/// A builder for a [`Subscriber`].
+pub struct SubscriberBuilder {
+    uri: Result<Uri, InvalidUri>,
+    cursor: Option<i64>,
+}
+
+impl SubscriberBuilder {
+    /// Configures the sequence number from which the [`Subscriber`] should replay events.
+    ///
+    /// If the cursor is older than the server's backfill window, the server sends an `#info`
+    /// message with the name `OutdatedCursor` and starts from the oldest available event.
+    #[inline]
+    pub fn cursor(mut self, seq: i64) -> Self {
+        self.cursor = Some(seq);
+        self
+    }
+
+    /// Creates a `Subscriber` with the configured options.
+    pub async fn connect(self) -> Result<Subscriber, Error> {
+        let SubscriberBuilder { uri, cursor } = self;
+
+        let uri = uri.map_err(|e| Error::Http(e.into()))?;
+
+        Subscriber::new(uri, cursor).await
+    }
+}
+
+/// A firehose subscriber.
+///
+/// This type wraps a WebSocket and decodes the [`Message`]s sent by the server. `#info` messages
+/// are yielded as [`Message::Info`], while error frames are yielded as [`Error::Rpc`].
+pub struct Subscriber {
+    ws: tokio_tungstenite::WebSocketStream<MaybeTlsStream<TcpStream>>,
+    // Store URI for reconnects (TODO).
+    _uri: Uri,
+}
+
+impl Subscriber {
+    /// Creates a new [`SubscriberBuilder`] with the default configuration.
+    ///
+    /// If `uri` has no scheme, `wss` is used.
+    #[inline]
+    pub fn builder<U>(uri: U) -> SubscriberBuilder
+    where
+        Uri: TryFrom<U, Error = InvalidUri>,
+    {
+        SubscriberBuilder {
+            uri: uri.try_into(),
+            cursor: Default::default(),
+        }
+    }
+
+    /// Creates a new `Subscriber`.
+    ///
+    /// This connects to the event stream served at `uri`, starting from `cursor` if provided.
+    #[tracing::instrument(skip(uri))]
+    async fn new(uri: Uri, cursor: Option<i64>) -> Result<Self, Error> {
+        let params = subscribe_repos::Params { cursor };
+        let query =
+            SubscribeRepos::serialize_params(&params).expect("serialization should not fail");
+
+        let mut path_and_query = format!("/xrpc/{}", SubscribeRepos::nsid());
+        if !query.is_empty() {
+            path_and_query.push('?');
+            path_and_query.push_str(&query);
+        }
+
+        let scheme = uri.scheme_str().unwrap_or("wss").to_owned();
+
+        let uri = http::uri::Builder::from(uri)
+            .scheme(scheme.as_str())
+            .path_and_query(&path_and_query)
+            .build()
+            .map_err(tungstenite::Error::from)?;
+
+        let (ws, _resp) = tokio_tungstenite::connect_async(&uri).await?;
+
+        Ok(Self { ws, _uri: uri })
+    }
+}
+
+impl Stream for Subscriber {
+    type Item = Result<Message, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            let msg = match ready!(self.ws.poll_next_unpin(cx)) {
+                Some(Ok(msg)) => msg,
+                Some(Err(e)) => return Poll::Ready(Some(Err(Error::WebSocket(e)))),
+                None => return Poll::Ready(None),
+            };
+
+            let bytes = match msg {
+                tungstenite::Message::Binary(b) => b,
+
+                tungstenite::Message::Close(c) => return Poll::Ready(Some(Err(Error::Closed(c)))),
+
+                _ => {
+                    tracing::debug!("unexpected message type");
+                    continue;
+                }
+            };
+
+            let res = match SubscribeReposFrame::decode::<SubscribeRepos>(&bytes) {
+                Ok(Frame::Message(msg)) => Ok(msg),
+                Ok(Frame::Error(e)) => Err(Error::Rpc(e)),
+                Err(e) => Err(Error::Frame(e)),
+            };
+
+            return Poll::Ready(Some(res));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use atmo_api::com::atproto::sync::subscribe_repos::{info, repo_op, Identity, Info};
+    use atmo_core::{
+        car,
+        cid::{CidLink, Codec},
+        commit::Commit,
+        mst::Node,
+        xrpc, DateTime, Did, Handle,
+    };
+    use futures::SinkExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::handshake::server::{
+        Callback, ErrorResponse, Request, Response,
+    };
+
+    use super::*;
+
+    fn identity(seq: i64) -> Message {
+        Message::Identity(Identity {
+            did: Did::from_str("did:plc:ufbl4k27gp6kzas5glhz7fim").unwrap(),
+            handle: None,
+            seq,
+            time: DateTime::from_str("2024-09-05T06:11:04.870Z").unwrap(),
+        })
+    }
+
+    /// A handshake callback which records the request URI.
+    struct RecordUri<'a>(&'a mut Option<Uri>);
+
+    impl Callback for RecordUri<'_> {
+        fn on_request(self, req: &Request, resp: Response) -> Result<Response, ErrorResponse> {
+            *self.0 = Some(req.uri().clone());
+            Ok(resp)
+        }
+    }
+
+    /// Serves a single WebSocket connection, sending each of `frames` as a binary message and
+    /// returning the request URI.
+    async fn replay(listener: TcpListener, frames: Vec<Vec<u8>>) -> Uri {
+        let (stream, _) = listener.accept().await.unwrap();
+
+        let mut uri = None;
+        let mut ws = tokio_tungstenite::accept_hdr_async(stream, RecordUri(&mut uri))
+            .await
+            .unwrap();
+
+        for frame in frames {
+            ws.send(tungstenite::Message::Binary(frame)).await.unwrap();
+        }
+
+        ws.close(None).await.unwrap();
+
+        uri.unwrap()
+    }
+
+    #[tokio::test]
+    async fn replay_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let info = Message::Info(Info {
+            name: info::Name::OutdatedCursor,
+            message: None,
+        });
+
+        let frames = [
+            Frame::Message(info.clone()),
+            Frame::Message(identity(1)),
+            Frame::Message(identity(2)),
+            Frame::Error(xrpc::Error {
+                error: xrpc::ErrorCode::Method(subscribe_repos::Error::ConsumerTooSlow),
+                message: Some("slow down".into()),
+            }),
+        ];
+        let frames = frames
+            .into_iter()
+            .map(|frame| frame.encode::<SubscribeRepos>().unwrap().to_vec())
+            .collect();
+
+        let server = tokio::spawn(replay(listener, frames));
+
+        let mut subscriber = Subscriber::builder(format!("ws://{addr}"))
+            .cursor(1)
+            .connect()
+            .await
+            .unwrap();
+
+        assert_eq!(subscriber.next().await.unwrap().unwrap(), info);
+        assert_eq!(subscriber.next().await.unwrap().unwrap(), identity(1));
+        assert_eq!(subscriber.next().await.unwrap().unwrap(), identity(2));
+
+        match subscriber.next().await.unwrap() {
+            Err(Error::Rpc(e)) => assert_eq!(
+                e.error,
+                xrpc::ErrorCode::Method(subscribe_repos::Error::ConsumerTooSlow)
+            ),
+            other => panic!("expected error frame, got {other:?}"),
+        }
+
+        assert!(matches!(
+            subscriber.next().await.unwrap(),
+            Err(Error::Closed(_))
+        ));
+
+        let uri = server.await.unwrap();
+        assert_eq!(uri.path(), "/xrpc/com.atproto.sync.subscribeRepos");
+        assert_eq!(uri.query(), Some("cursor=1"));
+    }
+
+    #[tokio::test]
+    async fn relay_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // Frames laid out byte for byte as a relay sends them, encoded independently of
+        // `Frame::encode`. The `#commit` frame carries a CAR with the commit, its MST node and the
+        // created record.
+        let frames = vec![
+            include_bytes!("testdata/commit.bin").to_vec(),
+            include_bytes!("testdata/identity.bin").to_vec(),
+        ];
+        let server = tokio::spawn(replay(listener, frames));
+
+        let mut subscriber = Subscriber::builder(format!("ws://{addr}"))
+            .connect()
+            .await
+            .unwrap();
+
+        let Message::Commit(commit) = subscriber.next().await.unwrap().unwrap() else {
+            panic!("expected #commit message");
+        };
+        let did = Did::from_str("did:plc:ufbl4k27gp6kzas5glhz7fim").unwrap();
+        assert_eq!(commit.repo, did);
+        assert_eq!(commit.seq, 2085474113);
+        assert_eq!(commit.rev, "3l3qo2vuowo2b");
+        assert_eq!(Option::from(commit.since), Some("3l3qo2vunfx2b".to_owned()));
+        assert!(!commit.rebase && !commit.too_big && commit.blobs.is_empty());
+
+        let [op] = &commit.ops[..] else {
+            panic!("expected one op, got {:?}", commit.ops);
+        };
+        assert_eq!(op.action, repo_op::Action::Create);
+        assert_eq!(op.path, "app.bsky.feed.post/3l3qo2vutsw2b");
+
+        // The commit and the op link into the blocks, whose CIDs match their contents.
+        let (header, blocks) = car::read_all(&commit.blocks).unwrap();
+        assert_eq!(header.roots, std::slice::from_ref(&commit.commit));
+        for (cid, data) in &blocks {
+            assert_eq!(*cid, CidLink::compute(Codec::DagCbor, data));
+        }
+        let block = |cid: &CidLink| {
+            let (_, data) = blocks.iter().find(|(c, _)| c == cid).unwrap();
+            data.clone()
+        };
+
+        let root = Commit::decode(&block(&commit.commit)).unwrap();
+        assert_eq!(root.did, did);
+        assert_eq!(root.rev.to_string(), commit.rev);
+
+        let node = Node::decode(&block(&root.data)).unwrap();
+        let record = Option::from(op.cid.clone()).unwrap();
+        assert_eq!(node.entries[0].key, op.path);
+        assert_eq!(node.entries[0].value, record);
+        block(&record);
+
+        assert_eq!(
+            subscriber.next().await.unwrap().unwrap(),
+            Message::Identity(Identity {
+                did,
+                handle: Some(Handle::from_str("alice.test").unwrap()),
+                seq: 2085474114,
+                time: DateTime::from_str("2024-09-05T06:11:04.870Z").unwrap(),
+            })
+        );
+
+        assert!(matches!(
+            subscriber.next().await.unwrap(),
+            Err(Error::Closed(_))
+        ));
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn invalid_frame() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.send(tungstenite::Message::Binary(vec![0xff]))
+                .await
+                .unwrap();
+            ws.close(None).await.unwrap();
+        });
+
+        let mut subscriber = Subscriber::builder(format!("ws://{addr}"))
+            .connect()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            subscriber.next().await.unwrap(),
+            Err(Error::Frame(_))
+        ));
+
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,986 @@
+//! Identity caching.
+//!
+//! Resolving identities is slow, and most consumers see the same accounts over and over. An
+//! [`IdentityCache`] wraps a [`DidResolver`] and a [`HandleResolver`], caching their results.
+//!
+//! Cached identities should be purged when the account's identity changes. Relays announce this
+//! with `#identity` events, and Jetstream with [`EventKind::Identity`] events:
+//!
+//! ```ignore
+//! if let EventKind::Identity(identity) = &event.kind {
+//!     cache.purge(&identity.did);
+//!
+//!     if let Some(handle) = &identity.handle {
+//!         cache.purge_handle(handle);
+//!     }
+//! }
+//! ```
+//!
+//! [`EventKind::Identity`]: https://docs.rs/atmo_jetstream/latest/atmo_jetstream/enum.EventKind.html
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use atmo_core::{did::DidDoc, AtIdentifier, Did, Handle};
+use lru::LruCache;
+use tokio::time::Instant;
+
+use crate::{DidError, DidResolver, HandleError, HandleResolver, Identity, IdentityError};
+
+/// The default time for which resolved identities are cached.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The default time for which DIDs and handles which do not exist are cached.
+pub const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The default time after expiry for which resolved identities are served while they are
+/// refreshed.
+pub const DEFAULT_STALE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The default maximum number of DIDs and handles cached.
+pub const DEFAULT_CAPACITY: usize = 100_000;
+
+/// A builder for an [`IdentityCache`].
+pub struct IdentityCacheBuilder {
+    ttl: Duration,
+    negative_ttl: Duration,
+    stale_ttl: Duration,
+    capacity: NonZeroUsize,
+}
+
+impl IdentityCacheBuilder {
+    /// Sets the time for which resolved identities are cached.
+    ///
+    /// The default is [`DEFAULT_TTL`].
+    #[inline]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets the time for which DIDs and handles which do not exist are cached.
+    ///
+    /// Other errors, such as network failures, are never cached. The default is
+    /// [`DEFAULT_NEGATIVE_TTL`].
+    #[inline]
+    pub fn negative_ttl(mut self, ttl: Duration) -> Self {
+        self.negative_ttl = ttl;
+        self
+    }
+
+    /// Sets the time after expiry for which cached results are still served.
+    ///
+    /// A stale result is refreshed in the background the first time it is served. The default is
+    /// [`DEFAULT_STALE_TTL`].
+    #[inline]
+    pub fn stale_ttl(mut self, ttl: Duration) -> Self {
+        self.stale_ttl = ttl;
+        self
+    }
+
+    /// Sets the maximum number of DIDs and handles cached.
+    ///
+    /// DIDs and handles are cached separately, and each cache holds up to `capacity` entries.
+    /// The least recently used entries are evicted first. The default is [`DEFAULT_CAPACITY`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    #[inline]
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = NonZeroUsize::new(capacity).expect("capacity must be non-zero");
+        self
+    }
+
+    /// Creates an `IdentityCache` wrapping `dids` and `handles`.
+    pub fn build<R, H>(self, dids: R, handles: H) -> IdentityCache<R, H> {
+        IdentityCache {
+            inner: Arc::new(Inner {
+                dids,
+                handles,
+                ttl: self.ttl,
+                negative_ttl: self.negative_ttl,
+                stale_ttl: self.stale_ttl,
+                did_cache: Mutex::new(Entries::new(LruCache::new(self.capacity))),
+                handle_cache: Mutex::new(Entries::new(HandleCache {
+                    lru: LruCache::new(self.capacity),
+                    by_did: HashMap::new(),
+                })),
+            }),
+        }
+    }
+}
+
+/// A cache of resolved DIDs and handles.
+///
+/// `IdentityCache` implements [`DidResolver`] and [`HandleResolver`], so it can be used in place
+/// of the resolvers it wraps. Cloning an `IdentityCache` produces a handle to the same cache.
+///
+/// Background refreshes are spawned on the current Tokio runtime.
+pub struct IdentityCache<R, H> {
+    inner: Arc<Inner<R, H>>,
+}
+
+struct Inner<R, H> {
+    dids: R,
+    handles: H,
+    ttl: Duration,
+    negative_ttl: Duration,
+    stale_ttl: Duration,
+    did_cache: Mutex<Entries<LruCache<Did, Slot<DidDoc>>, Did>>,
+    handle_cache: Mutex<Entries<HandleCache, String>>,
+}
+
+/// A cache, along with the fetches in flight for its keys.
+struct Entries<C, K> {
+    slots: C,
+    /// The fetches in flight for each key, so that purging a key discards their results.
+    fetches: HashMap<K, Fetches>,
+}
+
+/// The fetches in flight for a key.
+///
+/// Each fetch is given a ticket when it starts. Its result is only stored if the ticket is above
+/// the floor, which is raised when the key is purged or a later fetch stores its result.
+#[derive(Default)]
+struct Fetches {
+    pending: usize,
+    started: u64,
+    floor: u64,
+}
+
+impl<C, K> Entries<C, K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn new(slots: C) -> Self {
+        Entries {
+            slots,
+            fetches: HashMap::new(),
+        }
+    }
+
+    /// Records the start of a fetch of `key`, returning its ticket.
+    fn begin(&mut self, key: &K) -> u64 {
+        let fetches = self.fetches.entry(key.clone()).or_default();
+        fetches.pending += 1;
+        fetches.started += 1;
+        fetches.started
+    }
+
+    /// Records the end of a fetch of `key`, returning whether its result is still current.
+    ///
+    /// If `stored` is true and the result is current, the results of older fetches are discarded.
+    fn end(&mut self, key: &K, ticket: u64, stored: bool) -> bool {
+        let Some(fetches) = self.fetches.get_mut(key) else {
+            return false;
+        };
+
+        let current = ticket > fetches.floor;
+        if current && stored {
+            fetches.floor = ticket;
+        }
+
+        fetches.pending -= 1;
+        if fetches.pending == 0 {
+            self.fetches.remove(key);
+        }
+
+        current
+    }
+
+    /// Discards the results of the fetches of `key` in flight.
+    fn invalidate(&mut self, key: &K) {
+        if let Some(fetches) = self.fetches.get_mut(key) {
+            fetches.floor = fetches.started;
+        }
+    }
+
+    /// Discards the results of all fetches in flight.
+    fn invalidate_all(&mut self) {
+        for fetches in self.fetches.values_mut() {
+            fetches.floor = fetches.started;
+        }
+    }
+}
+
+/// A fetch in flight, which is ended when dropped if its result is not stored.
+struct Fetch<'a, C, K>
+where
+    K: Hash + Eq + Clone,
+{
+    entries: &'a Mutex<Entries<C, K>>,
+    key: K,
+    ticket: u64,
+    done: bool,
+}
+
+impl<'a, C, K> Fetch<'a, C, K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn begin(entries: &'a Mutex<Entries<C, K>>, key: K) -> Self {
+        let ticket = lock(entries).begin(&key);
+
+        Fetch {
+            entries,
+            key,
+            ticket,
+            done: false,
+        }
+    }
+
+    /// Stores the result of the fetch, unless the key was purged after it started.
+    fn store<T>(mut self, value: Result<T, Miss>)
+    where
+        C: Cache<K, T>,
+    {
+        self.done = true;
+
+        let mut entries = lock(self.entries);
+        if !entries.end(&self.key, self.ticket, true) {
+            return;
+        }
+
+        entries.slots.put(
+            self.key.clone(),
+            Slot {
+                value,
+                fetched: Instant::now(),
+                refreshing: false,
+            },
+        );
+    }
+
+    /// Ends a failed fetch, allowing a failed refresh to be retried.
+    fn failed<T>(mut self)
+    where
+        C: Cache<K, T>,
+    {
+        self.done = true;
+
+        let mut entries = lock(self.entries);
+        entries.end(&self.key, self.ticket, false);
+        if let Some(slot) = entries.slots.peek_mut(&self.key) {
+            slot.refreshing = false;
+        }
+    }
+}
+
+impl<C, K> Drop for Fetch<'_, C, K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn drop(&mut self) {
+        if !self.done {
+            lock(self.entries).end(&self.key, self.ticket, false);
+        }
+    }
+}
+
+/// The cached handles, indexed by the DIDs they resolve to.
+struct HandleCache {
+    // Handles are case-insensitive, so they are keyed in lowercase.
+    lru: LruCache<String, Slot<Did>>,
+    /// The cached handles which resolve to each DID, so a DID can be purged without a scan.
+    by_did: HashMap<Did, HashSet<String>>,
+}
+
+impl HandleCache {
+    fn unindex(&mut self, key: &str, slot: &Slot<Did>) {
+        let Ok(did) = &slot.value else {
+            return;
+        };
+
+        if let Some(handles) = self.by_did.get_mut(did) {
+            handles.remove(key);
+            if handles.is_empty() {
+                self.by_did.remove(did);
+            }
+        }
+    }
+}
+
+/// The operations of a cache of slots.
+trait Cache<K, T> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut Slot<T>>;
+    fn peek_mut(&mut self, key: &K) -> Option<&mut Slot<T>>;
+    fn put(&mut self, key: K, slot: Slot<T>);
+    fn pop(&mut self, key: &K);
+}
+
+impl<K, T> Cache<K, T> for LruCache<K, Slot<T>>
+where
+    K: Hash + Eq,
+{
+    #[inline]
+    fn get_mut(&mut self, key: &K) -> Option<&mut Slot<T>> {
+        LruCache::get_mut(self, key)
+    }
+
+    #[inline]
+    fn peek_mut(&mut self, key: &K) -> Option<&mut Slot<T>> {
+        LruCache::peek_mut(self, key)
+    }
+
+    #[inline]
+    fn put(&mut self, key: K, slot: Slot<T>) {
+        LruCache::put(self, key, slot);
+    }
+
+    #[inline]
+    fn pop(&mut self, key: &K) {
+        LruCache::pop(self, key);
+    }
+}
+
+impl Cache<String, Did> for HandleCache {
+    #[inline]
+    fn get_mut(&mut self, key: &String) -> Option<&mut Slot<Did>> {
+        self.lru.get_mut(key)
+    }
+
+    #[inline]
+    fn peek_mut(&mut self, key: &String) -> Option<&mut Slot<Did>> {
+        self.lru.peek_mut(key)
+    }
+
+    fn put(&mut self, key: String, slot: Slot<Did>) {
+        Cache::pop(self, &key);
+
+        if let Ok(did) = &slot.value {
+            self.by_did
+                .entry(did.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+
+        if let Some((evicted_key, evicted)) = self.lru.push(key, slot) {
+            self.unindex(&evicted_key, &evicted);
+        }
+    }
+
+    fn pop(&mut self, key: &String) {
+        if let Some(slot) = self.lru.pop(key) {
+            self.unindex(key, &slot);
+        }
+    }
+}
+
+struct Slot<T> {
+    value: Result<T, Miss>,
+    fetched: Instant,
+    refreshing: bool,
+}
+
+/// A cacheable resolution failure.
+#[derive(Clone, Copy)]
+enum Miss {
+    NotFound,
+    Tombstoned,
+}
+
+enum Lookup<T> {
+    Hit(Result<T, Miss>),
+    /// The entry is stale, and the caller must refresh it.
+    Stale(Result<T, Miss>),
+    Absent,
+}
+
+impl<R, H> Clone for IdentityCache<R, H> {
+    #[inline]
+    fn clone(&self) -> Self {
+        IdentityCache {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl IdentityCache<(), ()> {
+    /// Returns a builder for an `IdentityCache`.
+    #[inline]
+    pub fn builder() -> IdentityCacheBuilder {
+        IdentityCacheBuilder {
+            ttl: DEFAULT_TTL,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            stale_ttl: DEFAULT_STALE_TTL,
+            capacity: NonZeroUsize::new(DEFAULT_CAPACITY).unwrap(),
+        }
+    }
+}
+
+impl<R, H> IdentityCache<R, H>
+where
+    R: DidResolver + Send + Sync + 'static,
+    H: HandleResolver + Send + Sync + 'static,
+{
+    /// Creates an `IdentityCache` with the default configuration, wrapping `dids` and `handles`.
+    #[inline]
+    pub fn new(dids: R, handles: H) -> Self {
+        IdentityCache::builder().build(dids, handles)
+    }
+
+    /// Resolves `id` to a verified [`Identity`], using cached results where possible.
+    ///
+    /// See [`resolve_identity`](crate::resolve_identity).
+    pub async fn resolve_identity(&self, id: &AtIdentifier) -> Result<Identity, IdentityError> {
+        crate::resolve_identity(self, self, id).await
+    }
+
+    /// Removes `did` from the cache, along with any handles which resolved to it.
+    ///
+    /// Lookups in flight when `did` is purged are not cached, including lookups of handles, which
+    /// may resolve to `did`.
+    pub fn purge(&self, did: &Did) {
+        let mut dids = lock(&self.inner.did_cache);
+        dids.slots.pop(did);
+        dids.invalidate(did);
+        drop(dids);
+
+        let mut handles = lock(&self.inner.handle_cache);
+        for handle in handles.slots.by_did.remove(did).unwrap_or_default() {
+            handles.slots.lru.pop(&handle);
+        }
+        handles.invalidate_all();
+    }
+
+    /// Removes `handle` from the cache.
+    ///
+    /// Lookups in flight when `handle` is purged are not cached.
+    pub fn purge_handle(&self, handle: &Handle) {
+        let key = handle.as_str().to_ascii_lowercase();
+
+        let mut handles = lock(&self.inner.handle_cache);
+        Cache::pop(&mut handles.slots, &key);
+        handles.invalidate(&key);
+    }
+}
+
+impl<R, H> DidResolver for IdentityCache<R, H>
+where
+    R: DidResolver + Send + Sync + 'static,
+    H: HandleResolver + Send + Sync + 'static,
+{
+    async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+        let inner = &self.inner;
+
+        let cached = match inner.lookup(&inner.did_cache, did) {
+            Lookup::Hit(cached) => cached,
+            Lookup::Stale(cached) => {
+                let inner = inner.clone();
+                let did = did.clone();
+                tokio::spawn(async move { inner.fetch_did(&did, false).await });
+                cached
+            }
+            Lookup::Absent => return inner.fetch_did(did, false).await,
+        };
+
+        cached.map_err(|miss| match miss {
+            Miss::NotFound => DidError::NotFound,
+            Miss::Tombstoned => DidError::Tombstoned,
+        })
+    }
+
+    async fn refresh(&self, did: &Did) -> Result<DidDoc, DidError> {
+        self.inner.fetch_did(did, true).await
+    }
+}
+
+impl<R, H> HandleResolver for IdentityCache<R, H>
+where
+    R: DidResolver + Send + Sync + 'static,
+    H: HandleResolver + Send + Sync + 'static,
+{
+    async fn resolve(&self, handle: &Handle) -> Result<Did, HandleError> {
+        let inner = &self.inner;
+        let key = handle.as_str().to_ascii_lowercase();
+
+        let cached = match inner.lookup(&inner.handle_cache, &key) {
+            Lookup::Hit(cached) => cached,
+            Lookup::Stale(cached) => {
+                let inner = inner.clone();
+                let handle = handle.clone();
+                tokio::spawn(async move { inner.fetch_handle(&handle).await });
+                cached
+            }
+            Lookup::Absent => return inner.fetch_handle(handle).await,
+        };
+
+        cached.map_err(|_| HandleError::NotFound)
+    }
+}
+
+impl<R, H> Inner<R, H>
+where
+    R: DidResolver,
+    H: HandleResolver,
+{
+    /// Fetches `did` and caches the result. If `refresh` is true, the wrapped resolver's cache is
+    /// bypassed.
+    async fn fetch_did(&self, did: &Did, refresh: bool) -> Result<DidDoc, DidError> {
+        let fetch = Fetch::begin(&self.did_cache, did.clone());
+        let result = if refresh {
+            self.dids.refresh(did).await
+        } else {
+            self.dids.resolve(did).await
+        };
+
+        let value = match &result {
+            Ok(doc) => Ok(doc.clone()),
+            Err(DidError::NotFound) => Err(Miss::NotFound),
+            Err(DidError::Tombstoned) => Err(Miss::Tombstoned),
+            Err(e) => {
+                tracing::debug!(did = did.as_str(), error = %e, "failed to resolve DID");
+                fetch.failed::<DidDoc>();
+                return result;
+            }
+        };
+
+        fetch.store(value);
+        result
+    }
+
+    async fn fetch_handle(&self, handle: &Handle) -> Result<Did, HandleError> {
+        let fetch = Fetch::begin(&self.handle_cache, handle.as_str().to_ascii_lowercase());
+        let result = self.handles.resolve(handle).await;
+
+        let value = match &result {
+            Ok(did) => Ok(did.clone()),
+            Err(HandleError::NotFound) => Err(Miss::NotFound),
+            Err(e) => {
+                tracing::debug!(handle = handle.as_str(), error = %e, "failed to resolve handle");
+                fetch.failed::<Did>();
+                return result;
+            }
+        };
+
+        fetch.store(value);
+        result
+    }
+}
+
+impl<R, H> Inner<R, H> {
+    fn lookup<C, K, T>(&self, entries: &Mutex<Entries<C, K>>, key: &K) -> Lookup<T>
+    where
+        C: Cache<K, T>,
+        T: Clone,
+    {
+        let cache = &mut lock(entries).slots;
+
+        let Some(slot) = cache.get_mut(key) else {
+            return Lookup::Absent;
+        };
+
+        let ttl = if slot.value.is_ok() {
+            self.ttl
+        } else {
+            self.negative_ttl
+        };
+        let age = slot.fetched.elapsed();
+
+        if age < ttl || (age < ttl + self.stale_ttl && slot.refreshing) {
+            Lookup::Hit(slot.value.clone())
+        } else if age < ttl + self.stale_ttl {
+            slot.refreshing = true;
+            Lookup::Stale(slot.value.clone())
+        } else {
+            cache.pop(key);
+            Lookup::Absent
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // The cache is never left in an inconsistent state, so poisoning can be ignored.
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        str::FromStr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use super::*;
+
+    const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+    const OTHER_DID: &str = "did:plc:z72i7hdynmk6r22z27h6tvur";
+
+    /// A resolver backed by a mutable map, which counts its lookups.
+    #[derive(Default)]
+    struct Directory {
+        docs: Mutex<HashMap<String, Result<DidDoc, u16>>>,
+        handles: Mutex<HashMap<String, Did>>,
+        lookups: AtomicUsize,
+        refreshes: AtomicUsize,
+    }
+
+    impl Directory {
+        fn set_doc(&self, did: &str, handle: &str) {
+            let doc = serde_json::from_value(serde_json::json!({
+                "id": did,
+                "alsoKnownAs": [format!("at://{handle}")],
+            }))
+            .unwrap();
+
+            lock(&self.docs).insert(did.into(), Ok(doc));
+            lock(&self.handles).insert(handle.into(), Did::from_str(did).unwrap());
+        }
+
+        fn set_status(&self, did: &str, status: u16) {
+            lock(&self.docs).insert(did.into(), Err(status));
+        }
+
+        fn lookups(&self) -> usize {
+            self.lookups.load(Ordering::SeqCst)
+        }
+
+        fn refreshes(&self) -> usize {
+            self.refreshes.load(Ordering::SeqCst)
+        }
+    }
+
+    impl DidResolver for Arc<Directory> {
+        async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+
+            match lock(&self.docs).get(did.as_str()).cloned() {
+                Some(Ok(doc)) => Ok(doc),
+                Some(Err(410)) => Err(DidError::Tombstoned),
+                Some(Err(status)) => Err(DidError::Status(status.try_into().unwrap())),
+                None => Err(DidError::NotFound),
+            }
+        }
+
+        async fn refresh(&self, did: &Did) -> Result<DidDoc, DidError> {
+            self.refreshes.fetch_add(1, Ordering::SeqCst);
+            DidResolver::resolve(self, did).await
+        }
+    }
+
+    /// A directory whose answers take a second to arrive.
+    struct Slow(Arc<Directory>);
+
+    impl DidResolver for Slow {
+        async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+            let result = DidResolver::resolve(&self.0, did).await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            result
+        }
+    }
+
+    impl HandleResolver for Slow {
+        async fn resolve(&self, handle: &Handle) -> Result<Did, HandleError> {
+            let result = HandleResolver::resolve(&self.0, handle).await;
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            result
+        }
+    }
+
+    impl HandleResolver for Arc<Directory> {
+        async fn resolve(&self, handle: &Handle) -> Result<Did, HandleError> {
+            self.lookups.fetch_add(1, Ordering::SeqCst);
+
+            lock(&self.handles)
+                .get(handle.as_str())
+                .cloned()
+                .ok_or(HandleError::NotFound)
+        }
+    }
+
+    fn cache(dir: &Arc<Directory>) -> IdentityCache<Arc<Directory>, Arc<Directory>> {
+        IdentityCache::builder()
+            .ttl(Duration::from_secs(60))
+            .negative_ttl(Duration::from_secs(10))
+            .stale_ttl(Duration::from_secs(60))
+            .build(dir.clone(), dir.clone())
+    }
+
+    fn did(s: &str) -> Did {
+        Did::from_str(s).unwrap()
+    }
+
+    fn handle(s: &str) -> Handle {
+        Handle::from_str(s).unwrap()
+    }
+
+    /// Lets spawned refreshes run.
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn caches_results() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        let cache = cache(&dir);
+
+        let doc = DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(doc.handle(), Some(handle("alice.test")));
+        DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(dir.lookups(), 1);
+
+        let resolved = HandleResolver::resolve(&cache, &handle("alice.test"))
+            .await
+            .unwrap();
+        assert_eq!(resolved, did(DID));
+        HandleResolver::resolve(&cache, &handle("Alice.test"))
+            .await
+            .unwrap();
+        assert_eq!(dir.lookups(), 2);
+
+        // Past the stale window, entries are fetched again before returning.
+        tokio::time::advance(Duration::from_secs(121)).await;
+        DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(dir.lookups(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refresh_bypasses_cache() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        let cache = cache(&dir);
+
+        DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        dir.set_doc(DID, "bob.test");
+
+        let doc = DidResolver::refresh(&cache, &did(DID)).await.unwrap();
+        assert_eq!(doc.handle(), Some(handle("bob.test")));
+        assert_eq!(dir.lookups(), 2);
+
+        // The wrapped resolver's cache is bypassed too.
+        assert_eq!(dir.refreshes(), 1);
+
+        // The refreshed document replaces the cached one.
+        let doc = DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(doc.handle(), Some(handle("bob.test")));
+        assert_eq!(dir.lookups(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn caches_missing() {
+        let dir = Arc::<Directory>::default();
+        dir.set_status(OTHER_DID, 410);
+        let cache = cache(&dir);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                DidResolver::resolve(&cache, &did(DID)).await,
+                Err(DidError::NotFound)
+            ));
+            assert!(matches!(
+                DidResolver::resolve(&cache, &did(OTHER_DID)).await,
+                Err(DidError::Tombstoned)
+            ));
+        }
+        assert_eq!(dir.lookups(), 2);
+
+        // Negative results expire sooner, and become stale.
+        dir.set_doc(DID, "alice.test");
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert!(DidResolver::resolve(&cache, &did(DID)).await.is_err());
+        settle().await;
+        assert!(DidResolver::resolve(&cache, &did(DID)).await.is_ok());
+        assert_eq!(dir.lookups(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn skips_errors() {
+        let dir = Arc::<Directory>::default();
+        dir.set_status(DID, 500);
+        let cache = cache(&dir);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                DidResolver::resolve(&cache, &did(DID)).await,
+                Err(DidError::Status(_))
+            ));
+        }
+        assert_eq!(dir.lookups(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stale_while_revalidate() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        let cache = cache(&dir);
+
+        DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        dir.set_doc(DID, "bob.test");
+        tokio::time::advance(Duration::from_secs(61)).await;
+
+        // The stale document is served, and refreshed once in the background.
+        for _ in 0..2 {
+            let doc = DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+            assert_eq!(doc.handle(), Some(handle("alice.test")));
+        }
+        settle().await;
+        assert_eq!(dir.lookups(), 2);
+
+        let doc = DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(doc.handle(), Some(handle("bob.test")));
+        assert_eq!(dir.lookups(), 2);
+
+        // A failed refresh keeps the stale document, and is retried.
+        dir.set_status(DID, 500);
+        tokio::time::advance(Duration::from_secs(61)).await;
+        assert!(DidResolver::resolve(&cache, &did(DID)).await.is_ok());
+        settle().await;
+        assert!(DidResolver::resolve(&cache, &did(DID)).await.is_ok());
+        settle().await;
+        assert_eq!(dir.lookups(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bounded_size() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        dir.set_doc(OTHER_DID, "bob.test");
+        let cache = IdentityCache::builder()
+            .capacity(1)
+            .build(dir.clone(), dir.clone());
+
+        for did_str in [DID, OTHER_DID, DID] {
+            DidResolver::resolve(&cache, &did(did_str)).await.unwrap();
+        }
+        assert_eq!(dir.lookups(), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn purge() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        let cache = cache(&dir);
+
+        let id = AtIdentifier::Handle(handle("alice.test"));
+        let identity = cache.resolve_identity(&id).await.unwrap();
+        assert_eq!(identity.did, did(DID));
+        assert_eq!(dir.lookups(), 2);
+
+        // The account changes its handle.
+        lock(&dir.handles).remove("alice.test");
+        dir.set_doc(DID, "bob.test");
+        cache.purge(&did(DID));
+
+        assert!(matches!(
+            cache.resolve_identity(&id).await,
+            Err(IdentityError::Handle(HandleError::NotFound))
+        ));
+        let id = AtIdentifier::Did(did(DID));
+        let identity = cache.resolve_identity(&id).await.unwrap();
+        assert_eq!(identity.handle, Some(handle("bob.test")));
+        assert_eq!(dir.lookups(), 5);
+
+        // A handle may also be purged on its own.
+        cache.purge_handle(&handle("bob.test"));
+        cache.resolve_identity(&id).await.unwrap();
+        assert_eq!(dir.lookups(), 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn purge_in_flight() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        let cache = IdentityCache::builder()
+            .ttl(Duration::from_secs(60))
+            .stale_ttl(Duration::from_secs(60))
+            .build(Slow(dir.clone()), Slow(dir.clone()));
+        let resolve = |cache: &IdentityCache<Slow, Slow>| {
+            let cache = cache.clone();
+            tokio::spawn(async move {
+                let (did, handle) = (did(DID), handle("alice.test"));
+                let (doc, owner) = tokio::join!(
+                    DidResolver::resolve(&cache, &did),
+                    HandleResolver::resolve(&cache, &handle),
+                );
+                (doc.unwrap().handle(), owner.ok())
+            })
+        };
+
+        // The account changes its handle while it is first being resolved.
+        let lookup = resolve(&cache);
+        settle().await;
+        lock(&dir.handles).remove("alice.test");
+        dir.set_doc(DID, "bob.test");
+        cache.purge(&did(DID));
+        let (resolved, owner) = lookup.await.unwrap();
+        assert_eq!(resolved, Some(handle("alice.test")));
+        assert_eq!(owner, Some(did(DID)));
+
+        // The results fetched before the purge were not cached.
+        let (resolved, owner) = resolve(&cache).await.unwrap();
+        assert_eq!(resolved, Some(handle("bob.test")));
+        assert_eq!(owner, None);
+        assert_eq!(dir.lookups(), 4);
+
+        // A background refresh which started before a purge does not overwrite the result of a
+        // later lookup.
+        tokio::time::advance(Duration::from_secs(61)).await;
+        DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        settle().await;
+        dir.set_doc(DID, "carol.test");
+        cache.purge(&did(DID));
+        let doc = DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(doc.handle(), Some(handle("carol.test")));
+        settle().await;
+
+        let doc = DidResolver::resolve(&cache, &did(DID)).await.unwrap();
+        assert_eq!(doc.handle(), Some(handle("carol.test")));
+        assert_eq!(dir.lookups(), 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn purge_index() {
+        let dir = Arc::<Directory>::default();
+        dir.set_doc(DID, "alice.test");
+        dir.set_doc(OTHER_DID, "bob.test");
+        let cache = IdentityCache::builder()
+            .capacity(1)
+            .build(dir.clone(), dir.clone());
+        let indexed = || {
+            let handles = lock(&cache.inner.handle_cache);
+            let mut indexed: Vec<_> = handles
+                .slots
+                .by_did
+                .keys()
+                .map(|d| d.as_str().to_owned())
+                .collect();
+            indexed.sort();
+            indexed
+        };
+
+        HandleResolver::resolve(&cache, &handle("alice.test"))
+            .await
+            .unwrap();
+        assert_eq!(indexed(), [DID]);
+
+        // Evicting a handle removes it from the index.
+        HandleResolver::resolve(&cache, &handle("bob.test"))
+            .await
+            .unwrap();
+        assert_eq!(indexed(), [OTHER_DID]);
+
+        // A handle which now resolves to another DID is not purged with its old DID.
+        cache.purge_handle(&handle("bob.test"));
+        lock(&dir.handles).insert("bob.test".into(), did(DID));
+        HandleResolver::resolve(&cache, &handle("bob.test"))
+            .await
+            .unwrap();
+        assert_eq!(indexed(), [DID]);
+
+        cache.purge(&did(OTHER_DID));
+        assert_eq!(indexed(), [DID]);
+        cache.purge(&did(DID));
+        assert!(indexed().is_empty());
+        assert!(lock(&cache.inner.handle_cache).slots.lru.is_empty());
+    }
+}
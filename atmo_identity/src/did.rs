@@ -0,0 +1,333 @@
+//! DID resolution.
+//!
+//! ATProto supports two DID methods: `did:plc`, whose documents are served by a [PLC directory],
+//! and `did:web`, whose documents are served by the domain named in the DID. See the [DID]
+//! section of the ATProto specification.
+//!
+//! [PLC directory]: https://web.plc.directory/
+//! [DID]: https://atproto.com/specs/did
+
+use std::future::Future;
+
+use atmo_core::{did::DidDoc, Did};
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+use crate::DidError;
+
+/// The default PLC directory.
+pub const DEFAULT_PLC_DIRECTORY: &str = "https://plc.directory";
+
+/// A trait for types which resolve DIDs to DID documents.
+pub trait DidResolver {
+    /// Resolves `did` to its DID document.
+    ///
+    /// Implementations must check that the returned document belongs to `did`.
+    fn resolve(&self, did: &Did) -> impl Future<Output = Result<DidDoc, DidError>> + Send;
+
+    /// Resolves `did` to its DID document, bypassing any cached result.
+    ///
+    /// This is used when a cached document may be out of date, such as after a signing key
+    /// rotation. The default implementation calls [`resolve`](Self::resolve).
+    fn refresh(&self, did: &Did) -> impl Future<Output = Result<DidDoc, DidError>> + Send {
+        self.resolve(did)
+    }
+}
+
+impl<T> DidResolver for &T
+where
+    T: DidResolver + Sync + ?Sized,
+{
+    #[inline]
+    fn resolve(&self, did: &Did) -> impl Future<Output = Result<DidDoc, DidError>> + Send {
+        T::resolve(self, did)
+    }
+
+    #[inline]
+    fn refresh(&self, did: &Did) -> impl Future<Output = Result<DidDoc, DidError>> + Send {
+        T::refresh(self, did)
+    }
+}
+
+/// A builder for an [`HttpDidResolver`].
+pub struct HttpDidResolverBuilder {
+    client: Option<reqwest::Client>,
+    plc_directory: Url,
+}
+
+impl HttpDidResolverBuilder {
+    /// Sets the HTTP client used to fetch DID documents.
+    #[inline]
+    pub fn client(mut self, client: reqwest::Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Sets the base URL of the PLC directory used to resolve `did:plc` DIDs.
+    ///
+    /// The default is [`DEFAULT_PLC_DIRECTORY`].
+    #[inline]
+    pub fn plc_directory(mut self, url: Url) -> Self {
+        self.plc_directory = url;
+        self
+    }
+
+    /// Creates an `HttpDidResolver` with the configured options.
+    pub fn build(self) -> HttpDidResolver {
+        HttpDidResolver {
+            client: self.client.unwrap_or_default(),
+            plc_directory: self.plc_directory,
+        }
+    }
+}
+
+/// A resolver for `did:plc` and `did:web` DIDs.
+#[derive(Clone, Debug)]
+pub struct HttpDidResolver {
+    client: reqwest::Client,
+    plc_directory: Url,
+}
+
+impl HttpDidResolver {
+    /// Creates an `HttpDidResolver` with the default configuration.
+    #[inline]
+    pub fn new() -> Self {
+        HttpDidResolver::builder().build()
+    }
+
+    /// Creates a new [`HttpDidResolverBuilder`] with the default configuration.
+    #[inline]
+    pub fn builder() -> HttpDidResolverBuilder {
+        HttpDidResolverBuilder {
+            client: None,
+            plc_directory: Url::parse(DEFAULT_PLC_DIRECTORY).unwrap(),
+        }
+    }
+
+    fn plc_url(&self, did: &Did) -> Url {
+        let mut url = self.plc_directory.clone();
+        url.path_segments_mut()
+            .expect("PLC directory URL should be a base")
+            .pop_if_empty()
+            .push(did.as_str());
+        url
+    }
+
+    fn web_url(did: &Did) -> Result<Url, DidError> {
+        let ident = did
+            .as_str()
+            .strip_prefix("did:web:")
+            .ok_or(DidError::InvalidDid)?;
+
+        // ATProto only supports hostnames. A percent-encoded port is only allowed for localhost,
+        // for testing.
+        if ident.contains(':') {
+            return Err(DidError::InvalidDid);
+        }
+
+        let host = percent_decode_str(ident)
+            .decode_utf8()
+            .map_err(|_| DidError::InvalidDid)?;
+
+        // Local development servers are commonly served without TLS.
+        let scheme = match host.split_once(':') {
+            Some(("localhost", _)) => "http",
+            Some(_) => return Err(DidError::InvalidDid),
+            None if host == "localhost" => "http",
+            None => "https",
+        };
+
+        let url = Url::parse(&format!("{scheme}://{host}/.well-known/did.json"))
+            .map_err(|_| DidError::InvalidDid)?;
+
+        if url.path() != "/.well-known/did.json" || url.query().is_some() {
+            return Err(DidError::InvalidDid);
+        }
+
+        Ok(url)
+    }
+
+    async fn fetch(&self, did: &Did, url: Url) -> Result<DidDoc, DidError> {
+        tracing::debug!(did = did.as_str(), %url, "resolving DID");
+
+        let resp = self
+            .client
+            .get(url)
+            .header(
+                http::header::ACCEPT,
+                "application/did+ld+json, application/json",
+            )
+            .send()
+            .await?;
+
+        match resp.status() {
+            http::StatusCode::NOT_FOUND => return Err(DidError::NotFound),
+            http::StatusCode::GONE => return Err(DidError::Tombstoned),
+            status if !status.is_success() => return Err(DidError::Status(status)),
+            _ => (),
+        }
+
+        let bytes = resp.bytes().await?;
+        let doc: DidDoc = serde_json::from_slice(&bytes).map_err(DidError::Malformed)?;
+
+        if doc.id != *did {
+            return Err(DidError::DidMismatch(doc.id));
+        }
+
+        Ok(doc)
+    }
+}
+
+impl Default for HttpDidResolver {
+    #[inline]
+    fn default() -> Self {
+        HttpDidResolver::new()
+    }
+}
+
+impl DidResolver for HttpDidResolver {
+    async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+        let url = match did.method() {
+            "plc" => self.plc_url(did),
+            "web" => HttpDidResolver::web_url(did)?,
+            method => return Err(DidError::UnsupportedMethod(method.into())),
+        };
+
+        self.fetch(did, url).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::test::{serve, serve_with, Route};
+
+    use super::*;
+
+    const PLC_DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+
+    fn doc(did: &str) -> String {
+        serde_json::json!({
+            "@context": ["https://www.w3.org/ns/did/v1"],
+            "id": did,
+            "alsoKnownAs": ["at://atproto.com"],
+            "verificationMethod": [],
+            "service": [{
+                "id": "#atproto_pds",
+                "type": "AtprotoPersonalDataServer",
+                "serviceEndpoint": "https://pds.example.com",
+            }],
+        })
+        .to_string()
+    }
+
+    async fn plc_resolver(routes: Vec<Route>) -> HttpDidResolver {
+        let addr = serve(routes).await;
+
+        HttpDidResolver::builder()
+            .plc_directory(Url::parse(&format!("http://{addr}")).unwrap())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn resolve_plc() {
+        let resolver =
+            plc_resolver(vec![Route::new(format!("/{PLC_DID}"), 200, doc(PLC_DID))]).await;
+
+        let did = Did::from_str(PLC_DID).unwrap();
+        let doc = resolver.resolve(&did).await.unwrap();
+
+        assert_eq!(doc.id, did);
+        assert_eq!(
+            doc.pds_service_url().unwrap().as_str(),
+            "https://pds.example.com/"
+        );
+    }
+
+    #[tokio::test]
+    async fn resolve_plc_errors() {
+        let tombstoned = "did:plc:z72i7hdynmk6r22z27h6tvur";
+        let mismatched = "did:plc:yk4dd2qkboz2yv6tpubpc6co";
+        let malformed = "did:plc:vwzwgnygau7ed7b7wt5ux7y2";
+
+        let resolver = plc_resolver(vec![
+            Route::new(format!("/{tombstoned}"), 410, ""),
+            Route::new(format!("/{mismatched}"), 200, doc(PLC_DID)),
+            Route::new(format!("/{malformed}"), 200, "{}"),
+        ])
+        .await;
+
+        let resolve = |did: &str| {
+            let did = Did::from_str(did).unwrap();
+            let resolver = &resolver;
+            async move { resolver.resolve(&did).await }
+        };
+
+        assert!(matches!(resolve(PLC_DID).await, Err(DidError::NotFound)));
+        assert!(matches!(
+            resolve(tombstoned).await,
+            Err(DidError::Tombstoned)
+        ));
+        assert!(matches!(
+            resolve(mismatched).await,
+            Err(DidError::DidMismatch(_))
+        ));
+        assert!(matches!(
+            resolve(malformed).await,
+            Err(DidError::Malformed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolve_web() {
+        let addr = serve_with(|addr| {
+            let did = format!("did:web:localhost%3A{}", addr.port());
+            vec![Route::new("/.well-known/did.json", 200, doc(&did))]
+        })
+        .await;
+
+        let did = Did::from_str(&format!("did:web:localhost%3A{}", addr.port())).unwrap();
+        let doc = HttpDidResolver::new().resolve(&did).await.unwrap();
+
+        assert_eq!(doc.id, did);
+    }
+
+    #[test]
+    fn web_urls() {
+        let url = |did: &str| HttpDidResolver::web_url(&Did::from_str(did).unwrap());
+
+        assert_eq!(
+            url("did:web:example.com").unwrap().as_str(),
+            "https://example.com/.well-known/did.json"
+        );
+        assert_eq!(
+            url("did:web:localhost%3A8080").unwrap().as_str(),
+            "http://localhost:8080/.well-known/did.json"
+        );
+
+        assert!(matches!(
+            url("did:web:example.com:user:alice"),
+            Err(DidError::InvalidDid)
+        ));
+        assert!(matches!(
+            url("did:web:example.com%2Fpath"),
+            Err(DidError::InvalidDid)
+        ));
+        assert!(matches!(
+            url("did:web:example.com%3A8443"),
+            Err(DidError::InvalidDid)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unsupported_method() {
+        let did =
+            Did::from_str("did:key:zQ3shqwJEJyMBsBXCWyCBpUBMqxcon9oHB7mCvx4sSpMdLJwc").unwrap();
+
+        assert!(matches!(
+            HttpDidResolver::new().resolve(&did).await,
+            Err(DidError::UnsupportedMethod(m)) if m == "key"
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+use std::fmt;
+
+use atmo_core::{crypto::CryptoError, Did};
+
+/// An error produced while resolving a DID.
+#[derive(Debug)]
+pub enum DidError {
+    /// The resolved document belongs to a different DID.
+    DidMismatch(Did),
+    /// An error occurred in the underlying HTTP request.
+    Http(reqwest::Error),
+    /// The DID is not resolvable, e.g. a `did:web` with a path.
+    InvalidDid,
+    /// The DID document could not be parsed.
+    Malformed(serde_json::Error),
+    /// The DID does not exist.
+    NotFound,
+    /// The server returned an unexpected HTTP status.
+    Status(http::StatusCode),
+    /// The DID has been permanently deactivated.
+    Tombstoned,
+    /// The DID method is not supported.
+    UnsupportedMethod(String),
+}
+
+impl From<reqwest::Error> for DidError {
+    #[inline]
+    fn from(e: reqwest::Error) -> Self {
+        DidError::Http(e)
+    }
+}
+
+impl fmt::Display for DidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DidError::DidMismatch(did) => write!(f, "DID document belongs to {}", did.as_str()),
+            DidError::Http(e) => fmt::Display::fmt(e, f),
+            DidError::InvalidDid => f.write_str("DID cannot be resolved"),
+            DidError::Malformed(e) => write!(f, "malformed DID document: {e}"),
+            DidError::NotFound => f.write_str("DID not found"),
+            DidError::Status(status) => write!(f, "unexpected HTTP status: {status}"),
+            DidError::Tombstoned => f.write_str("DID has been deactivated"),
+            DidError::UnsupportedMethod(method) => write!(f, "unsupported DID method: {method}"),
+        }
+    }
+}
+
+impl std::error::Error for DidError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DidError::Http(e) => Some(e),
+            DidError::Malformed(e) => Some(e),
+            DidError::DidMismatch(_)
+            | DidError::InvalidDid
+            | DidError::NotFound
+            | DidError::Status(_)
+            | DidError::Tombstoned
+            | DidError::UnsupportedMethod(_) => None,
+        }
+    }
+}
+
+/// An error produced while resolving a handle.
+#[derive(Debug)]
+pub enum HandleError {
+    /// The handle has DNS records for more than one DID.
+    Ambiguous,
+    /// An error occurred in the DNS lookup.
+    Dns(Box<dyn std::error::Error + Send + Sync>),
+    /// An error occurred in the HTTPS request.
+    Http(Box<dyn std::error::Error + Send + Sync>),
+    /// The handle resolved to an invalid DID.
+    InvalidDid(String),
+    /// The handle is not registered with either method.
+    NotFound,
+}
+
+impl fmt::Display for HandleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandleError::Ambiguous => f.write_str("handle resolves to multiple DIDs"),
+            HandleError::Dns(e) => write!(f, "DNS lookup failed: {e}"),
+            HandleError::Http(e) => write!(f, "HTTPS lookup failed: {e}"),
+            HandleError::InvalidDid(did) => write!(f, "handle resolved to invalid DID: {did:?}"),
+            HandleError::NotFound => f.write_str("handle not found"),
+        }
+    }
+}
+
+impl std::error::Error for HandleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HandleError::Dns(e) | HandleError::Http(e) => Some(&**e),
+            HandleError::Ambiguous | HandleError::InvalidDid(_) | HandleError::NotFound => None,
+        }
+    }
+}
+
+/// An error produced while resolving an identity.
+#[derive(Debug)]
+pub enum IdentityError {
+    /// The DID could not be resolved.
+    Did(DidError),
+    /// The handle could not be resolved.
+    Handle(HandleError),
+    /// The handle resolved to a DID whose document does not claim the handle.
+    HandleMismatch,
+}
+
+impl From<DidError> for IdentityError {
+    #[inline]
+    fn from(e: DidError) -> Self {
+        IdentityError::Did(e)
+    }
+}
+
+impl From<HandleError> for IdentityError {
+    #[inline]
+    fn from(e: HandleError) -> Self {
+        IdentityError::Handle(e)
+    }
+}
+
+impl fmt::Display for IdentityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdentityError::Did(e) => fmt::Display::fmt(e, f),
+            IdentityError::Handle(e) => fmt::Display::fmt(e, f),
+            IdentityError::HandleMismatch => f.write_str("DID document does not claim handle"),
+        }
+    }
+}
+
+impl std::error::Error for IdentityError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IdentityError::Did(e) => Some(e),
+            IdentityError::Handle(e) => Some(e),
+            IdentityError::HandleMismatch => None,
+        }
+    }
+}
+
+/// An error produced while verifying a service auth token.
+#[derive(Debug)]
+pub enum ServiceAuthError {
+    /// The token is intended for a different service.
+    AudienceMismatch(String),
+    /// The issuer's DID could not be resolved.
+    Did(DidError),
+    /// The token has expired.
+    Expired,
+    /// The token's lifetime exceeds the verifier's maximum.
+    LifetimeTooLong,
+    /// The token is not a valid JWT.
+    Malformed,
+    /// The token is restricted to a different method, or is not restricted to any method.
+    MethodMismatch(Option<String>),
+    /// The issuer's DID document has no signing key.
+    NoSigningKey,
+    /// The token was issued in the future.
+    NotYetValid,
+    /// The signing key could not be parsed, or the signature is invalid.
+    Signature(CryptoError),
+    /// The token's algorithm does not match the issuer's signing key.
+    UnsupportedAlgorithm(String),
+}
+
+impl From<DidError> for ServiceAuthError {
+    #[inline]
+    fn from(e: DidError) -> Self {
+        ServiceAuthError::Did(e)
+    }
+}
+
+impl fmt::Display for ServiceAuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServiceAuthError::AudienceMismatch(aud) => write!(f, "token is intended for {aud}"),
+            ServiceAuthError::Did(e) => fmt::Display::fmt(e, f),
+            ServiceAuthError::Expired => f.write_str("token has expired"),
+            ServiceAuthError::LifetimeTooLong => f.write_str("token lifetime is too long"),
+            ServiceAuthError::Malformed => f.write_str("malformed token"),
+            ServiceAuthError::MethodMismatch(Some(lxm)) => {
+                write!(f, "token is restricted to {lxm}")
+            }
+            ServiceAuthError::MethodMismatch(None) => {
+                f.write_str("token is not restricted to a method")
+            }
+            ServiceAuthError::NoSigningKey => f.write_str("DID document has no signing key"),
+            ServiceAuthError::NotYetValid => f.write_str("token was issued in the future"),
+            ServiceAuthError::Signature(e) => fmt::Display::fmt(e, f),
+            ServiceAuthError::UnsupportedAlgorithm(alg) => {
+                write!(f, "unsupported token algorithm: {alg}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ServiceAuthError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ServiceAuthError::Did(e) => Some(e),
+            ServiceAuthError::Signature(e) => Some(e),
+            ServiceAuthError::AudienceMismatch(_)
+            | ServiceAuthError::Expired
+            | ServiceAuthError::LifetimeTooLong
+            | ServiceAuthError::Malformed
+            | ServiceAuthError::MethodMismatch(_)
+            | ServiceAuthError::NoSigningKey
+            | ServiceAuthError::NotYetValid
+            | ServiceAuthError::UnsupportedAlgorithm(_) => None,
+        }
+    }
+}
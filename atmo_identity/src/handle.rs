@@ -0,0 +1,546 @@
+//! Handle resolution.
+//!
+//! A handle is resolved to a DID either via a DNS `TXT` record at `_atproto.<handle>`, or via an
+//! HTTPS request to `https://<handle>/.well-known/atproto-did`. A handle is only valid if the DID
+//! document of the resolved DID claims the handle in turn. See the [Handle] section of the ATProto
+//! specification.
+//!
+//! [Handle]: https://atproto.com/specs/handle
+
+use std::{error::Error as StdError, future::Future, str::FromStr, time::Duration};
+
+use atmo_core::{did::DidDoc, AtIdentifier, Did, Handle};
+use url::Url;
+
+use crate::{DidResolver, HandleError, IdentityError};
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+/// The maximum size of a `/.well-known/atproto-did` response read by [`reqwest::Client`].
+///
+/// DIDs are at most 2 KiB, so this leaves room for whitespace.
+const MAX_BODY_SIZE: usize = 4096;
+
+/// The timeout for `/.well-known/atproto-did` requests sent by [`reqwest::Client`].
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A trait for types which resolve handles to DIDs.
+///
+/// Implementations only perform the forward lookup; use [`resolve_identity`] to check that the
+/// resolved DID claims the handle.
+pub trait HandleResolver {
+    /// Resolves `handle` to a DID.
+    fn resolve(&self, handle: &Handle) -> impl Future<Output = Result<Did, HandleError>> + Send;
+}
+
+impl<T> HandleResolver for &T
+where
+    T: HandleResolver + Sync + ?Sized,
+{
+    #[inline]
+    fn resolve(&self, handle: &Handle) -> impl Future<Output = Result<Did, HandleError>> + Send {
+        T::resolve(self, handle)
+    }
+}
+
+/// A trait for DNS clients which can look up `TXT` records.
+pub trait DnsTxtResolver {
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Returns the `TXT` records for `name`, or an empty list if there are none.
+    fn txt_lookup(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<Vec<String>, Self::Error>> + Send;
+}
+
+/// A trait for HTTP clients which can fetch a text document.
+///
+/// The implementation for [`reqwest::Client`] times out after 10 seconds, and treats bodies larger
+/// than 4 KiB as missing, since they cannot hold a DID.
+pub trait HttpTextClient {
+    type Error: StdError + Send + Sync + 'static;
+
+    /// Fetches `url`, returning the response body, or `None` if the server returned an error
+    /// status.
+    fn get_text(
+        &self,
+        url: Url,
+    ) -> impl Future<Output = Result<Option<String>, Self::Error>> + Send;
+}
+
+impl HttpTextClient for reqwest::Client {
+    type Error = reqwest::Error;
+
+    async fn get_text(&self, url: Url) -> Result<Option<String>, Self::Error> {
+        let mut resp = self.get(url).timeout(HTTP_TIMEOUT).send().await?;
+
+        if !resp.status().is_success() {
+            return Ok(None);
+        }
+
+        // The server is controlled by whoever claims the handle, so the body is read up to a
+        // limit rather than buffered whole.
+        let mut body = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            if body.len() + chunk.len() > MAX_BODY_SIZE {
+                return Ok(None);
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+    }
+}
+
+#[cfg(feature = "hickory-dns")]
+impl DnsTxtResolver for hickory_resolver::TokioAsyncResolver {
+    type Error = hickory_resolver::error::ResolveError;
+
+    async fn txt_lookup(&self, name: &str) -> Result<Vec<String>, Self::Error> {
+        use hickory_resolver::error::ResolveErrorKind;
+
+        let lookup = match hickory_resolver::TokioAsyncResolver::txt_lookup(self, name).await {
+            Ok(lookup) => lookup,
+            Err(e) if matches!(e.kind(), ResolveErrorKind::NoRecordsFound { .. }) => {
+                return Ok(Vec::new())
+            }
+            Err(e) => return Err(e),
+        };
+
+        let records = lookup
+            .iter()
+            .map(|txt| {
+                txt.txt_data()
+                    .iter()
+                    .map(|data| String::from_utf8_lossy(data))
+                    .collect()
+            })
+            .collect();
+
+        Ok(records)
+    }
+}
+
+/// A handle resolver using DNS and HTTPS, as described by the ATProto specification.
+///
+/// The DNS and HTTP clients may be replaced, e.g. with in-memory implementations for testing.
+#[derive(Clone, Debug)]
+pub struct AtprotoHandleResolver<D, H> {
+    dns: D,
+    http: H,
+}
+
+#[cfg(feature = "hickory-dns")]
+impl AtprotoHandleResolver<hickory_resolver::TokioAsyncResolver, reqwest::Client> {
+    /// Creates an `AtprotoHandleResolver` using the system DNS configuration.
+    ///
+    /// If the system configuration cannot be read, the default configuration of
+    /// [`hickory_resolver`] is used instead.
+    pub fn new() -> Self {
+        use hickory_resolver::{
+            config::{ResolverConfig, ResolverOpts},
+            TokioAsyncResolver,
+        };
+
+        let dns = TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "failed to read system DNS configuration");
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+        });
+
+        AtprotoHandleResolver::with_clients(dns, reqwest::Client::new())
+    }
+}
+
+#[cfg(feature = "hickory-dns")]
+impl Default for AtprotoHandleResolver<hickory_resolver::TokioAsyncResolver, reqwest::Client> {
+    #[inline]
+    fn default() -> Self {
+        AtprotoHandleResolver::new()
+    }
+}
+
+impl<D, H> AtprotoHandleResolver<D, H>
+where
+    D: DnsTxtResolver + Sync,
+    H: HttpTextClient + Sync,
+{
+    /// Creates an `AtprotoHandleResolver` with the given DNS and HTTP clients.
+    #[inline]
+    pub fn with_clients(dns: D, http: H) -> Self {
+        AtprotoHandleResolver { dns, http }
+    }
+
+    async fn resolve_dns(&self, handle: &Handle) -> Result<Option<Did>, HandleError> {
+        let records = self
+            .dns
+            .txt_lookup(&format!("_atproto.{}", handle.as_str()))
+            .await
+            .map_err(|e| HandleError::Dns(Box::new(e) as BoxError))?;
+
+        let mut dids = records.iter().filter_map(|txt| txt.strip_prefix("did="));
+
+        let Some(did) = dids.next() else {
+            return Ok(None);
+        };
+
+        // Multiple records are ambiguous, so the lookup fails.
+        if dids.next().is_some() {
+            return Err(HandleError::Ambiguous);
+        }
+
+        Did::from_str(did)
+            .map(Some)
+            .map_err(|_| HandleError::InvalidDid(did.into()))
+    }
+
+    async fn resolve_http(&self, handle: &Handle) -> Result<Option<Did>, HandleError> {
+        let url = Url::parse(&format!(
+            "https://{}/.well-known/atproto-did",
+            handle.as_str()
+        ))
+        .map_err(|e| HandleError::Http(Box::new(e) as BoxError))?;
+
+        let Some(body) = self
+            .http
+            .get_text(url)
+            .await
+            .map_err(|e| HandleError::Http(Box::new(e) as BoxError))?
+        else {
+            return Ok(None);
+        };
+
+        let did = body.trim();
+
+        Did::from_str(did)
+            .map(Some)
+            .map_err(|_| HandleError::InvalidDid(did.into()))
+    }
+}
+
+impl<D, H> HandleResolver for AtprotoHandleResolver<D, H>
+where
+    D: DnsTxtResolver + Sync,
+    H: HttpTextClient + Sync,
+{
+    async fn resolve(&self, handle: &Handle) -> Result<Did, HandleError> {
+        // DNS takes precedence, but a failed DNS lookup doesn't prevent HTTPS resolution.
+        let dns_err = match self.resolve_dns(handle).await {
+            Ok(Some(did)) => return Ok(did),
+            Ok(None) => None,
+            Err(e) => Some(e),
+        };
+
+        match self.resolve_http(handle).await {
+            Ok(Some(did)) => Ok(did),
+            Ok(None) => Err(dns_err.unwrap_or(HandleError::NotFound)),
+            Err(e) => Err(dns_err.unwrap_or(e)),
+        }
+    }
+}
+
+/// A verified identity.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Identity {
+    /// The account's DID.
+    pub did: Did,
+    /// The account's handle, if it has been verified in both directions.
+    pub handle: Option<Handle>,
+    /// The account's DID document.
+    pub doc: DidDoc,
+}
+
+/// Resolves `id` to a verified [`Identity`].
+///
+/// If `id` is a handle, it is resolved to a DID whose document must claim the handle; otherwise
+/// resolution fails. If `id` is a DID, the handle claimed by its document is resolved, and only
+/// included in the result if it resolves back to the DID.
+pub async fn resolve_identity<R, H>(
+    dids: &R,
+    handles: &H,
+    id: &AtIdentifier,
+) -> Result<Identity, IdentityError>
+where
+    R: DidResolver,
+    H: HandleResolver,
+{
+    match id {
+        AtIdentifier::Handle(handle) => {
+            let did = handles.resolve(handle).await?;
+            let doc = dids.resolve(&did).await?;
+
+            if !claims_handle(&doc, handle) {
+                return Err(IdentityError::HandleMismatch);
+            }
+
+            Ok(Identity {
+                did,
+                handle: Some(handle.clone()),
+                doc,
+            })
+        }
+
+        AtIdentifier::Did(did) => {
+            let doc = dids.resolve(did).await?;
+
+            let handle = match doc.handle() {
+                Some(handle) => match handles.resolve(&handle).await {
+                    Ok(resolved) if resolved == *did => Some(handle),
+                    Ok(_) => None,
+                    Err(e) => {
+                        tracing::debug!(did = did.as_str(), error = %e, "handle did not resolve");
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            Ok(Identity {
+                did: did.clone(),
+                handle,
+                doc,
+            })
+        }
+    }
+}
+
+fn claims_handle(doc: &DidDoc, handle: &Handle) -> bool {
+    // Handles are case-insensitive.
+    doc.handle()
+        .is_some_and(|claimed| claimed.as_str().eq_ignore_ascii_case(handle.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fmt};
+
+    use crate::{test::serve, test::Route, DidError};
+
+    use super::*;
+
+    const DID: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+    const OTHER_DID: &str = "did:plc:z72i7hdynmk6r22z27h6tvur";
+
+    #[derive(Debug)]
+    struct Unreachable;
+
+    impl fmt::Display for Unreachable {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("unreachable")
+        }
+    }
+
+    impl StdError for Unreachable {}
+
+    /// DNS records by name. Names missing from the map fail to resolve.
+    #[derive(Default)]
+    struct Dns(HashMap<String, Vec<String>>);
+
+    impl Dns {
+        fn with(mut self, name: &str, records: &[&str]) -> Self {
+            self.0
+                .insert(name.into(), records.iter().map(|r| r.to_string()).collect());
+            self
+        }
+    }
+
+    impl DnsTxtResolver for Dns {
+        type Error = Unreachable;
+
+        async fn txt_lookup(&self, name: &str) -> Result<Vec<String>, Unreachable> {
+            self.0.get(name).cloned().ok_or(Unreachable)
+        }
+    }
+
+    /// Documents by URL. URLs missing from the map return an error status.
+    #[derive(Default)]
+    struct Http(HashMap<String, String>);
+
+    impl Http {
+        fn with(mut self, url: &str, body: &str) -> Self {
+            self.0.insert(url.into(), body.into());
+            self
+        }
+    }
+
+    impl HttpTextClient for Http {
+        type Error = Unreachable;
+
+        async fn get_text(&self, url: Url) -> Result<Option<String>, Unreachable> {
+            Ok(self.0.get(url.as_str()).cloned())
+        }
+    }
+
+    struct Docs(Vec<DidDoc>);
+
+    impl DidResolver for Docs {
+        async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+            self.0
+                .iter()
+                .find(|doc| doc.id == *did)
+                .cloned()
+                .ok_or(DidError::NotFound)
+        }
+    }
+
+    fn doc(did: &str, handle: &str) -> DidDoc {
+        serde_json::from_value(serde_json::json!({
+            "id": did,
+            "alsoKnownAs": [format!("at://{handle}")],
+        }))
+        .unwrap()
+    }
+
+    fn handle(s: &str) -> Handle {
+        Handle::from_str(s).unwrap()
+    }
+
+    fn did(s: &str) -> Did {
+        Did::from_str(s).unwrap()
+    }
+
+    #[tokio::test]
+    async fn resolve_dns() {
+        let dns = Dns::default()
+            .with(
+                "_atproto.alice.test",
+                &["v=spf1 -all", &format!("did={DID}")],
+            )
+            .with(
+                "_atproto.bob.test",
+                &[&format!("did={DID}"), &format!("did={OTHER_DID}")],
+            )
+            .with("_atproto.carol.test", &["did=not-a-did"]);
+        let resolver = AtprotoHandleResolver::with_clients(dns, Http::default());
+
+        assert_eq!(
+            resolver.resolve(&handle("alice.test")).await.unwrap(),
+            did(DID)
+        );
+        assert!(matches!(
+            resolver.resolve(&handle("bob.test")).await,
+            Err(HandleError::Ambiguous)
+        ));
+        assert!(matches!(
+            resolver.resolve(&handle("carol.test")).await,
+            Err(HandleError::InvalidDid(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolve_http() {
+        let dns = Dns::default().with("_atproto.alice.test", &[]);
+        let http = Http::default()
+            .with(
+                "https://alice.test/.well-known/atproto-did",
+                &format!("{DID}\n"),
+            )
+            .with("https://bob.test/.well-known/atproto-did", DID);
+        let resolver = AtprotoHandleResolver::with_clients(dns, http);
+
+        assert_eq!(
+            resolver.resolve(&handle("alice.test")).await.unwrap(),
+            did(DID)
+        );
+
+        // A DNS failure falls back to HTTPS.
+        assert_eq!(
+            resolver.resolve(&handle("bob.test")).await.unwrap(),
+            did(DID)
+        );
+
+        // If both fail, the DNS error is reported.
+        assert!(matches!(
+            resolver.resolve(&handle("carol.test")).await,
+            Err(HandleError::Dns(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn resolve_not_found() {
+        let dns = Dns::default().with("_atproto.alice.test", &["v=spf1 -all"]);
+        let resolver = AtprotoHandleResolver::with_clients(dns, Http::default());
+
+        assert!(matches!(
+            resolver.resolve(&handle("alice.test")).await,
+            Err(HandleError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn reqwest_client() {
+        let addr = serve(vec![
+            Route::new("/.well-known/atproto-did", 200, DID),
+            Route::new("/large", 200, " ".repeat(MAX_BODY_SIZE + 1)),
+        ])
+        .await;
+        let client = reqwest::Client::new();
+
+        let url = Url::parse(&format!("http://{addr}/.well-known/atproto-did")).unwrap();
+        assert_eq!(client.get_text(url).await.unwrap().as_deref(), Some(DID));
+
+        let url = Url::parse(&format!("http://{addr}/missing")).unwrap();
+        assert_eq!(client.get_text(url).await.unwrap(), None);
+
+        let url = Url::parse(&format!("http://{addr}/large")).unwrap();
+        assert_eq!(client.get_text(url).await.unwrap(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reqwest_timeout() {
+        // The server accepts connections, but never responds.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let url = Url::parse(&format!("http://{addr}/.well-known/atproto-did")).unwrap();
+        let err = reqwest::Client::new().get_text(url).await.unwrap_err();
+        assert!(err.is_timeout());
+    }
+
+    #[tokio::test]
+    async fn identity_from_handle() {
+        let dns = Dns::default()
+            .with("_atproto.alice.test", &[&format!("did={DID}")])
+            .with("_atproto.mallory.test", &[&format!("did={DID}")]);
+        let handles = AtprotoHandleResolver::with_clients(dns, Http::default());
+        let dids = Docs(vec![doc(DID, "Alice.test")]);
+
+        let id = AtIdentifier::Handle(handle("alice.test"));
+        let identity = resolve_identity(&dids, &handles, &id).await.unwrap();
+        assert_eq!(identity.did, did(DID));
+        assert_eq!(identity.handle, Some(handle("alice.test")));
+        assert_eq!(identity.doc, doc(DID, "Alice.test"));
+
+        // The DID document doesn't claim this handle.
+        let id = AtIdentifier::Handle(handle("mallory.test"));
+        assert!(matches!(
+            resolve_identity(&dids, &handles, &id).await,
+            Err(IdentityError::HandleMismatch)
+        ));
+    }
+
+    #[tokio::test]
+    async fn identity_from_did() {
+        let dns = Dns::default()
+            .with("_atproto.alice.test", &[&format!("did={DID}")])
+            .with("_atproto.bob.test", &[&format!("did={DID}")]);
+        let handles = AtprotoHandleResolver::with_clients(dns, Http::default());
+        let dids = Docs(vec![doc(DID, "alice.test"), doc(OTHER_DID, "bob.test")]);
+
+        let id = AtIdentifier::Did(did(DID));
+        let identity = resolve_identity(&dids, &handles, &id).await.unwrap();
+        assert_eq!(identity.handle, Some(handle("alice.test")));
+
+        // The handle points to a different DID, so it is not verified.
+        let id = AtIdentifier::Did(did(OTHER_DID));
+        let identity = resolve_identity(&dids, &handles, &id).await.unwrap();
+        assert_eq!(identity.did, did(OTHER_DID));
+        assert_eq!(identity.handle, None);
+
+        let id = AtIdentifier::Did(did("did:plc:aaaaaaaaaaaaaaaaaaaaaaaa"));
+        assert!(matches!(
+            resolve_identity(&dids, &handles, &id).await,
+            Err(IdentityError::Did(DidError::NotFound))
+        ));
+    }
+}
@@ -0,0 +1,24 @@
+//! ATProto identity resolution.
+//!
+//! This crate resolves [DIDs] to their DID documents and [handles] to DIDs, and verifies that the
+//! two agree. It also mints and verifies the JWTs used for [service auth], which depend on the
+//! issuer's DID document. See the [Identity] section of the ATProto specification.
+//!
+//! [DIDs]: https://atproto.com/specs/did
+//! [handles]: https://atproto.com/specs/handle
+//! [service auth]: service_auth
+//! [Identity]: https://atproto.com/specs/identity
+
+pub mod cache;
+pub mod did;
+mod error;
+pub mod handle;
+pub mod service_auth;
+#[cfg(test)]
+mod test;
+
+pub use cache::IdentityCache;
+pub use did::{DidResolver, HttpDidResolver};
+pub use error::{DidError, HandleError, IdentityError, ServiceAuthError};
+pub use handle::{resolve_identity, AtprotoHandleResolver, HandleResolver, Identity};
+pub use service_auth::{ServiceAuthClaims, ServiceAuthVerifier};
@@ -0,0 +1,583 @@
+//! Inter-service authentication.
+//!
+//! Services such as feed generators and labelers authenticate requests with short-lived JWTs
+//! signed by the caller's repo signing key. The token names the caller (`iss`), the service it is
+//! intended for (`aud`) and, usually, the single XRPC method it may be used for (`lxm`). See the
+//! [Service Auth] section of the ATProto specification.
+//!
+//! [Service Auth]: https://atproto.com/specs/xrpc#inter-service-authentication-jwt
+
+use std::{
+    num::NonZeroUsize,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use atmo_core::{
+    crypto::{Curve, Signer},
+    did::DidDoc,
+    xrpc::Request,
+    Did,
+};
+use data_encoding::BASE64URL_NOPAD;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::time::Instant;
+
+use crate::{DidResolver, ServiceAuthError};
+
+/// The default lifetime of a service auth token, in seconds.
+pub const DEFAULT_LIFETIME: i64 = 60;
+
+/// The default maximum lifetime of a service auth token accepted by a [`ServiceAuthVerifier`], in
+/// seconds.
+pub const DEFAULT_MAX_LIFETIME: i64 = 60 * 60;
+
+/// The default minimum time between refreshes of an issuer's DID document by a
+/// [`ServiceAuthVerifier`].
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How far in the future a token's `iat` may be, in seconds, to allow for clock skew.
+const CLOCK_SKEW: i64 = 30;
+
+/// The number of issuers whose last refresh is remembered.
+const REFRESH_CAPACITY: usize = 10_000;
+
+/// The claims of a service auth token.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ServiceAuthClaims {
+    /// The DID of the caller.
+    ///
+    /// This may have a fragment identifying a service of the caller, such as
+    /// `did:web:labeler.example.com#atproto_labeler`.
+    pub iss: String,
+    /// The DID of the service, optionally with a fragment.
+    pub aud: String,
+    /// When the token expires, in seconds since the Unix epoch.
+    pub exp: i64,
+    /// When the token was issued, in seconds since the Unix epoch.
+    pub iat: i64,
+    /// The NSID of the method the token may be used for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lxm: Option<String>,
+    /// A unique identifier for the token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+impl ServiceAuthClaims {
+    /// Creates claims for a token from `iss` to `aud`, valid for [`DEFAULT_LIFETIME`] seconds.
+    pub fn new(iss: impl Into<String>, aud: impl Into<String>) -> Self {
+        let now = jiff::Timestamp::now().as_second();
+
+        ServiceAuthClaims {
+            iss: iss.into(),
+            aud: aud.into(),
+            exp: now + DEFAULT_LIFETIME,
+            iat: now,
+            lxm: None,
+            jti: None,
+        }
+    }
+
+    /// Restricts the token to the XRPC method `R`.
+    #[inline]
+    pub fn for_request<R>(mut self) -> Self
+    where
+        R: Request,
+    {
+        self.lxm = Some(R::nsid().into());
+        self
+    }
+
+    /// Sets how long the token is valid for, in seconds.
+    #[inline]
+    pub fn lifetime(mut self, secs: i64) -> Self {
+        self.exp = self.iat + secs;
+        self
+    }
+
+    /// Returns the DID of the caller, without any fragment.
+    pub fn issuer_did(&self) -> Result<Did, ServiceAuthError> {
+        let did = self.iss.split_once('#').map_or(&*self.iss, |(did, _)| did);
+        Did::from_str(did).map_err(|_| ServiceAuthError::Malformed)
+    }
+
+    /// Signs these claims with `signer`, which should hold the issuer's signing key on `curve`,
+    /// producing a token.
+    pub fn sign<S>(&self, curve: Curve, signer: &S) -> Result<String, S::Error>
+    where
+        S: Signer + ?Sized,
+    {
+        let header = json!({
+            "typ": "JWT",
+            "alg": algorithm(curve),
+        });
+        let claims = serde_json::to_vec(self).expect("claims serialization should never fail");
+
+        let signing_input = format!(
+            "{}.{}",
+            BASE64URL_NOPAD.encode(header.to_string().as_bytes()),
+            BASE64URL_NOPAD.encode(&claims),
+        );
+        let sig = signer.sign(signing_input.as_bytes())?;
+
+        Ok(format!("{signing_input}.{}", BASE64URL_NOPAD.encode(&sig)))
+    }
+}
+
+/// Verifies service auth tokens sent to a service.
+#[derive(Clone, Debug)]
+pub struct ServiceAuthVerifier<R> {
+    resolver: R,
+    audience: String,
+    max_lifetime: i64,
+    refresh_interval: Duration,
+    /// When each issuer's DID document was last refreshed.
+    refreshed: Arc<Mutex<LruCache<Did, Instant>>>,
+}
+
+impl<R> ServiceAuthVerifier<R>
+where
+    R: DidResolver,
+{
+    /// Creates a verifier for tokens whose `aud` is `audience`, resolving issuers with
+    /// `resolver`.
+    pub fn new(resolver: R, audience: impl Into<String>) -> Self {
+        ServiceAuthVerifier {
+            resolver,
+            audience: audience.into(),
+            max_lifetime: DEFAULT_MAX_LIFETIME,
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            refreshed: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(REFRESH_CAPACITY).unwrap(),
+            ))),
+        }
+    }
+
+    /// Sets the maximum lifetime of accepted tokens, from `iat` to `exp`, in seconds.
+    ///
+    /// The default is [`DEFAULT_MAX_LIFETIME`].
+    #[inline]
+    pub fn max_lifetime(mut self, secs: i64) -> Self {
+        self.max_lifetime = secs;
+        self
+    }
+
+    /// Sets the minimum time between refreshes of an issuer's DID document.
+    ///
+    /// Tokens which fail to verify cause the issuer's DID document to be refreshed, so this limits
+    /// the requests made on behalf of forged tokens. The default is [`DEFAULT_REFRESH_INTERVAL`].
+    #[inline]
+    pub fn refresh_interval(mut self, interval: Duration) -> Self {
+        self.refresh_interval = interval;
+        self
+    }
+
+    /// Verifies a token sent with a request for the XRPC method `Q`.
+    ///
+    /// The token must be restricted to `Q`.
+    pub async fn verify<Q>(&self, token: &str) -> Result<ServiceAuthClaims, ServiceAuthError>
+    where
+        Q: Request,
+    {
+        self.verify_method(token, Some(Q::nsid())).await
+    }
+
+    /// Verifies a token, checking that its `lxm` is `lxm`.
+    ///
+    /// If `lxm` is `None`, the token may be unrestricted or restricted to any method.
+    ///
+    /// If the signature cannot be verified with the issuer's signing key, the issuer's DID
+    /// document is resolved again with [`DidResolver::refresh`] in case the key was rotated,
+    /// unless it was refreshed within the [refresh interval](Self::refresh_interval).
+    pub async fn verify_method(
+        &self,
+        token: &str,
+        lxm: Option<&str>,
+    ) -> Result<ServiceAuthClaims, ServiceAuthError> {
+        let mut parts = token.split('.');
+        let (Some(header), Some(claims), Some(sig), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ServiceAuthError::Malformed);
+        };
+
+        let header: Header = decode_json(header)?;
+        let claims: ServiceAuthClaims = decode_json(claims)?;
+        let sig = BASE64URL_NOPAD
+            .decode(sig.as_bytes())
+            .map_err(|_| ServiceAuthError::Malformed)?;
+
+        if claims.aud != self.audience {
+            return Err(ServiceAuthError::AudienceMismatch(claims.aud));
+        }
+
+        if let Some(lxm) = lxm {
+            if claims.lxm.as_deref() != Some(lxm) {
+                return Err(ServiceAuthError::MethodMismatch(claims.lxm));
+            }
+        }
+
+        let now = jiff::Timestamp::now().as_second();
+        if claims.exp <= now {
+            return Err(ServiceAuthError::Expired);
+        }
+        if claims.iat > now + CLOCK_SKEW {
+            return Err(ServiceAuthError::NotYetValid);
+        }
+        if claims.exp - claims.iat > self.max_lifetime {
+            return Err(ServiceAuthError::LifetimeTooLong);
+        }
+
+        let did = claims.issuer_did()?;
+        let signing_input = &token[..token.rfind('.').expect("token should have three parts")];
+        let verify = |doc: &DidDoc| {
+            verify_signature(doc, &did, &claims.iss, &header.alg, signing_input, &sig)
+        };
+
+        let doc = self.resolver.resolve(&did).await?;
+        match verify(&doc) {
+            Err(
+                ServiceAuthError::NoSigningKey
+                | ServiceAuthError::Signature(_)
+                | ServiceAuthError::UnsupportedAlgorithm(_),
+            ) if self.start_refresh(&did) => verify(&self.resolver.refresh(&did).await?)?,
+            result => result?,
+        }
+
+        Ok(claims)
+    }
+
+    /// Returns whether the DID document of `did` may be refreshed, recording the refresh if so.
+    fn start_refresh(&self, did: &Did) -> bool {
+        let mut refreshed = self
+            .refreshed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(last) = refreshed.get(did) {
+            if last.elapsed() < self.refresh_interval {
+                return false;
+            }
+        }
+
+        refreshed.put(did.clone(), Instant::now());
+        true
+    }
+}
+
+/// Verifies the signature of a token issued by `iss` with the signing key in `doc`.
+fn verify_signature(
+    doc: &DidDoc,
+    did: &Did,
+    iss: &str,
+    alg: &str,
+    signing_input: &str,
+    sig: &[u8],
+) -> Result<(), ServiceAuthError> {
+    // Labelers sign with a separate key.
+    let fragment = match iss.split_once('#') {
+        Some((_, "atproto_labeler")) => "atproto_label",
+        _ => "atproto",
+    };
+    let key = doc
+        .verification_method
+        .iter()
+        .flatten()
+        .find(|method| method.id.matches(did, fragment))
+        .ok_or(ServiceAuthError::NoSigningKey)?
+        .public_key()
+        .map_err(ServiceAuthError::Signature)?;
+
+    if alg != algorithm(key.curve()) {
+        return Err(ServiceAuthError::UnsupportedAlgorithm(alg.into()));
+    }
+
+    key.verify(signing_input.as_bytes(), sig)
+        .map_err(ServiceAuthError::Signature)
+}
+
+#[derive(Deserialize)]
+struct Header {
+    alg: String,
+}
+
+fn decode_json<T>(part: &str) -> Result<T, ServiceAuthError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let bytes = BASE64URL_NOPAD
+        .decode(part.as_bytes())
+        .map_err(|_| ServiceAuthError::Malformed)?;
+
+    serde_json::from_slice(&bytes).map_err(|_| ServiceAuthError::Malformed)
+}
+
+fn algorithm(curve: Curve) -> &'static str {
+    match curve {
+        Curve::P256 => "ES256",
+        Curve::K256 => "ES256K",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use atmo_core::crypto::Keypair;
+
+    use super::*;
+    use crate::DidError;
+
+    const ISS: &str = "did:plc:ewvi7nxzyoun6zhxrhs64oiz";
+    const AUD: &str = "did:web:feed.example.com";
+    const LXM: &str = "app.bsky.feed.getFeedSkeleton";
+
+    /// A resolver which knows a single DID document.
+    struct Static(DidDoc);
+
+    impl DidResolver for Static {
+        async fn resolve(&self, did: &Did) -> Result<DidDoc, DidError> {
+            if *did == self.0.id {
+                Ok(self.0.clone())
+            } else {
+                Err(DidError::NotFound)
+            }
+        }
+    }
+
+    fn key(curve: Curve, seed: u8) -> Keypair {
+        Keypair::from_bytes(curve, &[seed; 32]).unwrap()
+    }
+
+    /// A resolver whose cached document is out of date, which counts its refreshes.
+    struct Rotated {
+        cached: DidDoc,
+        current: DidDoc,
+        refreshes: AtomicUsize,
+    }
+
+    impl DidResolver for Rotated {
+        async fn resolve(&self, _: &Did) -> Result<DidDoc, DidError> {
+            Ok(self.cached.clone())
+        }
+
+        async fn refresh(&self, _: &Did) -> Result<DidDoc, DidError> {
+            self.refreshes.fetch_add(1, Ordering::SeqCst);
+            Ok(self.current.clone())
+        }
+    }
+
+    fn verifier(key: &Keypair) -> ServiceAuthVerifier<Static> {
+        ServiceAuthVerifier::new(Static(doc(key)), AUD)
+    }
+
+    fn doc(key: &Keypair) -> DidDoc {
+        serde_json::from_value(json!({
+            "id": ISS,
+            "verificationMethod": [
+                {
+                    "id": format!("{ISS}#atproto"),
+                    "type": "Multikey",
+                    "controller": ISS,
+                    "publicKeyMultibase": key.public_key().to_multibase(),
+                },
+                {
+                    "id": "#atproto_label",
+                    "type": "Multikey",
+                    "controller": ISS,
+                    "publicKeyMultibase": self::key(Curve::K256, 9).public_key().to_multibase(),
+                },
+            ],
+        }))
+        .unwrap()
+    }
+
+    fn claims() -> ServiceAuthClaims {
+        ServiceAuthClaims {
+            lxm: Some(LXM.into()),
+            ..ServiceAuthClaims::new(ISS, AUD)
+        }
+    }
+
+    #[tokio::test]
+    async fn round_trip() {
+        for curve in [Curve::P256, Curve::K256] {
+            let key = key(curve, 1);
+            let verifier = verifier(&key);
+            let token = claims().sign(curve, &key).unwrap();
+
+            assert_eq!(
+                verifier.verify_method(&token, Some(LXM)).await.unwrap(),
+                claims()
+            );
+            assert!(verifier.verify_method(&token, None).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn labeler_key() {
+        let verifier = verifier(&key(Curve::K256, 1));
+        let claims = ServiceAuthClaims::new(format!("{ISS}#atproto_labeler"), AUD);
+
+        let token = claims.sign(Curve::K256, &key(Curve::K256, 9)).unwrap();
+        assert!(verifier.verify_method(&token, None).await.is_ok());
+
+        let token = claims.sign(Curve::K256, &key(Curve::K256, 1)).unwrap();
+        assert!(matches!(
+            verifier.verify_method(&token, None).await,
+            Err(ServiceAuthError::Signature(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejected() {
+        let key = key(Curve::K256, 1);
+        let verifier = verifier(&key);
+        let verify = |claims: ServiceAuthClaims| {
+            let token = claims.sign(Curve::K256, &key).unwrap();
+            let verifier = &verifier;
+            async move { verifier.verify_method(&token, Some(LXM)).await }
+        };
+
+        let other_aud = ServiceAuthClaims {
+            aud: "did:web:other.example.com".into(),
+            ..claims()
+        };
+        assert!(matches!(
+            verify(other_aud).await,
+            Err(ServiceAuthError::AudienceMismatch(aud)) if aud == "did:web:other.example.com"
+        ));
+
+        let other_lxm = ServiceAuthClaims {
+            lxm: Some("com.atproto.repo.createRecord".into()),
+            ..claims()
+        };
+        assert!(matches!(
+            verify(other_lxm).await,
+            Err(ServiceAuthError::MethodMismatch(Some(_)))
+        ));
+        assert!(matches!(
+            verify(ServiceAuthClaims::new(ISS, AUD)).await,
+            Err(ServiceAuthError::MethodMismatch(None))
+        ));
+
+        assert!(matches!(
+            verify(claims().lifetime(0)).await,
+            Err(ServiceAuthError::Expired)
+        ));
+
+        let future = ServiceAuthClaims {
+            iat: claims().iat + 120,
+            ..claims()
+        };
+        assert!(matches!(
+            verify(future).await,
+            Err(ServiceAuthError::NotYetValid)
+        ));
+
+        assert!(matches!(
+            verify(claims().lifetime(DEFAULT_MAX_LIFETIME + 1)).await,
+            Err(ServiceAuthError::LifetimeTooLong)
+        ));
+        assert!(verify(claims().lifetime(DEFAULT_MAX_LIFETIME))
+            .await
+            .is_ok());
+
+        let unknown = ServiceAuthClaims {
+            iss: "did:plc:z72i7hdynmk6r22z27h6tvur".into(),
+            ..claims()
+        };
+        assert!(matches!(
+            verify(unknown).await,
+            Err(ServiceAuthError::Did(DidError::NotFound))
+        ));
+
+        let forged = claims()
+            .sign(Curve::K256, &self::key(Curve::K256, 2))
+            .unwrap();
+        assert!(matches!(
+            verifier.verify_method(&forged, Some(LXM)).await,
+            Err(ServiceAuthError::Signature(_))
+        ));
+
+        let wrong_alg = claims()
+            .sign(Curve::P256, &self::key(Curve::P256, 1))
+            .unwrap();
+        assert!(matches!(
+            verifier.verify_method(&wrong_alg, Some(LXM)).await,
+            Err(ServiceAuthError::UnsupportedAlgorithm(alg)) if alg == "ES256"
+        ));
+
+        assert!(matches!(
+            verifier.verify_method("not.a.jwt.at-all", Some(LXM)).await,
+            Err(ServiceAuthError::Malformed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rotated_key() {
+        let old = key(Curve::K256, 1);
+        let new = key(Curve::K256, 2);
+        let verifier = ServiceAuthVerifier::new(
+            Rotated {
+                cached: doc(&old),
+                current: doc(&new),
+                refreshes: AtomicUsize::new(0),
+            },
+            AUD,
+        );
+
+        let token = claims().sign(Curve::K256, &old).unwrap();
+        assert!(verifier.verify_method(&token, Some(LXM)).await.is_ok());
+        assert_eq!(verifier.resolver.refreshes.load(Ordering::SeqCst), 0);
+
+        let token = claims().sign(Curve::K256, &new).unwrap();
+        assert!(verifier.verify_method(&token, Some(LXM)).await.is_ok());
+        assert_eq!(verifier.resolver.refreshes.load(Ordering::SeqCst), 1);
+
+        let forged = claims().sign(Curve::K256, &key(Curve::K256, 3)).unwrap();
+        assert!(matches!(
+            verifier.verify_method(&forged, Some(LXM)).await,
+            Err(ServiceAuthError::Signature(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn throttled_refresh() {
+        let key = key(Curve::K256, 1);
+        let verifier = ServiceAuthVerifier::new(
+            Rotated {
+                cached: doc(&key),
+                current: doc(&key),
+                refreshes: AtomicUsize::new(0),
+            },
+            AUD,
+        );
+        let refreshes = || verifier.resolver.refreshes.load(Ordering::SeqCst);
+
+        // Forged tokens only refresh the issuer's DID document once per interval.
+        let forged = claims()
+            .sign(Curve::K256, &self::key(Curve::K256, 2))
+            .unwrap();
+        for _ in 0..3 {
+            assert!(matches!(
+                verifier.verify_method(&forged, Some(LXM)).await,
+                Err(ServiceAuthError::Signature(_))
+            ));
+        }
+        assert_eq!(refreshes(), 1);
+
+        tokio::time::advance(DEFAULT_REFRESH_INTERVAL).await;
+        assert!(verifier.verify_method(&forged, Some(LXM)).await.is_err());
+        assert_eq!(refreshes(), 2);
+
+        // Valid tokens are still accepted.
+        let token = claims().sign(Curve::K256, &key).unwrap();
+        assert!(verifier.verify_method(&token, Some(LXM)).await.is_ok());
+        assert_eq!(refreshes(), 2);
+    }
+}
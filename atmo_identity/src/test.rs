@@ -0,0 +1,70 @@
+use std::net::SocketAddr;
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// A canned HTTP response, served for requests to `path`.
+pub struct Route {
+    pub path: String,
+    pub status: u16,
+    pub body: String,
+}
+
+impl Route {
+    pub fn new(path: impl Into<String>, status: u16, body: impl Into<String>) -> Self {
+        Route {
+            path: path.into(),
+            status,
+            body: body.into(),
+        }
+    }
+}
+
+/// Starts a minimal HTTP server on localhost which serves `routes`, and 404s for anything else.
+pub async fn serve(routes: Vec<Route>) -> SocketAddr {
+    serve_with(|_| routes).await
+}
+
+/// Like [`serve`], but the routes may depend on the address of the server.
+pub async fn serve_with<F>(routes: F) -> SocketAddr
+where
+    F: FnOnce(SocketAddr) -> Vec<Route>,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let routes = routes(addr);
+
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let mut buf = Vec::new();
+            while !buf.ends_with(b"\r\n\r\n") {
+                let mut byte = [0];
+                if stream.read(&mut byte).await.unwrap() == 0 {
+                    break;
+                }
+                buf.push(byte[0]);
+            }
+
+            let request = String::from_utf8_lossy(&buf);
+            let path = request.split(' ').nth(1).unwrap_or_default();
+
+            let (status, body) = routes
+                .iter()
+                .find(|route| route.path == path)
+                .map_or((404, ""), |route| (route.status, route.body.as_str()));
+
+            let response = format!(
+                "HTTP/1.1 {status} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    addr
+}
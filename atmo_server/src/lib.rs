@@ -0,0 +1,530 @@
+//! An XRPC server.
+//!
+//! A [`Router`] dispatches requests for `/xrpc/{nsid}` to handlers registered for each XRPC
+//! method. Handlers receive the decoded parameters and input of the request, and return either
+//! the method's output or an [`xrpc::Error`], which is sent with the status of its code. To set
+//! response headers, such as the media type of a blob, handlers may return an
+//! [`xrpc::Response`] wrapping the output instead. The
+//! router is a [tower] [`Service`], so it can be served by hyper or mounted in an axum
+//! application.
+//!
+//! ```
+//! use atmo_api::com::atproto::identity::{resolve_handle, ResolveHandle};
+//! use atmo_core::xrpc::{Error, ErrorCode};
+//! use atmo_server::{Router, XrpcRequest};
+//!
+//! let router = Router::new().route(ResolveHandle, |req: XrpcRequest<ResolveHandle>| async move {
+//!     match req.params.handle.as_str() {
+//!         "alice.test" => Ok(resolve_handle::Output {
+//!             did: "did:plc:ewvi7nxzyoun6zhxrhs64oiz".parse().unwrap(),
+//!         }),
+//!         _ => Err(Error::new(ErrorCode::Method("HandleNotFound".into()))),
+//!     }
+//! });
+//! ```
+//!
+//! [tower]: https://docs.rs/tower
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::{self, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use atmo_core::xrpc::{self, ErrorCode, Request};
+use bytes::Bytes;
+use http::{header, request::Parts, Method, StatusCode};
+use http_body::Body;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use serde::Serialize;
+use tower_service::Service;
+
+/// The default maximum size of a request body, in bytes.
+pub const DEFAULT_BODY_LIMIT: usize = 1024 * 1024;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+type Handler = dyn Fn(Parts, Bytes) -> BoxFuture<http::Response<Full<Bytes>>> + Send + Sync;
+
+/// A decoded XRPC request, passed to a handler.
+pub struct XrpcRequest<R>
+where
+    R: Request,
+{
+    /// The query parameters of the request.
+    pub params: R::Params,
+    /// The body of the request.
+    pub input: R::Input,
+    /// The HTTP request, for access to headers such as `Authorization`.
+    pub parts: Parts,
+}
+
+#[derive(Clone)]
+struct Route {
+    method: Method,
+    input_content_type: Option<&'static str>,
+    handler: Arc<Handler>,
+}
+
+/// A router which dispatches XRPC requests to handlers.
+#[derive(Clone)]
+pub struct Router {
+    routes: Arc<HashMap<&'static str, Route>>,
+    body_limit: usize,
+}
+
+impl Router {
+    /// Creates a router with no routes.
+    pub fn new() -> Self {
+        Router {
+            routes: Arc::default(),
+            body_limit: DEFAULT_BODY_LIMIT,
+        }
+    }
+
+    /// Registers `handler` for requests to the XRPC method `R`.
+    ///
+    /// The handler returns either the method's output or an [`xrpc::Response`] wrapping it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a handler is already registered for `R`.
+    pub fn route<R, F, Fut, O>(mut self, req: R, handler: F) -> Self
+    where
+        R: Request + 'static,
+        R::RpcError: Serialize,
+        F: Fn(XrpcRequest<R>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<O, xrpc::Error<R::RpcError>>> + Send + 'static,
+        O: Into<xrpc::Response<R::Output>>,
+    {
+        let _ = req;
+
+        let handler = move |parts: Parts, body: Bytes| -> BoxFuture<_> {
+            let params = match R::deserialize_params(parts.uri.query().unwrap_or_default()) {
+                Ok(params) => params,
+                Err(e) => return Box::pin(future::ready(invalid_request(e.to_string()))),
+            };
+
+            let input = match R::deserialize_input(&body) {
+                Ok(input) => input,
+                Err(e) => return Box::pin(future::ready(invalid_request(e.to_string()))),
+            };
+
+            let fut = handler(XrpcRequest {
+                params,
+                input,
+                parts,
+            });
+
+            Box::pin(async move {
+                match fut.await {
+                    Ok(output) => encode_output::<R>(output.into()),
+                    Err(e) => error_response(&e),
+                }
+            })
+        };
+
+        let route = Route {
+            method: R::method(),
+            input_content_type: R::input_content_type(),
+            handler: Arc::new(handler),
+        };
+
+        let prev = Arc::make_mut(&mut self.routes).insert(R::nsid(), route);
+        assert!(
+            prev.is_none(),
+            "a handler is already registered for {}",
+            R::nsid()
+        );
+        self
+    }
+
+    /// Sets the maximum size of a request body, in bytes.
+    ///
+    /// Larger requests are rejected with [`ErrorCode::PayloadTooLarge`]. The default is
+    /// [`DEFAULT_BODY_LIMIT`].
+    #[inline]
+    pub fn body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+
+    async fn dispatch<B>(self, req: http::Request<B>) -> http::Response<Full<Bytes>>
+    where
+        B: Body,
+        B::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let (parts, body) = req.into_parts();
+
+        let Some(nsid) = parts.uri.path().strip_prefix("/xrpc/") else {
+            return http::Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Full::default())
+                .expect("response should be valid");
+        };
+
+        let Some(route) = self.routes.get(nsid) else {
+            return generic(ErrorCode::MethodNotImplemented, "method not implemented");
+        };
+
+        if parts.method != route.method {
+            return invalid_request(format!(
+                "incorrect HTTP method ({}), expected {}",
+                parts.method, route.method
+            ));
+        }
+
+        let content_type = parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or_default().trim());
+
+        let body = match Limited::new(body, self.body_limit).collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(e) if e.is::<LengthLimitError>() => {
+                return generic(ErrorCode::PayloadTooLarge, "request body is too large")
+            }
+            Err(_) => return invalid_request("failed to read request body"),
+        };
+
+        match (route.input_content_type, content_type) {
+            (None, _) if !body.is_empty() => {
+                return invalid_request("request body was provided when none was expected")
+            }
+            (None, _) | (Some("*/*"), _) => {}
+            (Some(expected), Some(actual)) if xrpc::encoding_matches(expected, actual) => {}
+            (Some(expected), _) => {
+                return invalid_request(format!("wrong request encoding, expected {expected}"))
+            }
+        }
+
+        (route.handler)(parts, body).await
+    }
+}
+
+impl Default for Router {
+    #[inline]
+    fn default() -> Self {
+        Router::new()
+    }
+}
+
+impl<B> Service<http::Request<B>> for Router
+where
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    type Response = http::Response<Full<Bytes>>;
+    type Error = Infallible;
+    type Future = BoxFuture<Result<Self::Response, Infallible>>;
+
+    #[inline]
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let router = self.clone();
+        Box::pin(async move { Ok(router.dispatch(req).await) })
+    }
+}
+
+fn encode_output<R>(response: xrpc::Response<R::Output>) -> http::Response<Full<Bytes>>
+where
+    R: Request,
+{
+    match response.encode::<R>() {
+        Ok(response) => response.map(Full::new),
+        Err(_) => generic(ErrorCode::InternalServerError, "failed to serialize output"),
+    }
+}
+
+fn response(status: StatusCode, content_type: &str, body: Bytes) -> http::Response<Full<Bytes>> {
+    http::Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Full::new(body))
+        .expect("response should be valid")
+}
+
+fn error_response<E>(error: &xrpc::Error<E>) -> http::Response<Full<Bytes>>
+where
+    E: Serialize,
+{
+    match serde_json::to_vec(error) {
+        Ok(body) => response(error.status(), "application/json", body.into()),
+        Err(_) => generic(ErrorCode::InternalServerError, "failed to serialize error"),
+    }
+}
+
+fn generic(code: ErrorCode<()>, message: impl Into<String>) -> http::Response<Full<Bytes>> {
+    error_response(&xrpc::Error::new(code).with_message(message))
+}
+
+fn invalid_request(message: impl Into<String>) -> http::Response<Full<Bytes>> {
+    generic(ErrorCode::InvalidRequest, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use atmo_api::com::atproto::{
+        identity::{resolve_handle, ResolveHandle},
+        server::{deactivate_account, DeactivateAccount},
+        sync::{GetBlob, GetRepo},
+    };
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn router(deactivated: Arc<Mutex<Option<deactivate_account::Input>>>) -> Router {
+        Router::new()
+            .route(
+                ResolveHandle,
+                |req: XrpcRequest<ResolveHandle>| async move {
+                    match req.params.handle.as_str() {
+                        "alice.test" => Ok(resolve_handle::Output {
+                            did: "did:plc:ewvi7nxzyoun6zhxrhs64oiz".parse().unwrap(),
+                        }),
+                        "bob.test" => Err(ErrorCode::UpstreamTimeout.into()),
+                        _ => Err(xrpc::Error::new(ErrorCode::Method("HandleNotFound".into()))
+                            .with_message("handle not found")),
+                    }
+                },
+            )
+            .route(
+                DeactivateAccount,
+                move |req: XrpcRequest<DeactivateAccount>| {
+                    let deactivated = deactivated.clone();
+                    async move {
+                        if req.parts.headers.get(header::AUTHORIZATION).is_none() {
+                            return Err(ErrorCode::AuthRequired.into());
+                        }
+
+                        *deactivated.lock().unwrap() = Some(req.input);
+                        Ok(())
+                    }
+                },
+            )
+            .body_limit(64)
+    }
+
+    async fn call(
+        router: &mut Router,
+        req: http::Request<Full<Bytes>>,
+    ) -> (StatusCode, Option<Value>) {
+        let resp = router.call(req).await.unwrap();
+        let status = resp.status();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+
+        let json = (!body.is_empty()).then(|| serde_json::from_slice(&body).unwrap());
+        (status, json)
+    }
+
+    fn get(uri: &str) -> http::Request<Full<Bytes>> {
+        http::Request::get(uri).body(Full::default()).unwrap()
+    }
+
+    fn post(uri: &str, content_type: &str, body: &str) -> http::Request<Full<Bytes>> {
+        http::Request::post(uri)
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::AUTHORIZATION, "Bearer token")
+            .body(Full::new(Bytes::copy_from_slice(body.as_bytes())))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn query() {
+        let mut router = router(Arc::default());
+
+        let resp = call(
+            &mut router,
+            get("/xrpc/com.atproto.identity.resolveHandle?handle=alice.test"),
+        )
+        .await;
+        assert_eq!(
+            resp,
+            (
+                StatusCode::OK,
+                Some(json!({"did": "did:plc:ewvi7nxzyoun6zhxrhs64oiz"}))
+            )
+        );
+
+        let resp = call(
+            &mut router,
+            get("/xrpc/com.atproto.identity.resolveHandle?handle=carol.test"),
+        )
+        .await;
+        assert_eq!(
+            resp,
+            (
+                StatusCode::BAD_REQUEST,
+                Some(json!({"error": "HandleNotFound", "message": "handle not found"}))
+            )
+        );
+
+        let resp = call(
+            &mut router,
+            get("/xrpc/com.atproto.identity.resolveHandle?handle=bob.test"),
+        )
+        .await;
+        assert_eq!(
+            resp,
+            (
+                StatusCode::GATEWAY_TIMEOUT,
+                Some(json!({"error": "UpstreamTimeout"}))
+            )
+        );
+
+        let (status, body) = call(
+            &mut router,
+            get("/xrpc/com.atproto.identity.resolveHandle?handle=-invalid-"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.unwrap()["error"], "InvalidRequest");
+    }
+
+    #[tokio::test]
+    async fn procedure() {
+        let deactivated = Arc::default();
+        let mut router = router(Arc::clone(&deactivated));
+
+        let resp = call(
+            &mut router,
+            post(
+                "/xrpc/com.atproto.server.deactivateAccount",
+                "application/json; charset=utf-8",
+                r#"{"deleteAfter":"2024-01-01T00:00:00Z"}"#,
+            ),
+        )
+        .await;
+        assert_eq!(resp, (StatusCode::OK, None));
+        assert_eq!(
+            deactivated.lock().unwrap().take(),
+            Some(deactivate_account::Input {
+                delete_after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            })
+        );
+
+        let mut req = post(
+            "/xrpc/com.atproto.server.deactivateAccount",
+            "application/json",
+            "{}",
+        );
+        req.headers_mut().remove(header::AUTHORIZATION);
+        let (status, body) = call(&mut router, req).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body.unwrap()["error"], "AuthenticationRequired");
+        assert!(deactivated.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rejected() {
+        let mut router = router(Arc::default());
+        let deactivate = "/xrpc/com.atproto.server.deactivateAccount";
+
+        let cases = [
+            (get("/"), StatusCode::NOT_FOUND, None),
+            (
+                get("/xrpc/com.example.unknown"),
+                StatusCode::NOT_IMPLEMENTED,
+                Some("MethodNotImplemented"),
+            ),
+            (
+                get(deactivate),
+                StatusCode::BAD_REQUEST,
+                Some("InvalidRequest"),
+            ),
+            (
+                post(deactivate, "text/plain", "{}"),
+                StatusCode::BAD_REQUEST,
+                Some("InvalidRequest"),
+            ),
+            (
+                post(deactivate, "application/json", "{"),
+                StatusCode::BAD_REQUEST,
+                Some("InvalidRequest"),
+            ),
+            (
+                post(deactivate, "application/json", &" ".repeat(65)),
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Some("PayloadTooLarge"),
+            ),
+        ];
+
+        for (req, status, error) in cases {
+            let uri = req.uri().clone();
+            let resp = call(&mut router, req).await;
+            assert_eq!(resp.0, status, "{uri}");
+            assert_eq!(
+                resp.1.map(|body| body["error"].clone()),
+                error.map(Value::from)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn binary_output() {
+        let mut router = Router::new().route(GetRepo, |_: XrpcRequest<GetRepo>| async {
+            Ok(Bytes::from_static(b"car"))
+        });
+
+        let resp = router
+            .call(get(
+                "/xrpc/com.atproto.sync.getRepo?did=did:plc:ewvi7nxzyoun6zhxrhs64oiz",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            "application/vnd.ipld.car"
+        );
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "car");
+    }
+
+    #[tokio::test]
+    async fn wildcard_output() {
+        let mut router = Router::new().route(GetBlob, |req: XrpcRequest<GetBlob>| async move {
+            let output = xrpc::Response::new(Bytes::from_static(b"\x89PNG"));
+            match req.params.did.as_str() {
+                "did:plc:ewvi7nxzyoun6zhxrhs64oiz" => {
+                    Ok(output.content_type(http::HeaderValue::from_static("image/png")))
+                }
+                _ => Ok(output),
+            }
+        });
+        let uri = |did: &str| {
+            format!(
+                "/xrpc/com.atproto.sync.getBlob?did={did}\
+                 &cid=bafkreibme22gw2h7y2h7tg2fhqotaqjucnbc24deqo72b6mkl2egezxhvy"
+            )
+        };
+
+        let resp = router
+            .call(get(&uri("did:plc:ewvi7nxzyoun6zhxrhs64oiz")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], "image/png");
+
+        // Without a content type from the handler, the actual type is unknown.
+        let resp = router
+            .call(get(&uri("did:plc:z72i7hdynmk6r22z27h6tvur")))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[header::CONTENT_TYPE],
+            "application/octet-stream"
+        );
+    }
+}
@@ -1,21 +1,15 @@
-use atmo::{
-    api::{
-        app::bsky::actor::GetPreferences,
-        com::atproto::server::{create_session, CreateSession, DeleteSession},
-        XrpcClient,
-    },
-    core::did::DidDoc,
-};
-use serde::{de::IntoDeserializer, Deserialize};
+use atmo::api::{app::bsky::actor::GetPreferences, Agent};
 use url::Url;
 
 #[tokio::main]
 async fn main() {
-    let cl = XrpcClient::new();
-
     let url = Url::parse("https://bsky.social").unwrap();
 
-    let identifier = dialoguer::Input::new()
+    let agent = Agent::builder(url)
+        .on_session(|session| println!("session updated for {}", session.did.as_str()))
+        .build();
+
+    let identifier: String = dialoguer::Input::new()
         .with_prompt("Bluesky username")
         .interact_text()
         .unwrap();
@@ -25,30 +19,14 @@ async fn main() {
         .interact()
         .unwrap();
 
-    let session_resp = cl
-        .request(&url, CreateSession)
-        .input(&create_session::Input {
-            identifier,
-            password,
-            auth_factor_token: None,
-        })
-        .unwrap()
-        .send()
-        .await
-        .unwrap();
-
-    let session = session_resp.result().unwrap();
+    let session = agent.login(identifier, password).await.unwrap();
+    println!("logged in as {}", session.handle.as_str());
 
-    let did_doc_unk = session.did_doc.as_ref().unwrap();
-    let did_doc = DidDoc::deserialize(did_doc_unk.into_deserializer()).unwrap();
-    println!("DID doc: {did_doc:?}");
+    if let Some(pds) = &session.pds {
+        println!("using PDS at {pds}");
+    }
 
-    let prefs_resp = cl
-        .request(&url, GetPreferences)
-        .bearer_auth(&session.access_jwt)
-        .send()
-        .await
-        .unwrap();
+    let prefs_resp = agent.request(GetPreferences).send().await.unwrap();
 
     let prefs = prefs_resp.result().unwrap();
 
@@ -56,14 +34,7 @@ async fn main() {
         println!("pref: {pref:#?}");
     }
 
-    let del_resp = cl
-        .request(&url, DeleteSession)
-        .bearer_auth(&session.refresh_jwt)
-        .send()
-        .await
-        .unwrap();
-
-    if let Err(e) = del_resp.result() {
-        eprintln!("{e:?}");
+    if let Err(e) = agent.logout().await {
+        eprintln!("{e}");
     }
 }